@@ -0,0 +1,132 @@
+//! Benchmarks of searching and patching large application binaries.
+//!
+//! Run with `cargo bench`. Every benchmark uses binaries from [synthetic_binary], with the fuse wire and patch
+//! targets near the end so that almost the whole binary is searched.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use electron_hardener::{
+    bench::synthetic_binary,
+    fuses::FuseWire,
+    patcher::{ElectronOption, PatchTarget, Patchable},
+    ElectronApp, HardeningPolicy, Preset,
+};
+
+/// The binary sizes each benchmark is run with.
+const SIZES: &[usize] = &[1 << 20, 100 << 20];
+
+fn locate_fuse_wire(c: &mut Criterion) {
+    let mut group = c.benchmark_group("locate_fuse_wire");
+    group.sample_size(10);
+
+    for &size in SIZES {
+        let binary = synthetic_binary(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &binary, |b, binary| {
+            b.iter(|| FuseWire::locate_all(binary, 0).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn scan_option(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan_option");
+    group.sample_size(10);
+
+    for &size in SIZES {
+        let mut binary = synthetic_binary(size);
+        let app = ElectronApp::from_bytes(&mut binary).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| app.scan(ElectronOption::JsFlags).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn apply_policy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_policy");
+    group.sample_size(10);
+    let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+
+    for &size in SIZES {
+        let binary = synthetic_binary(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter_batched_ref(
+                || binary.clone(),
+                |binary| {
+                    ElectronApp::from_bytes(binary)
+                        .unwrap()
+                        .apply_policy(&policy)
+                        .unwrap()
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+/// The Electron options and Node.JS flags, which are patched one at a time and all at once.
+const OPTIONS: &[PatchTarget] = &[
+    PatchTarget::ElectronOption(ElectronOption::JsFlags),
+    PatchTarget::ElectronOption(ElectronOption::RemoteDebuggingPipe),
+    PatchTarget::ElectronOption(ElectronOption::RemoteDebuggingPort),
+    PatchTarget::ElectronOption(ElectronOption::WaitForDebuggerChildren),
+    PatchTarget::NodeInspect,
+    PatchTarget::NodeInspectBrk,
+    PatchTarget::NodeInspectPort,
+    PatchTarget::NodeDebug,
+    PatchTarget::NodeDebugBrk,
+    PatchTarget::NodeDebugPort,
+    PatchTarget::NodeInspectBrkNode,
+    PatchTarget::NodeInspectPublishUid,
+];
+
+fn patch_options(c: &mut Criterion) {
+    let mut group = c.benchmark_group("patch_options");
+    group.sample_size(10);
+    let options: Vec<&dyn Patchable> = OPTIONS.iter().map(|opt| opt as &dyn Patchable).collect();
+
+    for &size in SIZES {
+        let binary = synthetic_binary(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        // The applications are created before timing starts, so only searching and patching is measured.
+        group.bench_function(BenchmarkId::new("one_at_a_time", size), |b| {
+            b.iter_batched_ref(
+                || ElectronApp::from_vec(binary.clone()).unwrap(),
+                |app| {
+                    for option in OPTIONS {
+                        app.patch_option(*option).unwrap();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(BenchmarkId::new("all_at_once", size), |b| {
+            b.iter_batched_ref(
+                || ElectronApp::from_vec(binary.clone()).unwrap(),
+                |app| {
+                    for result in app.patch_all(&options) {
+                        result.unwrap();
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    locate_fuse_wire,
+    scan_option,
+    apply_policy,
+    patch_options
+);
+criterion_main!(benches);
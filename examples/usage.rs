@@ -1,6 +1,6 @@
 //! A small and basic sample of how to use the library's functionality, without needing an Electron app present.
 
-use electron_hardener::{patcher::ElectronOption, BinaryError, ElectronApp, Fuse, PatcherError};
+use electron_hardener::{fuses::FuseStatus, patcher::ElectronOption, ElectronApp, Fuse};
 
 fn main() {
     let mut application_bytes = {
@@ -9,28 +9,32 @@ fn main() {
         bytes
     };
 
-    let mut app = ElectronApp::from_bytes(&mut application_bytes).unwrap();
+    // Most applications only need this. `harden_file` does the same for a binary on disk.
+    println!("Hardening the application with the recommended policy");
+    let report = electron_hardener::harden(&mut application_bytes).unwrap();
 
-    let fuse = Fuse::RunAsNode;
-
-    let original_status = app.get_fuse_status(fuse).unwrap();
-    println!("The unmodified fuse status is `{:?}`", original_status);
+    for (fuse, status) in &report.fuses {
+        println!("The {:?} fuse is now `{:?}`", fuse, status);
+    }
+    for (target, outcome) in &report.targets {
+        println!("{:?} was {:?}", target, outcome);
+    }
+    for warning in &report.warnings {
+        println!("Warning: {}", warning);
+    }
 
-    println!("Removing RUN_AS_NODE functionality");
-    app.set_fuse_status(fuse, false).unwrap();
+    // Individual fuses and options can still be inspected and changed afterwards. Applications can also own their
+    // bytes, which is how `ElectronApp::from_path` reads them from a file.
+    let app = ElectronApp::from_vec(application_bytes).unwrap();
 
-    let new_status = app.get_fuse_status(fuse).unwrap();
-    println!("The new fuse status is now `{:?}`", new_status);
+    match app.get_fuse_status(Fuse::RunAsNode).unwrap() {
+        FuseStatus::Present(false) => println!("RUN_AS_NODE functionality is removed"),
+        status => println!("RUN_AS_NODE is still `{:?}`", status),
+    }
 
     let flag = ElectronOption::JsFlags;
-
-    println!("Removing {:?} functionality from the app", flag);
-    app.patch_option(flag).unwrap();
-
-    match app.patch_option(flag) {
-        Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(_))) => {
-            println!("Removed the Electron flag!")
-        }
-        _ => println!("Didn't remove the flag!"),
+    match app.scan(flag) {
+        None => println!("Removed {:?} from the app!", flag),
+        Some(_) => println!("Didn't remove {:?}!", flag),
     }
 }
@@ -0,0 +1,289 @@
+//! An append-only log of every modification made to applications on a machine.
+//!
+//! Unlike a [report](crate::HardeningReport), which describes a single run, an audit log keeps growing across every
+//! run that's configured to write to it. Each entry is a line of JSON that includes a hash of the line before it, so
+//! entries that are edited or removed after being written can be detected with [AuditLog::verify].
+
+use crate::{
+    fuses::FuseStatus,
+    journal::encode_hex,
+    patcher::{PatchOutcome, PatchTarget},
+    AuditError, Fuse, HardeningReport,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// What was done to an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum AuditAction {
+    /// A hardening policy was applied.
+    Harden,
+    /// A previous hardening was undone with an [undo journal](crate::UndoJournal).
+    Unharden,
+}
+
+/// A single modification recorded in an [audit log](AuditLog).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct AuditEntry {
+    /// When the modification was made, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// What was done to the application.
+    pub action: AuditAction,
+    /// The path of the modified binary.
+    pub path: String,
+    /// The fuses that were changed.
+    pub fuses: Vec<Fuse>,
+    /// The patch targets that were disabled.
+    pub targets: Vec<PatchTarget>,
+    /// The SHA-256 hash of the binary before it was modified.
+    pub before_sha256: String,
+    /// The SHA-256 hash of the binary after it was modified.
+    pub after_sha256: String,
+    /// The SHA-256 hash of the previous line in the log, or all zeros for the first entry.
+    pub previous_hash: String,
+}
+
+impl AuditEntry {
+    /// Creates an entry for a modification made at the current time.
+    pub fn new(action: AuditAction, path: &str, before: &[u8], after: &[u8]) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            timestamp,
+            action,
+            path: path.to_string(),
+            fuses: Vec::new(),
+            targets: Vec::new(),
+            before_sha256: sha256_hex(before),
+            after_sha256: sha256_hex(after),
+            previous_hash: String::new(),
+        }
+    }
+
+    /// Creates an entry for an application that was hardened, listing everything in the report that was modified.
+    pub fn hardened(path: &str, report: &HardeningReport, before: &[u8], after: &[u8]) -> Self {
+        let mut entry = Self::new(AuditAction::Harden, path, before, after);
+
+        entry.fuses = report
+            .fuses
+            .iter()
+            .filter(|(_, status)| *status == FuseStatus::Modified)
+            .map(|(fuse, _)| *fuse)
+            .collect();
+        entry.targets = report
+            .targets
+            .iter()
+            .filter(|(_, outcome)| *outcome == PatchOutcome::Patched)
+            .map(|(target, _)| *target)
+            .collect();
+
+        entry
+    }
+}
+
+/// The only part of an entry that needs to be read back to verify the log.
+#[derive(Deserialize)]
+struct ChainLink {
+    previous_hash: String,
+}
+
+/// An append-only file of [audit entries](AuditEntry).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// The hash recorded by the first entry of a log, which has no previous entry.
+    const GENESIS_HASH: &'static str =
+        "0000000000000000000000000000000000000000000000000000000000000000";
+
+    /// Creates a handle to the audit log at the provided path.
+    ///
+    /// The file is created when the first entry is appended.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry to the end of the log, linking it to the entry before it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the log couldn't be read or written.
+    pub fn append(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+        entry.previous_hash = match fs::read_to_string(&self.path) {
+            Ok(log) => match log.lines().last() {
+                Some(last) => sha256_hex(last.as_bytes()),
+                None => Self::GENESIS_HASH.to_string(),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::GENESIS_HASH.to_string(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut line =
+            serde_json::to_string(&entry).expect("audit entries should always be serializable");
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // A single write keeps the line whole even if something else is appending to the log.
+        file.write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Checks that every entry in the log links to the entry before it, returning how many entries there are.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [BrokenChain](AuditError::BrokenChain) if any entry was modified, removed, or inserted
+    /// after being written, and [MalformedEntry](AuditError::MalformedEntry) if an entry isn't valid.
+    pub fn verify(&self) -> Result<usize, AuditError> {
+        let log = fs::read_to_string(&self.path)?;
+        let mut expected = Self::GENESIS_HASH.to_string();
+        let mut entries = 0;
+
+        for (i, line) in log.lines().enumerate() {
+            let link: ChainLink = serde_json::from_str(line)
+                .map_err(|_| AuditError::MalformedEntry { line: i + 1 })?;
+
+            if link.previous_hash != expected {
+                return Err(AuditError::BrokenChain { line: i + 1 });
+            }
+
+            expected = sha256_hex(line.as_bytes());
+            entries += 1;
+        }
+
+        Ok(entries)
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    encode_hex(&Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{patcher::tests::test_app_bytes, ElectronApp, HardeningPolicy, Preset};
+
+    /// A log in the temporary directory that's removed when dropped.
+    struct TempLog(AuditLog);
+
+    impl TempLog {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "electron-hardener-{}-{}.jsonl",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_file(&path);
+            Self(AuditLog::new(path))
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(self.0.path());
+        }
+    }
+
+    fn hardened_entry() -> AuditEntry {
+        let mut bytes = test_app_bytes();
+        let before = bytes.clone();
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app
+            .apply_policy(&HardeningPolicy::from_preset(Preset::Recommended))
+            .unwrap();
+
+        AuditEntry::hardened("app.bin", &report, &before, &bytes)
+    }
+
+    #[test]
+    fn entries_are_chained() {
+        let log = TempLog::new("chained");
+
+        let entry = hardened_entry();
+        assert!(!entry.fuses.is_empty());
+        assert!(!entry.targets.is_empty());
+        assert_ne!(entry.before_sha256, entry.after_sha256);
+
+        log.0.append(entry).unwrap();
+        log.0
+            .append(AuditEntry::new(
+                AuditAction::Unharden,
+                "app.bin",
+                b"b",
+                b"a",
+            ))
+            .unwrap();
+        assert_eq!(log.0.verify().unwrap(), 2);
+
+        let contents = fs::read_to_string(log.0.path()).unwrap();
+        let first = contents.lines().next().unwrap();
+        assert!(first.contains(&format!("\"previous_hash\":\"{}\"", AuditLog::GENESIS_HASH)));
+        assert!(contents
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains(&sha256_hex(first.as_bytes())));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let log = TempLog::new("tampered");
+
+        for _ in 0..3 {
+            log.0.append(hardened_entry()).unwrap();
+        }
+
+        let contents = fs::read_to_string(log.0.path()).unwrap();
+
+        // Editing an entry breaks the link from the entry after it.
+        fs::write(log.0.path(), contents.replacen("app.bin", "other.bin", 1)).unwrap();
+        assert!(matches!(
+            log.0.verify(),
+            Err(AuditError::BrokenChain { line: 2 })
+        ));
+
+        // So does removing one.
+        let without_second: Vec<&str> = contents
+            .lines()
+            .enumerate()
+            .filter(|(i, _)| *i != 1)
+            .map(|(_, l)| l)
+            .collect();
+        fs::write(log.0.path(), without_second.join("\n")).unwrap();
+        assert!(matches!(
+            log.0.verify(),
+            Err(AuditError::BrokenChain { line: 2 })
+        ));
+
+        fs::write(log.0.path(), "not json\n").unwrap();
+        assert!(matches!(
+            log.0.verify(),
+            Err(AuditError::MalformedEntry { line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+//! Applying a list of individual fuse changes and patches, collecting the result of each instead of stopping at the
+//! first failure.
+
+use crate::{
+    fuses::FuseStatus,
+    journal::JournalEntry,
+    patcher::{is_not_present, ElectronOption, PatchOutcome, PatchTarget, PlannedPatch},
+    ElectronApp, Fuse, PatcherError,
+};
+use std::fmt;
+
+/// A single modification to make with [apply_items](ElectronApp::apply_items).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PatchItem {
+    /// Sets a fuse to a value in every fuse wire.
+    Fuse(Fuse, bool),
+    /// Patches a target out of the binary.
+    Target(PatchTarget),
+}
+
+impl From<PatchTarget> for PatchItem {
+    fn from(target: PatchTarget) -> Self {
+        Self::Target(target)
+    }
+}
+
+impl From<ElectronOption> for PatchItem {
+    fn from(option: ElectronOption) -> Self {
+        Self::Target(option.into())
+    }
+}
+
+impl fmt::Display for PatchItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fuse(fuse, enabled) => write!(f, "{:?}={}", fuse, enabled),
+            Self::Target(target) => write!(f, "{}", target),
+        }
+    }
+}
+
+/// What happened when a [patch item](PatchItem) was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ItemOutcome {
+    /// The fuse was changed or the target was patched out.
+    Applied,
+    /// Nothing needed to be modified, because the fuse already had the requested value or the target had already
+    /// been patched out.
+    AlreadyApplied,
+    /// The target couldn't be found in the binary, and doesn't appear to have been patched out either.
+    NotPresent,
+}
+
+impl fmt::Display for ItemOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ItemOutcome::Applied => "applied",
+            ItemOutcome::AlreadyApplied => "already applied",
+            ItemOutcome::NotPresent => "not present",
+        })
+    }
+}
+
+/// What [applying](ElectronApp::apply_items) a patch item would do, as computed by
+/// [simulate_items](ElectronApp::simulate_items).
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct SimulatedItem {
+    /// The item that was simulated.
+    pub item: PatchItem,
+    /// The result applying the item would have.
+    pub outcome: Result<ItemOutcome, PatcherError>,
+    /// Every modification applying the item would make, in the order they would be made.
+    pub changes: Vec<JournalEntry>,
+}
+
+impl ElectronApp<'_> {
+    /// Applies each of the items in turn, continuing past the ones that fail.
+    ///
+    /// A result is returned for every item, in the same order. Items that didn't need to modify anything are
+    /// [AlreadyApplied](ItemOutcome::AlreadyApplied), and targets that couldn't be found at all are
+    /// [NotPresent](ItemOutcome::NotPresent). Errors are reserved for items that couldn't be applied, such as fuses
+    /// that don't exist in the application's fuse wire. All of the targets are searched for in a
+    /// [single pass](ElectronApp::patch_all) before anything is modified, then the items are applied in order, so the
+    /// modifications are made in the same order as the items. Targets are patched the same way as with `patch_all`,
+    /// and one that isn't expected in the application's Electron version is still `NotPresent`.
+    ///
+    /// Unlike [apply_policy](ElectronApp::apply_policy), items that were applied stay applied when others fail.
+    pub fn apply_items(
+        &mut self,
+        items: &[PatchItem],
+    ) -> Vec<(PatchItem, Result<ItemOutcome, PatcherError>)> {
+        let targets: Vec<PatchTarget> = items
+            .iter()
+            .filter_map(|item| match item {
+                PatchItem::Target(target) => Some(*target),
+                PatchItem::Fuse(..) => None,
+            })
+            .collect();
+        let mut plans = self.plan_targets(&targets).into_iter();
+        let mut written = Vec::new();
+
+        let mut results = Vec::with_capacity(items.len());
+        for &item in items {
+            let result = match item {
+                PatchItem::Fuse(fuse, enabled) => match self.set_fuse_status(fuse, enabled) {
+                    Ok(FuseStatus::Modified) => Ok(ItemOutcome::Applied),
+                    Ok(_) => Ok(ItemOutcome::AlreadyApplied),
+                    Err(e) => Err(e),
+                },
+                PatchItem::Target(target) => {
+                    let plan = plans.next().expect("a plan per target");
+                    match self.patch_planned(target, plan, &mut written, None) {
+                        Ok((PatchOutcome::Patched, _)) => Ok(ItemOutcome::Applied),
+                        Ok((PatchOutcome::AlreadyPatched, _)) => Ok(ItemOutcome::AlreadyApplied),
+                        Ok((PatchOutcome::NotPresent, _)) | Ok((PatchOutcome::Unavailable, _)) => {
+                            Ok(ItemOutcome::NotPresent)
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            results.push((item, result));
+        }
+
+        results
+    }
+
+    /// Computes what [apply_items](ElectronApp::apply_items) would do to the application, without modifying it.
+    ///
+    /// Every item is searched for and planned like it would be when applied, with the same
+    /// [fuse plans](ElectronApp::plan_fuse_change) and [patch plans](ElectronApp::plan_option_patch). Items are planned
+    /// as if the items before them had already been applied, so a fuse that's changed twice is planned from the value
+    /// the first change gives it, and a target that an earlier item patches out is already applied. The result of
+    /// each item is returned along with the offset, original bytes and replacement bytes of every modification it
+    /// would make. This lets changes to a binary be reviewed before they're made.
+    ///
+    /// Only the planned modifications are held in memory, not a copy of the binary. Because of that, a target that
+    /// was found where an earlier item of a different target would make changes isn't searched for again like it
+    /// would be when applied, and returns [OverlappingPatches](PatcherError::OverlappingPatches) instead.
+    pub fn simulate_items(&self, items: &[PatchItem]) -> Vec<SimulatedItem> {
+        let targets: Vec<PatchTarget> = items
+            .iter()
+            .filter_map(|item| match item {
+                PatchItem::Target(target) => Some(*target),
+                PatchItem::Fuse(..) => None,
+            })
+            .collect();
+        let mut plans = self.plan_targets(&targets).into_iter();
+
+        let mut simulated: Vec<SimulatedItem> = Vec::with_capacity(items.len());
+        let mut earlier = Vec::new();
+        for &item in items {
+            let planned = match item {
+                PatchItem::Fuse(fuse, enabled) => self
+                    .plan_fuse_change_in(0..self.wires.len(), fuse, enabled, &earlier)
+                    .map(|plan| match plan.status {
+                        FuseStatus::Modified => (ItemOutcome::Applied, plan.changes),
+                        _ => (ItemOutcome::AlreadyApplied, plan.changes),
+                    }),
+                PatchItem::Target(target) => self.simulate_target(
+                    target,
+                    plans.next().expect("a plan per target"),
+                    &simulated,
+                ),
+            }
+            .and_then(|(outcome, changes)| {
+                if !changes.is_empty() {
+                    self.check_signature()?;
+                }
+                Ok((outcome, changes))
+            });
+
+            let (outcome, changes) = match planned {
+                Ok((outcome, changes)) => (Ok(outcome), changes),
+                Err(e) => (Err(e), Vec::new()),
+            };
+            earlier.extend(changes.iter().cloned());
+            simulated.push(SimulatedItem {
+                item,
+                outcome,
+                changes,
+            });
+        }
+
+        simulated
+    }
+
+    /// Plans a target item like [apply_items](ElectronApp::apply_items) would apply it after the `earlier` items.
+    fn simulate_target(
+        &self,
+        target: PatchTarget,
+        plan: Result<Vec<PlannedPatch>, PatcherError>,
+        earlier: &[SimulatedItem],
+    ) -> Result<(ItemOutcome, Vec<JournalEntry>), PatcherError> {
+        let patches = match plan {
+            Ok(patches) => patches,
+            Err(e) if is_not_present(&e) && self.is_patched_out(target) => {
+                return Ok((ItemOutcome::AlreadyApplied, Vec::new()))
+            }
+            Err(e) if is_not_present(&e) => return Ok((ItemOutcome::NotPresent, Vec::new())),
+            Err(e) => return Err(e),
+        };
+
+        // Once an earlier item patches the target out, it isn't found anymore.
+        let patched_earlier = earlier.iter().any(|simulated| {
+            simulated.item == PatchItem::Target(target)
+                && simulated.outcome == Ok(ItemOutcome::Applied)
+        });
+        if patched_earlier {
+            return Ok((ItemOutcome::AlreadyApplied, Vec::new()));
+        }
+
+        for simulated in earlier {
+            let first = match simulated.item {
+                PatchItem::Target(first) => first,
+                PatchItem::Fuse(..) => continue,
+            };
+            for change in &simulated.changes {
+                let range = change.offset..(change.offset + change.modified.len());
+                if let Some(patch) = patches.iter().find(|patch| {
+                    patch.found.range.start < range.end && range.start < patch.found.range.end
+                }) {
+                    return Err(PatcherError::OverlappingPatches {
+                        first: (first, range),
+                        second: (patch.target, patch.range.clone()),
+                    });
+                }
+            }
+        }
+
+        #[cfg(feature = "object-parser")]
+        self.check_code_patches(&patches)?;
+        let plan = self.patch_plan(target, patches);
+        Ok((ItemOutcome::Applied, plan.changes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{patcher::tests::test_app_bytes, BinaryError};
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+
+    #[test]
+    fn mixed_items_are_all_attempted() {
+        let mut bytes = test_app_bytes();
+        // Leave the last fuse out of the wire.
+        let wire = crate::fuses::FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start - 1] -= 1;
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.patch_option(ElectronOption::RemoteDebuggingPort)
+            .unwrap();
+
+        let items = [
+            PatchItem::Fuse(Fuse::RunAsNode, false),
+            PatchItem::Fuse(Fuse::EncryptedCookies, false),
+            PatchItem::Fuse(Fuse::LoadBrowserProcessSpecificV8Snapshot, false),
+            ElectronOption::JsFlags.into(),
+            ElectronOption::RemoteDebuggingPort.into(),
+            PatchItem::Target(PatchTarget::DevToolsListeningMessage),
+        ];
+        let results = app.apply_items(&items);
+
+        assert_eq!(
+            results,
+            vec![
+                (items[0], Ok(ItemOutcome::Applied)),
+                (items[1], Ok(ItemOutcome::AlreadyApplied)),
+                (
+                    items[2],
+                    Err(
+                        BinaryError::FuseDoesNotExist(Fuse::LoadBrowserProcessSpecificV8Snapshot)
+                            .into()
+                    )
+                ),
+                (items[3], Ok(ItemOutcome::Applied)),
+                (items[4], Ok(ItemOutcome::AlreadyApplied)),
+                (items[5], Ok(ItemOutcome::Applied)),
+            ]
+        );
+
+        // Targets that were never in the binary are told apart from ones that were patched out.
+        let mut app = ElectronApp::from_bytes(&mut bytes[..FUSE_BYTES.len()]).unwrap();
+        assert_eq!(
+            app.apply_items(&[ElectronOption::JsFlags.into()]),
+            vec![(ElectronOption::JsFlags.into(), Ok(ItemOutcome::NotPresent))]
+        );
+    }
+
+    #[test]
+    fn simulating_items_matches_applying_them() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::{Hash, Hasher},
+        };
+
+        let hash = |bytes: &[u8]| {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut bytes = test_app_bytes();
+        let original_hash = hash(&bytes);
+
+        let items = [
+            PatchItem::Fuse(Fuse::RunAsNode, false),
+            ElectronOption::JsFlags.into(),
+            PatchItem::Fuse(Fuse::RunAsNode, true),
+            ElectronOption::JsFlags.into(),
+            PatchItem::Target(PatchTarget::NodeInspect),
+        ];
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let simulated = app.simulate_items(&items);
+        assert_eq!(hash(app.as_bytes()), original_hash);
+        assert!(!app.is_dirty());
+
+        // The second change to the fuse undoes the first, and the second patch has nothing left to do.
+        assert_eq!(simulated.len(), items.len());
+        assert_eq!(simulated[0].changes[0].original, vec![b'1']);
+        assert_eq!(simulated[0].changes[0].modified, vec![b'0']);
+        assert_eq!(simulated[2].changes[0].modified, vec![b'1']);
+        assert_eq!(simulated[3].outcome, Ok(ItemOutcome::AlreadyApplied));
+        assert!(simulated[3].changes.is_empty());
+
+        // The real run makes the same modifications, one item at a time.
+        app.start_undo_journal();
+        for item in &simulated {
+            let (_, outcome) = app.apply_items(&[item.item]).pop().unwrap();
+            assert_eq!(outcome, item.outcome);
+        }
+        let journal = app.take_undo_journal().unwrap();
+        let planned: Vec<JournalEntry> = simulated
+            .iter()
+            .flat_map(|item| item.changes.iter().cloned())
+            .collect();
+        assert_eq!(journal.entries(), &planned[..]);
+
+        // Applying them all at once makes the same modifications in the same order, even though the targets are
+        // searched for before any of the fuses are changed.
+        let mut at_once = test_app_bytes();
+        assert_eq!(hash(&at_once), original_hash);
+        let mut app = ElectronApp::from_bytes(&mut at_once).unwrap();
+        app.start_undo_journal();
+        let results = app.apply_items(&items);
+        assert_eq!(results.len(), simulated.len());
+        for ((_, outcome), item) in results.iter().zip(&simulated) {
+            assert_eq!(outcome, &item.outcome);
+        }
+        assert_eq!(app.take_undo_journal().unwrap().entries(), &planned[..]);
+        assert!(at_once == bytes, "applying every item at once differed");
+    }
+}
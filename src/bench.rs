@@ -0,0 +1,62 @@
+//! Helpers for measuring how quickly applications are searched and patched.
+//!
+//! The crate's own benchmarks use these, and they can be used to benchmark binaries of other sizes.
+
+/// The fuse wire and patch targets placed at the end of every synthetic binary.
+const APP_BYTES: &[&[u8]] = &[
+    include_bytes!("../examples/fake_electron_flags.bin"),
+    include_bytes!("../examples/fake_electron_fuses.bin"),
+];
+
+/// Creates a synthetic application binary of `len` bytes, with its fuse wire and patch targets near the end.
+///
+/// Everything before them is filler that resembles machine code without containing anything that's searched for, so
+/// searches have to scan almost the whole binary like they would in a real application. Binaries shorter than the
+/// fuse wire and patch targets only contain as much of them as fits.
+pub fn synthetic_binary(len: usize) -> Vec<u8> {
+    let app_len: usize = APP_BYTES.iter().map(|bytes| bytes.len()).sum();
+    let filler_len = len.saturating_sub(app_len);
+
+    let mut binary = Vec::with_capacity(len);
+    let mut state: u32 = 0x9e37_79b9;
+    binary.extend((0..filler_len).map(|_| {
+        // A small xorshift generator keeps the filler varied without pulling in a dependency.
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 24) as u8
+    }));
+
+    for bytes in APP_BYTES {
+        binary.extend_from_slice(bytes);
+    }
+    binary.truncate(len);
+
+    binary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fuses::FuseWire, patcher::PatchTarget};
+
+    #[test]
+    fn synthetic_binaries_contain_an_app() {
+        let binary = synthetic_binary(1 << 20);
+        assert_eq!(binary.len(), 1 << 20);
+
+        let wires = FuseWire::locate_all(&binary, 0).unwrap();
+        assert_eq!(wires.len(), 1);
+        assert!(wires[0].range().start > (1 << 20) - 4096);
+
+        for target in PatchTarget::ALL {
+            assert!(target.locate(&binary).is_some(), "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn short_synthetic_binaries_are_truncated() {
+        assert_eq!(synthetic_binary(0), Vec::<u8>::new());
+        assert_eq!(synthetic_binary(16).len(), 16);
+    }
+}
@@ -1,40 +1,1097 @@
 //! A re-implementation of the `electron-evil-feature-patcher` CLI tool that works nearly identically.
+//!
+//! Usage: `electron-hardener [--strict] [--report <report-path>] [--search-range <start>..<end>]
+//! [--require-schema-version <version>] [--undo-journal] [--audit-log <log-path>] [--json]
+//! [--fuses-only | --options-only] [--normalize] [--allow-code-patches] [--expect-min-patches <count>] <path>...`
+//!
+//! Or: `electron-hardener unharden --undo <journal-path> [--audit-log <log-path>] <path>`
+//!
+//! Or: `electron-hardener verify-audit-log <log-path>`
+//!
+//! Or: `electron-hardener posture [--json] <path>`
+//!
+//! Or: `electron-hardener coverage [--json] <path>`
+//!
+//! Or: `electron-hardener --compat-eefp [--verbose] [--path] <path>`
+//!
+//! Or: `electron-hardener --version [--verbose]`
+//!
+//! Without any of the flags that change how binaries are hardened, each binary is hardened with the library's
+//! [harden_file](electron_hardener::harden_file).
+//!
+//! Each binary is hardened in turn and its result is printed as soon as it completes, followed by a summary of the
+//! whole run. A binary that fails doesn't stop the others from being hardened, but makes the run exit unsuccessfully.
+//!
+//! Binaries are hardened one at a time, and each one's contents are released before the next is read, so memory use
+//! peaks at the size of the largest binary. With `--audit-log`, a second copy is kept while it's hardened. The CLI
+//! doesn't use [harden_files](electron_hardener::harden_files) or a [BufferPool](electron_hardener::BufferPool),
+//! so that each binary's result can be printed before the next one starts.
+//!
+//! By default, patch targets that can't be found are reported as warnings. With `--strict`, a required target that
+//! is unexpectedly missing for the detected Electron version fails that binary and nothing is written to it.
+//!
+//! If a binary has a resources directory next to it, it's checked for an unpacked `app` directory before the
+//! `OnlyLoadAppFromAsar` fuse is enabled. Finding one is a warning, or fails the binary with `--strict`.
+//!
+//! `--report` writes a JSON report of everything that was done to each binary to the provided path.
+//!
+//! `--json` replaces the human readable output with a single JSON object describing the whole run, printed once every
+//! binary has been processed. Its format is described by [RunResult].
+//!
+//! `--fuses-only` only changes the fuses of each binary, and `--options-only` only patches out its options, flags
+//! and messages. By default, both are done.
+//!
+//! When more than one binary is hardened, their patch targets are compared afterwards. A target that's patched out of
+//! some of them but still present in others is reported, since it usually means an earlier run was interrupted or
+//! used a different policy. `--normalize` patches those targets out of the binaries that still contain them.
+//!
+//! Patch targets found inside of an executable section of a binary are refused, since they would corrupt its code.
+//! `--allow-code-patches` patches them anyway.
+//!
+//! `--expect-min-patches` fails a binary without modifying it if fewer than the provided number of patch targets are
+//! found in it. This catches the policy being applied to the wrong binary, such as another product or architecture.
+//!
+//! `--search-range` searches the provided byte range of each binary for patch targets before falling back to the
+//! rest of it. This speeds up patching large binaries when the location of Electron's switches is already known.
+//!
+//! `--require-schema-version` checks each binary's fuse schema version before anything is patched. If it doesn't
+//! match, the run stops immediately and exits with code 3.
+//!
+//! `--undo-journal` writes the original contents of every modified byte to a `<path>.undo` file next to each binary
+//! that was hardened. `unharden` uses that journal to restore the binary to how it was before being hardened.
+//!
+//! `--audit-log` appends an entry to the provided log for every binary that's modified, including when it's
+//! unhardened. The log is kept across runs, and `verify-audit-log` checks that none of its entries were changed.
+//!
+//! `posture` scores each fuse and patch target of an application as hardened, exposed or unknown without modifying
+//! it. The path can be a binary, a macOS `.app` bundle, or the directory an application is installed in.
+//!
+//! `--compat-eefp` accepts the arguments of [electron-evil-feature-patcher] instead, for scripts that haven't been
+//! migrated yet. It's also enabled when the binary is installed or linked under that tool's name. The arguments are
+//! mapped onto the native ones, which is printed as a notice, and the binaries are then hardened the same way.
+//!
+//! [electron-evil-feature-patcher]: https://github.com/antelle/electron-evil-feature-patcher
+//!
+//! `coverage` lists the patch targets that none of the search patterns match in an unpatched binary, separating the
+//! ones that aren't expected in its version of Electron. It's meant for updating the patterns for new versions.
+//!
+//! `--version` prints the version of the tool. With `--verbose`, it also prints the range of Electron versions, the
+//! fuse schema version, and the revision of the patch targets it supports, which are also included in `--json`
+//! output.
 
-use electron_hardener::{patcher::ElectronOption, ElectronApp, Fuse};
-use std::{env, fs};
+use electron_hardener::{
+    audit::{AuditAction, AuditEntry},
+    fuses::FuseStatus,
+    patcher::{PatchState, PatchTarget},
+    AppLayout, AuditLog, CoverageReport, ElectronApp, Exposure, HardeningPolicy, HardeningReport,
+    HardeningWarning, LibraryInfo, MixedTarget, PatcherError, PostureReport, Preset, Severity,
+    UndoJournal,
+};
+use serde::Serialize;
+use std::{
+    env,
+    error::Error,
+    fmt, fs,
+    ops::Range,
+    process,
+    time::{Duration, Instant},
+};
 
-const FUSES_TO_DISABLE: &[Fuse] = &[Fuse::RunAsNode, Fuse::NodeOptions, Fuse::NodeCliInspect];
-const FUSES_TO_ENABLE: &[Fuse] = &[Fuse::OnlyLoadAppFromAsar];
+/// The name of the tool whose arguments are accepted with `--compat-eefp`.
+const EEFP_NAME: &str = "electron-evil-feature-patcher";
 
-const ELECTRON_FLAGS: &[ElectronOption] = &[
-    ElectronOption::JsFlags,
-    ElectronOption::RemoteDebuggingPipe,
-    ElectronOption::RemoteDebuggingPort,
-    ElectronOption::WaitForDebuggerChildren,
-];
+/// The exit code used when a binary's fuse schema version doesn't match `--require-schema-version`.
+const SCHEMA_MISMATCH_EXIT_CODE: i32 = 3;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let application_path = env::args()
-        .nth(1)
-        .ok_or_else(|| "no file path provided".to_string())?;
+/// The report written by `--report` for each binary.
+#[derive(Serialize)]
+struct FileReport<'a> {
+    path: &'a str,
+    #[serde(flatten)]
+    report: &'a HardeningReport,
+}
+
+struct Args {
+    application_paths: Vec<String>,
+    strict: bool,
+    report_path: Option<String>,
+    search_range: Option<Range<usize>>,
+    required_schema_version: Option<u8>,
+    undo_journal: bool,
+    audit_log: Option<AuditLog>,
+    json: bool,
+    fuses_only: bool,
+    options_only: bool,
+    normalize: bool,
+    allow_code_patches: bool,
+    min_patches: usize,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut application_paths = Vec::new();
+        let mut strict = false;
+        let mut report_path = None;
+        let mut search_range = None;
+        let mut required_schema_version = None;
+        let mut undo_journal = false;
+        let mut audit_log = None;
+        let mut json = false;
+        let mut fuses_only = false;
+        let mut options_only = false;
+        let mut normalize = false;
+        let mut allow_code_patches = false;
+        let mut min_patches = 0;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--strict" => strict = true,
+                "--json" => json = true,
+                "--undo-journal" => undo_journal = true,
+                "--fuses-only" => fuses_only = true,
+                "--options-only" => options_only = true,
+                "--normalize" => normalize = true,
+                "--allow-code-patches" => allow_code_patches = true,
+                "--report" => {
+                    report_path = Some(args.next().ok_or("--report requires a path")?);
+                }
+                "--audit-log" => {
+                    audit_log = Some(AuditLog::new(
+                        args.next().ok_or("--audit-log requires a path")?,
+                    ));
+                }
+                "--search-range" => {
+                    let range = args.next().ok_or("--search-range requires a range")?;
+                    search_range = Some(parse_range(&range)?);
+                }
+                "--expect-min-patches" => {
+                    let count = args.next().ok_or("--expect-min-patches requires a count")?;
+                    min_patches = count
+                        .parse()
+                        .map_err(|_| format!("invalid patch count {}", count))?;
+                }
+                "--require-schema-version" => {
+                    let version = args
+                        .next()
+                        .ok_or("--require-schema-version requires a version")?;
+                    required_schema_version = Some(
+                        version
+                            .parse()
+                            .map_err(|_| format!("invalid schema version {}", version))?,
+                    );
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown flag {}", flag)),
+                _ => application_paths.push(arg),
+            }
+        }
+
+        if application_paths.is_empty() {
+            return Err("no file path provided".to_string());
+        }
+
+        if fuses_only && options_only {
+            return Err("--fuses-only and --options-only can't be used together".to_string());
+        }
+
+        if fuses_only && normalize {
+            return Err(
+                "--normalize patches options, so it can't be used with --fuses-only".to_string(),
+            );
+        }
+
+        Ok(Self {
+            application_paths,
+            strict,
+            report_path,
+            search_range,
+            required_schema_version,
+            undo_journal,
+            audit_log,
+            json,
+            fuses_only,
+            options_only,
+            normalize,
+            allow_code_patches,
+            min_patches,
+        })
+    }
+}
+
+impl Args {
+    /// Returns `true` if no flags that change how a binary is hardened were passed, so that it can be hardened with
+    /// [harden_file](electron_hardener::harden_file).
+    fn uses_library_defaults(&self) -> bool {
+        !self.strict
+            && self.search_range.is_none()
+            && self.required_schema_version.is_none()
+            && !self.undo_journal
+            && self.audit_log.is_none()
+            && !self.fuses_only
+            && !self.options_only
+            && !self.allow_code_patches
+            && self.min_patches == 0
+    }
+}
+
+/// The arguments of the `unharden` command.
+struct UnhardenArgs {
+    journal_path: String,
+    application_path: String,
+    audit_log: Option<AuditLog>,
+}
+
+impl UnhardenArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut journal_path = None;
+        let mut application_path = None;
+        let mut audit_log = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--undo" => {
+                    journal_path = Some(args.next().ok_or("--undo requires a path")?);
+                }
+                "--audit-log" => {
+                    audit_log = Some(AuditLog::new(
+                        args.next().ok_or("--audit-log requires a path")?,
+                    ));
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown flag {}", flag)),
+                _ if application_path.is_none() => application_path = Some(arg),
+                _ => return Err(format!("unexpected argument {}", arg)),
+            }
+        }
+
+        Ok(Self {
+            journal_path: journal_path.ok_or("unharden requires --undo <journal-path>")?,
+            application_path: application_path
+                .ok_or_else(|| "no file path provided".to_string())?,
+            audit_log,
+        })
+    }
+}
+
+/// Maps electron-evil-feature-patcher's arguments onto native ones.
+///
+/// It takes the application's path either on its own or with `--path`, and `--verbose` only affected how much it
+/// printed. Both tools harden the same things by default, so the path is all that needs to be passed on.
+fn map_eefp_args(mut args: impl Iterator<Item = String>) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--verbose" => {}
+            "--path" => paths.push(args.next().ok_or("--path requires a path")?),
+            _ if arg.starts_with("--path=") => paths.push(arg["--path=".len()..].to_string()),
+            flag if flag.starts_with('-') => {
+                return Err(format!(
+                    "{} isn't supported by {} compatibility",
+                    flag, EEFP_NAME
+                ))
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(format!("{} requires a path", EEFP_NAME));
+    }
+
+    Ok(paths)
+}
+
+/// Parses a range written as `start..end`.
+fn parse_range(range: &str) -> Result<Range<usize>, String> {
+    let invalid = || format!("invalid search range {}, expected <start>..<end>", range);
+
+    let split = range.find("..").ok_or_else(invalid)?;
+    let start = range[..split].parse().map_err(|_| invalid())?;
+    let end = range[split + 2..].parse().map_err(|_| invalid())?;
+
+    if start >= end {
+        return Err(invalid());
+    }
+
+    Ok(start..end)
+}
+
+/// A binary's fuse schema version didn't match the one required by `--require-schema-version`.
+#[derive(Debug)]
+struct SchemaVersionMismatch {
+    expected: u8,
+    found: u8,
+}
+
+impl fmt::Display for SchemaVersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected fuse schema version {}, but found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for SchemaVersionMismatch {}
+
+/// What happened to a single binary.
+enum FileOutcome {
+    /// At least one fuse or option was modified.
+    Hardened(HardeningReport),
+    /// Everything in the policy was already applied, so the binary was left untouched.
+    AlreadyHardened(HardeningReport),
+    Failed(Box<dyn Error>),
+}
+
+/// Counts of each [outcome](FileOutcome) over a whole run.
+#[derive(Default, Clone, Serialize)]
+struct Summary {
+    hardened: usize,
+    already_hardened: usize,
+    failed: usize,
+    /// How many binaries had [mixed targets](MixedTarget) patched out of them by `--normalize`.
+    normalized: usize,
+}
+
+impl Summary {
+    fn record(&mut self, outcome: &FileOutcome) {
+        match outcome {
+            FileOutcome::Hardened(_) => self.hardened += 1,
+            FileOutcome::AlreadyHardened(_) => self.already_hardened += 1,
+            FileOutcome::Failed(_) => self.failed += 1,
+        }
+    }
+
+    fn record_normalized(&mut self, outcome: &FileOutcome) {
+        match outcome {
+            FileOutcome::Failed(_) => self.failed += 1,
+            _ => self.normalized += 1,
+        }
+    }
+}
+
+/// Receives the result of each binary as it completes.
+trait Reporter {
+    fn file_finished(
+        &mut self,
+        path: &str,
+        policy: &HardeningPolicy,
+        outcome: &FileOutcome,
+        elapsed: Duration,
+    );
+
+    /// Called with the targets that are patched out of only some of the binaries. `binaries` are the paths the
+    /// indexes in each target refer to.
+    fn mixed_targets(&mut self, mixed: &[MixedTarget], binaries: &[&String]);
+
+    /// Called after `--normalize` patched mixed targets out of a binary.
+    fn file_normalized(&mut self, path: &str, outcome: &FileOutcome, elapsed: Duration);
+
+    fn finished(&mut self, summary: &Summary);
+}
+
+/// Prints a line-oriented report to the terminal.
+struct TerminalReporter {
+    color: bool,
+}
+
+impl TerminalReporter {
+    const GREEN: &'static str = "\x1b[32m";
+    const RED: &'static str = "\x1b[31m";
+    const RESET: &'static str = "\x1b[0m";
+
+    /// Follows the `NO_COLOR` convention, and avoids colors on terminals that can't display them.
+    fn new() -> Self {
+        let color = env::var_os("NO_COLOR").is_none()
+            && matches!(env::var_os("TERM"), Some(term) if term != "dumb");
+        Self { color }
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, Self::RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn file_finished(
+        &mut self,
+        path: &str,
+        policy: &HardeningPolicy,
+        outcome: &FileOutcome,
+        _elapsed: Duration,
+    ) {
+        let report = match outcome {
+            FileOutcome::Hardened(report) => {
+                println!("{}: hardened", path);
+                report
+            }
+            FileOutcome::AlreadyHardened(report) => {
+                println!("{}: already hardened", path);
+                report
+            }
+            FileOutcome::Failed(e) => {
+                println!(
+                    "{}: {}",
+                    path,
+                    self.paint(Self::RED, &format!("failed: {}", e))
+                );
+                return;
+            }
+        };
+
+        for (&(fuse, enabled), (_, status)) in policy.fuses().iter().zip(&report.fuses) {
+            let wanted = if enabled { "enabled" } else { "disabled" };
+            let line = match status {
+                FuseStatus::Removed => self.paint(Self::RED, &format!("{}: removed", fuse)),
+                _ => self.paint(Self::GREEN, &format!("{}: {}", fuse, wanted)),
+            };
+            println!("  {}", line);
+        }
+
+        for warning in &report.warnings {
+            eprintln!("  warning: {}", warning);
+        }
+    }
+
+    fn mixed_targets(&mut self, mixed: &[MixedTarget], binaries: &[&String]) {
+        let paths = |indexes: &[usize]| {
+            indexes
+                .iter()
+                .map(|&i| binaries[i].as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        for m in mixed {
+            eprintln!(
+                "warning: {} is patched out of {}, but not {}",
+                m.target,
+                paths(&m.hardened),
+                paths(&m.unhardened)
+            );
+        }
+    }
+
+    fn file_normalized(&mut self, path: &str, outcome: &FileOutcome, _elapsed: Duration) {
+        match outcome {
+            FileOutcome::Failed(e) => println!(
+                "{}: {}",
+                path,
+                self.paint(Self::RED, &format!("failed to normalize: {}", e))
+            ),
+            _ => println!("{}: normalized", path),
+        }
+    }
+
+    fn finished(&mut self, summary: &Summary) {
+        println!(
+            "{} hardened, {} already hardened, {} failed",
+            summary.hardened, summary.already_hardened, summary.failed
+        );
+
+        if summary.normalized > 0 {
+            println!("{} normalized", summary.normalized);
+        }
+    }
+}
+
+/// The result of a single binary in [RunResult].
+#[derive(Serialize)]
+struct FileResult {
+    path: String,
+    success: bool,
+    /// `hardened`, `already_hardened`, or `failed`.
+    outcome: &'static str,
+    duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Each warning's message, duplicated from the report for consumers that don't want to interpret it.
+    warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<HardeningReport>,
+}
+
+/// The JSON object printed by `--json`.
+#[derive(Serialize)]
+struct RunResult {
+    /// Incremented whenever a field is removed or changes meaning.
+    format_version: u32,
+    /// What the library that hardened the binaries supports.
+    hardener: LibraryInfo,
+    success: bool,
+    duration_ms: u128,
+    files: Vec<FileResult>,
+    /// The targets that are patched out of only some of the binaries.
+    mixed_targets: Vec<MixedTargetResult>,
+    /// The result of each binary that `--normalize` patched mixed targets out of.
+    normalized_files: Vec<FileResult>,
+    summary: Summary,
+}
+
+/// A [MixedTarget] with the paths of the binaries instead of their indexes.
+#[derive(Serialize)]
+struct MixedTargetResult {
+    target: PatchTarget,
+    hardened: Vec<String>,
+    unhardened: Vec<String>,
+}
+
+/// Collects the result of every binary and prints them as a single [RunResult].
+struct JsonReporter {
+    started: Instant,
+    files: Vec<FileResult>,
+    mixed_targets: Vec<MixedTargetResult>,
+    normalized_files: Vec<FileResult>,
+}
+
+impl JsonReporter {
+    const FORMAT_VERSION: u32 = 1;
+
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            files: Vec::new(),
+            mixed_targets: Vec::new(),
+            normalized_files: Vec::new(),
+        }
+    }
+
+    fn file_result(path: &str, outcome: &FileOutcome, elapsed: Duration) -> FileResult {
+        let (outcome, report, error) = match outcome {
+            FileOutcome::Hardened(report) => ("hardened", Some(report.clone()), None),
+            FileOutcome::AlreadyHardened(report) => {
+                ("already_hardened", Some(report.clone()), None)
+            }
+            FileOutcome::Failed(e) => ("failed", None, Some(e.to_string())),
+        };
+
+        FileResult {
+            path: path.to_string(),
+            success: error.is_none(),
+            outcome,
+            duration_ms: elapsed.as_millis(),
+            error,
+            warnings: report
+                .iter()
+                .flat_map(|report| report.warnings.iter().map(ToString::to_string))
+                .collect(),
+            report,
+        }
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn file_finished(
+        &mut self,
+        path: &str,
+        _policy: &HardeningPolicy,
+        outcome: &FileOutcome,
+        elapsed: Duration,
+    ) {
+        self.files.push(Self::file_result(path, outcome, elapsed));
+    }
+
+    fn mixed_targets(&mut self, mixed: &[MixedTarget], binaries: &[&String]) {
+        let paths = |indexes: &[usize]| indexes.iter().map(|&i| binaries[i].clone()).collect();
+
+        self.mixed_targets
+            .extend(mixed.iter().map(|m| MixedTargetResult {
+                target: m.target,
+                hardened: paths(&m.hardened),
+                unhardened: paths(&m.unhardened),
+            }));
+    }
+
+    fn file_normalized(&mut self, path: &str, outcome: &FileOutcome, elapsed: Duration) {
+        self.normalized_files
+            .push(Self::file_result(path, outcome, elapsed));
+    }
+
+    fn finished(&mut self, summary: &Summary) {
+        let result = RunResult {
+            format_version: Self::FORMAT_VERSION,
+            hardener: electron_hardener::info(),
+            success: summary.failed == 0,
+            duration_ms: self.started.elapsed().as_millis(),
+            files: std::mem::take(&mut self.files),
+            mixed_targets: std::mem::take(&mut self.mixed_targets),
+            normalized_files: std::mem::take(&mut self.normalized_files),
+            summary: summary.clone(),
+        };
+
+        println!(
+            "{}",
+            serde_json::to_string(&result).expect("run results should always be serializable")
+        );
+    }
+}
+
+/// The state of every patch target in a binary after it was hardened.
+type PatchStates = Vec<(PatchTarget, PatchState)>;
+
+/// Which pass over the binaries of a run is being made.
+#[derive(Clone, Copy)]
+enum Pass {
+    /// The policy is applied to every binary.
+    Harden,
+    /// [Mixed targets](MixedTarget) are patched out of a binary that still contains them.
+    Normalize {
+        /// Whether the binary was modified by the first pass, so its undo journal has to be extended.
+        hardened_earlier: bool,
+    },
+}
+
+/// Applies the policy to a binary on disk. The states of its patch targets are returned for the first pass of
+/// runs with more than one binary, so that they can be compared.
+fn harden_file(
+    path: &str,
+    args: &Args,
+    policy: &HardeningPolicy,
+    pass: Pass,
+) -> (FileOutcome, PatchStates) {
+    match try_harden_file(path, args, policy, pass) {
+        Ok((report, true, states)) => (FileOutcome::Hardened(report), states),
+        Ok((report, false, states)) => (FileOutcome::AlreadyHardened(report), states),
+        Err(e) => (FileOutcome::Failed(e), Vec::new()),
+    }
+}
+
+/// Applies the policy to a binary on disk, returning its report, whether anything was modified, and the states of its
+/// patch targets if they're needed.
+fn try_harden_file(
+    path: &str,
+    args: &Args,
+    policy: &HardeningPolicy,
+    pass: Pass,
+) -> Result<(HardeningReport, bool, PatchStates), Box<dyn Error>> {
+    // Without any flags, binaries are hardened exactly like the library does it.
+    if let Pass::Harden = pass {
+        if args.uses_library_defaults() {
+            let report = electron_hardener::harden_file(path)?;
+            let states = if args.application_paths.len() > 1 {
+                ElectronApp::from_path(path)?.patch_states()
+            } else {
+                Vec::new()
+            };
+            let modified = report.is_modified();
+            return Ok((report, modified, states));
+        }
+    }
+
+    let mut app = match (ElectronApp::from_path(path), args.required_schema_version) {
+        (Ok(app), _) => app,
+        // Binaries with a schema version this library doesn't support can't be loaded at all.
+        (Err(PatcherError::FuseVersion { found, .. }), Some(expected)) if found != expected => {
+            return Err(SchemaVersionMismatch { expected, found }.into())
+        }
+        (Err(e), _) => return Err(e.into()),
+    };
+    let original_bytes = args.audit_log.as_ref().map(|_| app.as_bytes().to_vec());
+
+    if let Some(expected) = args.required_schema_version {
+        let found = app.schema_version();
+        if found != expected {
+            return Err(SchemaVersionMismatch { expected, found }.into());
+        }
+    }
+
+    if let Some(range) = &args.search_range {
+        app.set_search_hint(range.clone());
+    }
+
+    app.allow_code_patches(args.allow_code_patches);
+
+    if let Some(layout) = AppLayout::for_binary(path) {
+        app.set_app_layout(layout);
+    }
+
+    if args.undo_journal {
+        app.start_undo_journal();
+    }
+
+    let report = if args.fuses_only {
+        app.apply_fuses_only(policy)?
+    } else if args.options_only {
+        app.apply_options_only(policy)?
+    } else {
+        app.apply_policy(policy)?
+    };
+
+    // Targets that are absent because of the Electron version never produce a warning, so any that
+    // remain here were unexpectedly missing. Only required targets block the release.
+    if args.strict {
+        if let Some(blocking) = report.warnings.iter().find(|w| {
+            matches!(
+                w,
+                HardeningWarning::TargetNotPresent {
+                    severity: Severity::Required,
+                    ..
+                } | HardeningWarning::UnpackedApp(_)
+            )
+        }) {
+            return Err(blocking.to_string().into());
+        }
+    }
+
+    let modified = app.is_dirty();
+
+    // Collecting these searches the whole binary for every target, so it's only done when they're compared.
+    let states = match pass {
+        Pass::Harden if args.application_paths.len() > 1 => app.patch_states(),
+        _ => Vec::new(),
+    };
+
+    if modified {
+        // The journal is written first so that a binary is never modified without a way to undo it.
+        if let Some(mut journal) = app.take_undo_journal() {
+            let journal_path = format!("{}.undo", path);
+
+            if let Pass::Normalize {
+                hardened_earlier: true,
+            } = pass
+            {
+                let mut earlier: UndoJournal = fs::read_to_string(&journal_path)?.parse()?;
+                earlier.append(journal);
+                journal = earlier;
+            }
+
+            electron_hardener::replace_binary(journal_path, journal.to_string().as_bytes())?;
+        }
+
+        app.write_back()?;
+
+        if let (Some(log), Some(original_bytes)) = (&args.audit_log, original_bytes) {
+            log.append(AuditEntry::hardened(
+                path,
+                &report,
+                &original_bytes,
+                app.as_bytes(),
+            ))?;
+        }
+    }
+
+    Ok((report, modified, states))
+}
+
+/// Restores a binary to its original contents using an undo journal.
+fn unharden(args: UnhardenArgs) -> Result<(), Box<dyn Error>> {
+    let journal: UndoJournal = fs::read_to_string(&args.journal_path)?.parse()?;
+
+    let mut application_bytes = fs::read(&args.application_path)?;
+    let original_bytes = args.audit_log.as_ref().map(|_| application_bytes.clone());
+
+    journal.revert(&mut application_bytes)?;
+    electron_hardener::replace_binary(&args.application_path, &application_bytes)?;
+
+    if let (Some(log), Some(original_bytes)) = (&args.audit_log, original_bytes) {
+        log.append(AuditEntry::new(
+            AuditAction::Unharden,
+            &args.application_path,
+            &original_bytes,
+            &application_bytes,
+        ))?;
+    }
+
+    println!(
+        "{}: restored {} modifications",
+        args.application_path,
+        journal.entries().len()
+    );
+
+    Ok(())
+}
+
+/// Prints the version of the tool, and with `--verbose`, what it supports.
+fn version(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut verbose = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--verbose" => verbose = true,
+            _ => return Err(format!("unexpected argument {}", arg).into()),
+        }
+    }
+
+    let info = electron_hardener::info();
+    println!("electron-hardener {}", info.crate_version);
+
+    if verbose {
+        println!(
+            "supported Electron versions: {}-{}",
+            info.min_electron_major, info.max_electron_major
+        );
+        println!("fuse schema version: {}", info.fuse_schema_version);
+        println!("pattern database revision: {}", info.pattern_revision);
+    }
+
+    Ok(())
+}
+
+/// Checks that none of the entries in an audit log were changed after being written.
+fn verify_audit_log(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let path = args.next().ok_or("verify-audit-log requires a path")?;
+    if let Some(arg) = args.next() {
+        return Err(format!("unexpected argument {}", arg).into());
+    }
+
+    let entries = AuditLog::new(&path).verify()?;
+    println!("{}: {} entries verified", path, entries);
+
+    Ok(())
+}
+
+fn posture(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut path = None;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {}", flag).into()),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg).into()),
+        }
+    }
 
-    let mut application_bytes = fs::read(&application_path)?;
+    let path = path.ok_or("posture requires a path")?;
+    let report = PostureReport::assess(&path)?;
 
-    let mut app = ElectronApp::from_bytes(&mut application_bytes)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let binary = report
+        .binary
+        .as_ref()
+        .map_or_else(|| path.clone(), |binary| binary.display().to_string());
+    match report.electron_version {
+        Some(version) => println!("{}: Electron {}", binary, version),
+        None => println!("{}: unknown Electron version", binary),
+    }
+
+    for (fuse, exposure) in &report.fuses {
+        println!("  fuse {}: {}", fuse, exposure);
+    }
+    for (target, exposure) in &report.targets {
+        println!("  target {}: {}", target, exposure);
+    }
+    for problem in &report.problems {
+        println!("  problem: {}", problem);
+    }
 
-    for fuse in FUSES_TO_DISABLE.iter().copied() {
-        app.set_fuse_status(fuse, false)?;
+    println!(
+        "{} hardened, {} exposed, {} unknown",
+        report.count(Exposure::Hardened),
+        report.count(Exposure::Exposed),
+        report.count(Exposure::Unknown)
+    );
+
+    Ok(())
+}
+
+fn coverage(args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut path = None;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {}", flag).into()),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg).into()),
+        }
     }
 
-    for fuse in FUSES_TO_ENABLE.iter().copied() {
-        app.set_fuse_status(fuse, true)?;
+    let path = path.ok_or("coverage requires a path")?;
+    let report = CoverageReport::scan(&fs::read(&path)?);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
     }
 
-    for flag in ELECTRON_FLAGS.iter().copied() {
-        app.patch_option(flag)?;
+    match report.electron_version {
+        Some(version) => println!("{}: Electron {}", path, version),
+        None => println!("{}: unknown Electron version", path),
     }
 
-    fs::write(application_path, application_bytes)?;
+    for coverage in report.missing() {
+        println!("  missing {}", coverage.target);
+    }
+    for coverage in report.not_expected() {
+        println!("  not expected {}", coverage.target);
+    }
+
+    println!(
+        "{} of {} targets matched",
+        report.targets.len() - report.missing().count() - report.not_expected().count(),
+        report.targets.len()
+    );
+
+    Ok(())
+}
+
+/// Reports the targets that are patched out of only some of the binaries, and patches them out of the rest with
+/// `--normalize`.
+fn normalize(
+    args: &Args,
+    outcomes: &[(&String, FileOutcome)],
+    states: &[PatchStates],
+    reporter: &mut dyn Reporter,
+    summary: &mut Summary,
+) {
+    let mixed = MixedTarget::find(states);
+    if mixed.is_empty() {
+        return;
+    }
+
+    let binaries: Vec<&String> = outcomes.iter().map(|(path, _)| *path).collect();
+    reporter.mixed_targets(&mixed, &binaries);
+
+    if !args.normalize {
+        return;
+    }
+
+    for (index, (path, outcome)) in outcomes.iter().enumerate() {
+        let repair = mixed
+            .iter()
+            .filter(|m| m.unhardened.contains(&index))
+            .fold(HardeningPolicy::new(), |policy, m| policy.patch(m.target));
+
+        if repair.targets().is_empty() {
+            continue;
+        }
+
+        let pass = Pass::Normalize {
+            hardened_earlier: matches!(outcome, FileOutcome::Hardened(_)),
+        };
+
+        let started = Instant::now();
+        let (outcome, _) = harden_file(path, args, &repair, pass);
+        reporter.file_normalized(path, &outcome, started.elapsed());
+        summary.record_normalized(&outcome);
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().peekable();
+    let invoked_as_eefp = args
+        .next()
+        .and_then(|arg0| {
+            let stem = std::path::Path::new(&arg0).file_stem()?.to_owned();
+            Some(stem == EEFP_NAME)
+        })
+        .unwrap_or(false);
+
+    if invoked_as_eefp || args.peek().map(String::as_str) == Some("--compat-eefp") {
+        if !invoked_as_eefp {
+            args.next();
+        }
+
+        let native = map_eefp_args(args)?;
+        eprintln!(
+            "notice: {} arguments mapped to `electron-hardener {}`",
+            EEFP_NAME,
+            native.join(" ")
+        );
+        return run(Args::parse(native.into_iter())?);
+    }
+
+    match args.peek().map(String::as_str) {
+        Some("unharden") => {
+            args.next();
+            return unharden(UnhardenArgs::parse(args)?);
+        }
+        Some("verify-audit-log") => {
+            args.next();
+            return verify_audit_log(args);
+        }
+        Some("posture") => {
+            args.next();
+            return posture(args);
+        }
+        Some("coverage") => {
+            args.next();
+            return coverage(args);
+        }
+        Some("--version") => {
+            args.next();
+            return version(args);
+        }
+        _ => {}
+    }
+
+    run(Args::parse(args)?)
+}
+
+/// Hardens every binary in the arguments.
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let policy =
+        HardeningPolicy::from_preset(Preset::Recommended).expect_min_patches(args.min_patches);
+
+    let mut reporter: Box<dyn Reporter> = if args.json {
+        Box::new(JsonReporter::new())
+    } else {
+        Box::new(TerminalReporter::new())
+    };
+    let mut summary = Summary::default();
+    let mut outcomes = Vec::new();
+    let mut states = Vec::new();
+    let mut schema_mismatch = false;
+
+    for path in &args.application_paths {
+        let started = Instant::now();
+        let (outcome, file_states) = harden_file(path, &args, &policy, Pass::Harden);
+        reporter.file_finished(path, &policy, &outcome, started.elapsed());
+        summary.record(&outcome);
+
+        schema_mismatch =
+            matches!(&outcome, FileOutcome::Failed(e) if e.is::<SchemaVersionMismatch>());
+        outcomes.push((path, outcome));
+        states.push(file_states);
+
+        // The build is making assumptions that don't hold, so nothing else should be patched.
+        if schema_mismatch {
+            break;
+        }
+    }
+
+    if !schema_mismatch {
+        normalize(&args, &outcomes, &states, &mut *reporter, &mut summary);
+    }
+
+    reporter.finished(&summary);
+
+    if let Some(report_path) = &args.report_path {
+        let file_reports: Vec<FileReport> = outcomes
+            .iter()
+            .filter_map(|(path, outcome)| match outcome {
+                FileOutcome::Hardened(report) | FileOutcome::AlreadyHardened(report) => {
+                    Some(FileReport { path, report })
+                }
+                FileOutcome::Failed(_) => None,
+            })
+            .collect();
+        fs::write(report_path, serde_json::to_vec_pretty(&file_reports)?)?;
+    }
+
+    if schema_mismatch {
+        process::exit(SCHEMA_MISMATCH_EXIT_CODE);
+    }
+
+    if summary.failed > 0 {
+        return Err(format!(
+            "failed to harden {} of {} binaries",
+            summary.failed,
+            args.application_paths.len()
+        )
+        .into());
+    }
 
     Ok(())
 }
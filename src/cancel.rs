@@ -0,0 +1,113 @@
+//! Cooperative cancellation of long-running searches and patches.
+
+#[cfg(feature = "patch")]
+use crate::{ElectronApp, PatcherError};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag that can be used to stop an application from being hardened, from another thread.
+///
+/// Clones of a token share the same flag, so one clone can be given to an [ElectronApp](crate::ElectronApp) while another is kept to
+/// cancel it.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a new token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every operation using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "patch")]
+impl ElectronApp<'_> {
+    /// Allows searches and patches made to the application to be stopped with a [CancelToken].
+    ///
+    /// The token is checked periodically while the binary is searched for patch targets. Once it has been cancelled,
+    /// operations that patch the application fail with [Cancelled](PatcherError::Cancelled). The binary is always
+    /// left unmodified in that case, because cancellation is only checked before anything is written.
+    pub fn set_cancel_token(&mut self, token: CancelToken) {
+        self.cancel = Some(token);
+    }
+
+    /// Returns an error if the application's [cancel token](CancelToken) has been cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<(), PatcherError> {
+        match &self.cancel {
+            Some(token) if token.is_cancelled() => Err(PatcherError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "patch"))]
+mod tests {
+    use super::*;
+    use crate::{patcher::PatchTarget, HardeningPolicy, Preset};
+    use std::{thread, time::Duration};
+
+    /// An application that is mostly padding, so that searching it for targets takes a long time.
+    fn large_app_bytes() -> Vec<u8> {
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        bytes.resize(bytes.len() + (32 << 20), 0);
+        bytes
+    }
+
+    #[test]
+    fn hardening_is_cancelled_mid_scan() {
+        let original = large_app_bytes();
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let token = CancelToken::new();
+        app.set_cancel_token(token.clone());
+
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        });
+
+        assert_eq!(
+            app.apply_policy(&HardeningPolicy::from_preset(Preset::Paranoid)),
+            Err(PatcherError::Cancelled)
+        );
+        canceller.join().unwrap();
+
+        assert!(bytes == original, "the binary was modified");
+    }
+
+    #[test]
+    fn cancelled_app_is_not_patched() {
+        let mut bytes = include_bytes!("../examples/fake_electron_flags.bin").to_vec();
+        bytes.extend_from_slice(include_bytes!("../examples/fake_electron_fuses.bin"));
+        let original = bytes.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let token = CancelToken::new();
+        app.set_cancel_token(token.clone());
+        token.cancel();
+
+        assert_eq!(
+            app.patch_option(PatchTarget::NodeInspect),
+            Err(PatcherError::Cancelled)
+        );
+        assert_eq!(
+            app.apply_fuses_only(&HardeningPolicy::from_preset(Preset::Minimal)),
+            Err(PatcherError::Cancelled)
+        );
+        assert_eq!(bytes, original);
+    }
+}
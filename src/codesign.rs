@@ -0,0 +1,97 @@
+//! Signing patched macOS applications again with Apple's `codesign` tool.
+
+use crate::{ElectronApp, PatcherError, SourceError};
+use std::{path::Path, process::Command};
+
+/// The location of the `codesign` tool on every macOS installation.
+const CODESIGN: &str = "/usr/bin/codesign";
+
+/// Signs the file at `path` with `identity` using the `codesign` program, replacing any existing signature.
+fn run_codesign(program: &Path, path: &Path, identity: &str) -> Result<(), PatcherError> {
+    let output = Command::new(program)
+        .arg("--force")
+        .arg("--sign")
+        .arg(identity)
+        .arg(path)
+        .output()
+        .map_err(|e| PatcherError::io(program, &e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(PatcherError::CodesignFailed {
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_string(),
+        })
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Writes the application [back to its file](Self::write_back) and signs it again with `codesign`.
+    ///
+    /// Patching a signed macOS binary [invalidates its signature](Self::has_code_signature), so this is usually the
+    /// last step of hardening one. The file is signed with `/usr/bin/codesign --force --sign <identity>`, where
+    /// `identity` is anything `codesign` accepts, such as the name of a certificate in the keychain or `-` for an
+    /// ad-hoc signature. Entitlements and other signing options aren't preserved, so applications that need them
+    /// should be signed by their own build tooling instead.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [write_back](Self::write_back), [Io](PatcherError::Io) if
+    /// `codesign` couldn't be run, such as on platforms other than macOS, or
+    /// [CodesignFailed](PatcherError::CodesignFailed) if it failed to sign the file. The modifications have been
+    /// written to the file when signing fails.
+    pub fn resign_with_codesign(&self, identity: &str) -> Result<(), SourceError> {
+        let path = self.path.as_ref().ok_or(PatcherError::NoPath)?;
+        self.write_back()?;
+        Ok(run_codesign(Path::new(CODESIGN), path, identity)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn apps_without_files_cant_be_signed() {
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert!(matches!(
+            app.resign_with_codesign("-"),
+            Err(SourceError::Patcher(PatcherError::NoPath))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn codesign_errors_are_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("electron-hardener-codesign-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("codesign");
+        fs::write(
+            &program,
+            "#!/bin/sh\necho \"$1 $2 $3\" >&2\necho \"no identity found\" >&2\nexit 1\n",
+        )
+        .unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let result = run_codesign(&program, &dir.join("app"), "Developer ID");
+        let missing = run_codesign(&dir.join("missing"), &dir.join("app"), "-");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            result,
+            Err(PatcherError::CodesignFailed {
+                status: Some(1),
+                stderr: "--force --sign Developer ID\nno identity found".to_string(),
+            })
+        );
+        assert!(matches!(missing, Err(PatcherError::Io { .. })));
+    }
+}
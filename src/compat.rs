@@ -0,0 +1,266 @@
+//! Checking that a policy applies cleanly to the fuse wire layouts of several versions of Electron.
+//!
+//! Electron adds fuses to the end of its fuse wire and new command line switches over time, so a policy written for a
+//! recent version can refer to things that older builds don't have. [compat_tests] applies a policy to a fixture for
+//! each [layout](CompatLayout) and reports which parts of it don't apply.
+//!
+//! The fixtures are shipped in `examples/compat` and are generated by [CompatLayout::build_fixture], so they never
+//! change unless the layouts do.
+
+use crate::{
+    patcher::{PatchOutcome, PatchTarget},
+    BinaryError, ElectronApp, ElectronVersion, Fuse, HardeningPolicy, PatcherError,
+};
+
+/// The fuse wire layout and patch targets of a version of Electron.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatLayout {
+    /// The version of Electron this layout represents.
+    pub electron_version: ElectronVersion,
+    /// How many fuses the layout's wire contains.
+    pub fuse_count: usize,
+    fixture: &'static [u8],
+}
+
+impl CompatLayout {
+    /// Electron 12, the first version with fuses. Its wire only contains the
+    /// [RunAsNode](Fuse::RunAsNode) and [EncryptedCookies](Fuse::EncryptedCookies) fuses.
+    pub const ELECTRON_12: CompatLayout = CompatLayout {
+        electron_version: ElectronVersion::new(12, 2, 3),
+        fuse_count: 2,
+        fixture: include_bytes!("../examples/compat/electron_12.bin"),
+    };
+
+    /// Electron 20, whose wire ends with the [OnlyLoadAppFromAsar](Fuse::OnlyLoadAppFromAsar) fuse.
+    pub const ELECTRON_20: CompatLayout = CompatLayout {
+        electron_version: ElectronVersion::new(20, 3, 12),
+        fuse_count: 6,
+        fixture: include_bytes!("../examples/compat/electron_20.bin"),
+    };
+
+    /// The most recent layout, containing every fuse and patch target known by this library.
+    pub const CURRENT: CompatLayout = CompatLayout {
+        electron_version: ElectronVersion::new(33, 2, 0),
+        fuse_count: Fuse::ALL.len(),
+        fixture: include_bytes!("../examples/compat/electron_current.bin"),
+    };
+
+    /// Every layout, from oldest to newest.
+    pub const ALL: &'static [CompatLayout] = &[Self::ELECTRON_12, Self::ELECTRON_20, Self::CURRENT];
+
+    /// Returns the shipped fixture for this layout.
+    pub fn fixture(&self) -> &'static [u8] {
+        self.fixture
+    }
+
+    /// Builds the fixture for this layout from scratch.
+    ///
+    /// The fixture contains a fuse wire with every fuse set to its insecure value, the version string Electron embeds
+    /// in its user agent, and every patch target that's available in the layout's version of Electron.
+    pub fn build_fixture(&self) -> Vec<u8> {
+        let mut fixture = Fuse::SENTINEL.to_vec();
+        fixture.push(Fuse::EXPECTED_VERSION);
+        fixture.push(self.fuse_count as u8);
+        fixture.extend((0..self.fuse_count).map(|pos| {
+            let fuse = Fuse::ALL.iter().find(|fuse| fuse.schema_pos() == pos);
+            match fuse {
+                Some(fuse) if fuse.secure_value() => Fuse::DISABLED,
+                _ => Fuse::ENABLED,
+            }
+        }));
+
+        fixture.extend_from_slice(format!("\0Electron/{}\0", self.electron_version).as_bytes());
+
+        for target in PatchTarget::ALL {
+            if target.is_available_in(self.electron_version) {
+                fixture.extend_from_slice(&target.embedded());
+            }
+        }
+
+        fixture
+    }
+}
+
+/// A part of a policy that doesn't apply to a [layout](CompatLayout).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Inapplicable {
+    /// The fuse isn't part of the layout's fuse wire.
+    MissingFuse(Fuse),
+    /// The patch target doesn't exist in the layout's version of Electron.
+    UnavailableTarget(PatchTarget),
+}
+
+/// The result of applying a policy to the fixture of a [layout](CompatLayout).
+#[derive(Debug, PartialEq)]
+pub struct CompatResult {
+    /// The layout the policy was applied to.
+    pub layout: CompatLayout,
+    /// The parts of the policy that don't apply to the layout, in policy order.
+    pub inapplicable: Vec<Inapplicable>,
+    /// The error returned when the rest of the policy was applied, if there was one.
+    pub error: Option<PatcherError>,
+}
+
+impl CompatResult {
+    /// Returns `true` if every part of the policy applied to the layout without an error.
+    pub fn applies_cleanly(&self) -> bool {
+        self.inapplicable.is_empty() && self.error.is_none()
+    }
+}
+
+/// Applies a policy to the fixture of every [layout](CompatLayout), reporting which parts of it don't apply to each.
+///
+/// Targets are classified the same way [apply_policy](ElectronApp::apply_policy) classifies them, so a target is
+/// only inapplicable when it's [unavailable](PatchOutcome::Unavailable) in the layout's version of Electron. Fuses that
+/// aren't in a layout's wire are left out when the rest of the policy is applied, since they would stop it from being
+/// applied at all.
+///
+/// This is meant to be called from the tests of crates that maintain their own policies, for example:
+///
+/// ```
+/// use electron_hardener::{compat::compat_tests, HardeningPolicy, Preset};
+///
+/// let policy = HardeningPolicy::from_preset(Preset::Recommended);
+/// for result in compat_tests(&policy) {
+///     assert!(result.error.is_none(), "{:?}", result);
+/// }
+/// ```
+pub fn compat_tests(policy: &HardeningPolicy) -> Vec<CompatResult> {
+    CompatLayout::ALL
+        .iter()
+        .map(|&layout| {
+            let mut bytes = layout.fixture().to_vec();
+            let mut app = match ElectronApp::from_bytes(&mut bytes) {
+                Ok(app) => app,
+                Err(e) => {
+                    return CompatResult {
+                        layout,
+                        inapplicable: Vec::new(),
+                        error: Some(e),
+                    }
+                }
+            };
+
+            let missing: Vec<Fuse> = policy
+                .fuses()
+                .iter()
+                .map(|&(fuse, _)| fuse)
+                .filter(|&fuse| {
+                    matches!(
+                        app.get_fuse_status(fuse),
+                        Err(PatcherError::Binary(BinaryError::FuseDoesNotExist(_)))
+                    )
+                })
+                .collect();
+            let mut inapplicable: Vec<Inapplicable> = missing
+                .iter()
+                .map(|&fuse| Inapplicable::MissingFuse(fuse))
+                .collect();
+
+            let error = match app.apply_policy(&policy.without_fuses(&missing)) {
+                Ok(report) => {
+                    inapplicable.extend(
+                        report
+                            .targets
+                            .iter()
+                            .filter(|(_, outcome)| *outcome == PatchOutcome::Unavailable)
+                            .map(|&(target, _)| Inapplicable::UnavailableTarget(target)),
+                    );
+                    None
+                }
+                Err(e) => Some(e),
+            };
+
+            CompatResult {
+                layout,
+                inapplicable,
+                error,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{patcher::ElectronOption, Preset};
+    use std::{env, fs};
+
+    #[test]
+    fn fixtures_match_the_builder() {
+        let paths = [
+            "examples/compat/electron_12.bin",
+            "examples/compat/electron_20.bin",
+            "examples/compat/electron_current.bin",
+        ];
+
+        // Run with this set to regenerate the fixtures after changing a layout.
+        let update = env::var_os("ELECTRON_HARDENER_UPDATE_FIXTURES").is_some();
+
+        for (layout, path) in CompatLayout::ALL.iter().zip(paths.iter()) {
+            let built = layout.build_fixture();
+            if update {
+                fs::write(path, &built).unwrap();
+            } else {
+                assert!(
+                    layout.fixture() == built.as_slice(),
+                    "{} is out of date",
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixtures_have_their_layouts() {
+        for layout in CompatLayout::ALL {
+            let mut bytes = layout.fixture().to_vec();
+            let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+            assert_eq!(app.wires()[0].range().len(), layout.fuse_count);
+            assert_eq!(app.electron_version(), Some(layout.electron_version));
+            for target in PatchTarget::ALL {
+                assert_eq!(
+                    app.scan(*target).is_some(),
+                    target.is_available_in(layout.electron_version),
+                    "{:?}",
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inapplicable_entries_are_reported_per_layout() {
+        let results = compat_tests(&HardeningPolicy::from_preset(Preset::Paranoid));
+        assert_eq!(results.len(), CompatLayout::ALL.len());
+
+        let remote_allow_origins =
+            Inapplicable::UnavailableTarget(ElectronOption::RemoteAllowOrigins.into());
+        assert_eq!(
+            results[0].inapplicable,
+            vec![
+                Inapplicable::MissingFuse(Fuse::NodeOptions),
+                Inapplicable::MissingFuse(Fuse::NodeCliInspect),
+                Inapplicable::MissingFuse(Fuse::OnlyLoadAppFromAsar),
+                remote_allow_origins,
+            ]
+        );
+        assert_eq!(results[1].inapplicable, vec![remote_allow_origins]);
+        assert!(results[2].applies_cleanly());
+        assert!(results.iter().all(|result| result.error.is_none()));
+    }
+
+    #[test]
+    fn errors_are_reported_per_layout() {
+        let policy = HardeningPolicy::from_preset(Preset::Recommended).expect_min_patches(100);
+
+        for result in compat_tests(&policy) {
+            assert!(matches!(
+                result.error,
+                Some(PatcherError::TooFewPatches { expected: 100, .. })
+            ));
+        }
+    }
+}
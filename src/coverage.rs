@@ -0,0 +1,129 @@
+//! Checks of which patch targets' search patterns still match in an application binary.
+//!
+//! This is meant for maintaining the patterns themselves. Before supporting a new version of Electron, running it
+//! against one of its binaries shows which targets were probably renamed or removed upstream.
+
+use crate::{patcher::PatchTarget, ElectronVersion};
+
+/// How often the search patterns of a patch target matched in a binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TargetCoverage {
+    /// The target that was searched for.
+    pub target: PatchTarget,
+    /// How many times the target's usual spelling matched.
+    pub canonical_matches: usize,
+    /// How many times the target's fallback pattern matched, or `None` if it doesn't have one.
+    pub fallback_matches: Option<usize>,
+    /// Whether the target is expected to be in the binary, based on its detected Electron version.
+    ///
+    /// This is always `true` when the version couldn't be detected.
+    pub expected: bool,
+}
+
+impl TargetCoverage {
+    /// Returns `true` if none of the target's patterns matched.
+    pub fn is_missing(&self) -> bool {
+        self.canonical_matches == 0 && self.fallback_matches.unwrap_or(0) == 0
+    }
+}
+
+/// Which patch targets' search patterns match in an application binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CoverageReport {
+    /// The version of Electron the binary was built with, if it could be detected.
+    pub electron_version: Option<ElectronVersion>,
+    /// The coverage of every patch target known by this library.
+    pub targets: Vec<TargetCoverage>,
+}
+
+impl CoverageReport {
+    /// Counts the matches of every pattern of every known patch target in the binary.
+    ///
+    /// The binary should be unpatched, since targets that were already patched out are reported as missing.
+    pub fn scan(binary: &[u8]) -> Self {
+        let electron_version = ElectronVersion::detect(binary);
+
+        let targets = PatchTarget::ALL
+            .iter()
+            .map(|&target| {
+                let (canonical_matches, fallback_matches) = target.count_matches(binary);
+                TargetCoverage {
+                    target,
+                    canonical_matches,
+                    fallback_matches,
+                    expected: !matches!(electron_version, Some(v) if !target.is_available_in(v)),
+                }
+            })
+            .collect();
+
+        Self {
+            electron_version,
+            targets,
+        }
+    }
+
+    /// Returns the targets that are expected in the binary but that none of the patterns matched.
+    ///
+    /// These are the ones whose patterns probably need to be updated.
+    pub fn missing(&self) -> impl Iterator<Item = &TargetCoverage> {
+        self.targets
+            .iter()
+            .filter(|coverage| coverage.expected && coverage.is_missing())
+    }
+
+    /// Returns the targets that none of the patterns matched, but that aren't expected in the binary's version of
+    /// Electron anyway.
+    pub fn not_expected(&self) -> impl Iterator<Item = &TargetCoverage> {
+        self.targets
+            .iter()
+            .filter(|coverage| !coverage.expected && coverage.is_missing())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patcher::ElectronOption;
+
+    const FLAG_BYTES: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+
+    #[test]
+    fn every_target_is_covered() {
+        let report = CoverageReport::scan(FLAG_BYTES);
+
+        assert_eq!(report.targets.len(), PatchTarget::ALL.len());
+        assert_eq!(report.missing().count(), 0);
+        assert_eq!(report.not_expected().count(), 0);
+
+        let node_inspect = report
+            .targets
+            .iter()
+            .find(|coverage| coverage.target == PatchTarget::NodeInspect)
+            .unwrap();
+        assert_eq!(node_inspect.fallback_matches, Some(0));
+    }
+
+    #[test]
+    fn missing_targets_are_split_by_version() {
+        let mut bytes = b"\0Electron/20.3.12\0".to_vec();
+        bytes.extend_from_slice(b"\0js-flags\0\0js-flags\0");
+
+        let report = CoverageReport::scan(&bytes);
+        let js_flags = report
+            .targets
+            .iter()
+            .find(|coverage| coverage.target == ElectronOption::JsFlags.into())
+            .unwrap();
+        assert_eq!(js_flags.canonical_matches, 2);
+
+        assert!(report
+            .missing()
+            .any(|coverage| coverage.target == PatchTarget::NodeInspect));
+        assert!(report
+            .not_expected()
+            .any(|coverage| coverage.target == PatchTarget::NodeInspectWait));
+        assert!(report.missing().all(|coverage| coverage.expected));
+    }
+}
@@ -1,20 +1,77 @@
-use std::fmt;
+use std::{fmt, ops::Range};
+
+/// An error that a string didn't name a [fuse](crate::Fuse) or [Electron option](crate::patcher::ElectronOption)
+/// known by this library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownNameError {
+    kind: &'static str,
+    name: String,
+    known: Vec<&'static str>,
+}
+
+impl UnknownNameError {
+    pub(crate) fn new(kind: &'static str, name: &str, known: Vec<&'static str>) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            known,
+        }
+    }
+
+    /// Returns the name that couldn't be parsed.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for UnknownNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} isn't a known {}. Expected one of: {}",
+            self.name,
+            self.kind,
+            self.known.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownNameError {}
 
 /// An error that the provided binary didn't contain the required information for
 /// an operation on it.
 #[derive(Debug, PartialEq)]
 #[non_exhaustive]
 pub enum BinaryError {
+    /// The provided binary was empty.
+    ///
+    /// This usually means the file it was read from is empty, such as when a download failed.
+    EmptyInput,
+    /// The provided binary was too short to contain a fuse wire, so it can't be an Electron application.
+    InputTooSmall {
+        /// The length of the binary.
+        len: usize,
+        /// The length of the shortest binary that could contain a fuse wire.
+        minimum: usize,
+    },
     /// No [sentinel byte marker]() could be found in the binary.
     ///
     /// [sentinel byte marker]: https://www.electronjs.org/docs/tutorial/fuses#quick-glossary
     NoSentinel,
     /// No fuse version was found in the binary.
-    NoFuseVersion,
+    NoFuseVersion {
+        /// The position of the sentinel that wasn't followed by a version.
+        sentinel: usize,
+    },
     /// The length of the fuse was not found in the binary.
-    NoFuseLength,
+    NoFuseLength {
+        /// The position of the sentinel that wasn't followed by a length.
+        sentinel: usize,
+    },
     /// The requested fuse to be modifed wasn't present in the fuse wire.
     FuseDoesNotExist(crate::Fuse),
+    /// There is no fuse wire at the requested index.
+    FuseWireDoesNotExist(usize),
     /// An unknown fuse status was encountered.
     ///
     /// The Electron project may have made a breaking change to the fuse format if
@@ -24,38 +81,165 @@ pub enum BinaryError {
         fuse: crate::Fuse,
         /// The value found querying the fuse.
         value: u8,
+        /// The position of the fuse's byte in the binary.
+        offset: usize,
+    },
+    /// The fuse wire's length was zero or unreasonably long.
+    ImplausibleFuseLength(u8),
+    /// The fuse wire's length extended past the end of the binary, usually because the binary was truncated.
+    TruncatedFuseWire {
+        /// The length of the fuse wire.
+        len: usize,
+        /// How many bytes of the wire are in the binary.
+        available: usize,
+    },
+    /// The sentinel was found multiple times, but none of the occurrences were followed by a valid fuse wire.
+    NoValidFuseWire {
+        /// How many occurrences of the sentinel were considered.
+        candidates: usize,
+    },
+    /// The sentinel was followed by a valid fuse wire more than once, when only one was expected.
+    ///
+    /// Picking one of them could modify the wrong bytes.
+    AmbiguousSentinel {
+        /// How many valid fuse wires were found.
+        count: usize,
+    },
+    /// A fuse wire was found, but it's stored in a layout that this library can't modify.
+    UnsupportedWireLayout {
+        /// How the fuse wire is stored.
+        layout: crate::fuses::WireLayout,
+        /// The position of the fuse wire's sentinel in the binary.
+        offset: usize,
+    },
+    /// The application's [fuse wires](crate::ElectronApp::wires) have different statuses for a fuse.
+    ///
+    /// This usually means only one architecture of a universal macOS binary was modified.
+    FuseWiresDisagree {
+        /// The fuse that was read.
+        fuse: crate::Fuse,
+        /// The status of the fuse in each wire.
+        statuses: Vec<crate::fuses::FuseStatus>,
+    },
+    /// The fuse wire contained a byte that isn't a valid fuse status.
+    MalformedFuseWire {
+        /// The position of the byte in the fuse wire.
+        position: usize,
+        /// The invalid value.
+        value: u8,
+        /// The position of the byte in the binary.
+        offset: usize,
+    },
+    /// A [raw fuse](crate::ElectronApp::raw_fuse) was requested at a position past the end of the fuse wire.
+    FuseIndexOutOfRange {
+        /// The requested position.
+        index: usize,
+        /// The length of the fuse wire.
+        len: usize,
     },
+    #[cfg(feature = "patch")]
     #[allow(deprecated)]
     /// The Node.JS command line flag attempted to be disabled wasn't present.
     NodeJsFlagNotPresent(crate::patcher::NodeJsCommandLineFlag),
     /// The Electron command line flag attempted to be disabled wasn't present.
     ElectronOptionNotPresent(crate::patcher::ElectronOption),
+    #[cfg(feature = "patch")]
     #[allow(deprecated)]
     /// The Node.JS debugging message attempted to be disabled wasn't present.
     MessageNotPresent(crate::patcher::DevToolsMessage),
+    /// The patch target attempted to be disabled wasn't present.
+    TargetNotPresent(crate::patcher::PatchTarget),
 }
 
 impl fmt::Display for BinaryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            BinaryError::EmptyInput => f.write_str("The provided binary was empty"),
+            BinaryError::InputTooSmall { len, minimum } => write!(
+                f,
+                "The provided binary was only {} bytes long, but it needs at least {} to contain a fuse wire",
+                len, minimum
+            ),
             BinaryError::NoSentinel => f.write_str("No fuse sentinel found"),
-            BinaryError::NoFuseVersion => f.write_str("Fuse had no version present"),
-            BinaryError::NoFuseLength => f.write_str("Fuse had no length specified"),
+            BinaryError::NoFuseVersion { sentinel } => write!(
+                f,
+                "Fuse had no version present after the sentinel at offset {}",
+                sentinel
+            ),
+            BinaryError::NoFuseLength { sentinel } => write!(
+                f,
+                "Fuse had no length specified after the sentinel at offset {}",
+                sentinel
+            ),
             BinaryError::FuseDoesNotExist(fuse) => write!(f, "The {:?} fuse wasn't present", fuse),
-            BinaryError::UnknownFuse { fuse, value } => write!(
+            BinaryError::FuseWireDoesNotExist(index) => {
+                write!(f, "There is no fuse wire at index {}", index)
+            }
+            BinaryError::UnknownFuse {
+                fuse,
+                value,
+                offset,
+            } => write!(
+                f,
+                "The {:?} fuse returned an unknown value of '{}' at offset {}",
+                fuse, value, offset
+            ),
+            BinaryError::ImplausibleFuseLength(len) => {
+                write!(f, "The fuse wire had an implausible length of {}", len)
+            }
+            BinaryError::TruncatedFuseWire { len, available } => write!(
+                f,
+                "The fuse wire should be {} bytes long, but the binary ends after {} of them",
+                len, available
+            ),
+            BinaryError::FuseWiresDisagree { fuse, statuses } => write!(
+                f,
+                "The {:?} fuse has different statuses in each fuse wire: {:?}",
+                fuse, statuses
+            ),
+            BinaryError::NoValidFuseWire { candidates } => write!(
+                f,
+                "None of the {} fuse sentinels found were followed by a valid fuse wire",
+                candidates
+            ),
+            BinaryError::AmbiguousSentinel { count } => write!(
+                f,
+                "The fuse sentinel was followed by a valid fuse wire {} times, so it's unclear which to use",
+                count
+            ),
+            BinaryError::UnsupportedWireLayout { layout, offset } => write!(
                 f,
-                "The {:?} fuse returned an unknown value of '{}'",
-                fuse, value
+                "Found a fuse wire at offset {} with an unsupported {:?} layout",
+                offset, layout
             ),
+            BinaryError::MalformedFuseWire {
+                position,
+                value,
+                offset,
+            } => write!(
+                f,
+                "The fuse wire contained an invalid value of '{}' at position {} (offset {})",
+                value, position, offset
+            ),
+            BinaryError::FuseIndexOutOfRange { index, len } => write!(
+                f,
+                "There is no fuse at position {} of a fuse wire with {} fuses",
+                index, len
+            ),
+            #[cfg(feature = "patch")]
             BinaryError::NodeJsFlagNotPresent(flag) => {
                 write!(f, "The {:?} debugging flag wasn't present", flag)
             }
             BinaryError::ElectronOptionNotPresent(opt) => {
                 write!(f, "The Electron option for {:?} wasn't present", opt)
             }
+            #[cfg(feature = "patch")]
             BinaryError::MessageNotPresent(msg) => {
                 write!(f, "The DevTools message {:?} wasn't present", msg)
             }
+            BinaryError::TargetNotPresent(target) => {
+                write!(f, "The patch target {} wasn't present", target)
+            }
         }
     }
 }
@@ -79,6 +263,104 @@ pub enum PatcherError {
     ///
     /// This is an error because modifying a removed fuse has no effect, so this may lead to unexpected behavior.
     RemovedFuse(crate::Fuse),
+    /// An attempt was made to modify a [raw fuse](crate::ElectronApp::set_raw_fuse) which has been removed from the
+    /// Electron schema.
+    RemovedRawFuse(usize),
+    /// A write to a fuse wire that this library should never make was attempted.
+    ///
+    /// Fuses are only ever changed between enabled and disabled, nothing else is written inside a fuse wire, and
+    /// fuses are never written outside of one. This indicates a bug in this library.
+    IllegalFuseWrite {
+        /// The position of the byte in the binary.
+        offset: usize,
+        /// The value the byte had before the write.
+        from: u8,
+        /// The value that would have been written.
+        to: u8,
+    },
+    /// Two patches would have modified the same bytes in the binary.
+    ///
+    /// Nothing is written to the binary when this is returned.
+    OverlappingPatches {
+        /// The patch target that starts first in the binary and the bytes it would modify.
+        first: (crate::patcher::PatchTarget, Range<usize>),
+        /// The patch target that overlaps it and the bytes it would modify.
+        second: (crate::patcher::PatchTarget, Range<usize>),
+    },
+    /// A saved [undo journal](crate::journal::UndoJournal) couldn't be parsed.
+    InvalidUndoJournal {
+        /// The line of the journal that was invalid, starting from 1.
+        line: usize,
+    },
+    /// The binary didn't contain the modifications recorded in an [undo journal](crate::journal::UndoJournal).
+    UndoMismatch {
+        /// The position of the first modification that didn't match.
+        offset: usize,
+    },
+    /// The operation was stopped by a [cancel token](crate::CancelToken).
+    ///
+    /// Nothing is written to the binary when this is returned.
+    Cancelled,
+    /// A patch target was found inside of an executable section of the binary, so patching it would corrupt code.
+    ///
+    /// This usually means the target's pattern matched something other than the option. Nothing is written to the
+    /// binary when this is returned.
+    PatchInCodeSection {
+        /// The patch target that was found.
+        target: crate::patcher::PatchTarget,
+        /// The name of the executable section.
+        section: String,
+        /// The bytes the patch would have modified.
+        range: Range<usize>,
+    },
+    /// Fewer of a policy's targets were found than it [expected](crate::HardeningPolicy::expect_min_patches).
+    ///
+    /// This usually means the policy was applied to the wrong binary. Nothing is written to the binary when this is
+    /// returned.
+    TooFewPatches {
+        /// The minimum number of targets the policy expected to patch.
+        expected: usize,
+        /// The number of targets that were found.
+        found: usize,
+    },
+    /// An application's file couldn't be [read](crate::ElectronApp::from_path) or
+    /// [written](crate::ElectronApp::write_to_path).
+    ///
+    /// The error is kept as its kind and message so that patcher errors can still be compared.
+    Io {
+        /// The file that was being accessed.
+        path: std::path::PathBuf,
+        /// The kind of the IO error.
+        kind: std::io::ErrorKind,
+        /// The IO error's message.
+        message: String,
+    },
+    /// The application can't be [written back](crate::ElectronApp::write_back) because it wasn't
+    /// [read from a file](crate::ElectronApp::from_path).
+    NoPath,
+    /// Modifying the application would invalidate its code signature, which
+    /// [isn't allowed](crate::ElectronApp::deny_signature_invalidation).
+    ///
+    /// Nothing is written to the binary when this is returned.
+    SignatureInvalidated,
+    /// `codesign` failed to [sign the application again](crate::ElectronApp::resign_with_codesign).
+    #[cfg(feature = "codesign")]
+    CodesignFailed {
+        /// The exit code of `codesign`, if it exited normally.
+        status: Option<i32>,
+        /// What `codesign` wrote to its standard error.
+        stderr: String,
+    },
+}
+
+impl PatcherError {
+    pub(crate) fn io(path: &std::path::Path, error: &std::io::Error) -> Self {
+        PatcherError::Io {
+            path: path.to_path_buf(),
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
 }
 
 impl From<BinaryError> for PatcherError {
@@ -101,8 +383,328 @@ impl fmt::Display for PatcherError {
                 "Failed to modify the {:?} fuse because it is marked as removed",
                 fuse
             ),
+            PatcherError::RemovedRawFuse(index) => write!(
+                f,
+                "Failed to modify the fuse at position {} because it is marked as removed",
+                index
+            ),
+            PatcherError::IllegalFuseWrite { offset, from, to } => write!(
+                f,
+                "Refused to change the fuse wire byte at offset {} from {:?} to {:?}",
+                offset, *from as char, *to as char
+            ),
+            PatcherError::OverlappingPatches { first, second } => write!(
+                f,
+                "The {} patch at {:?} overlaps the {} patch at {:?}",
+                first.0, first.1, second.0, second.1
+            ),
+            PatcherError::InvalidUndoJournal { line } => {
+                write!(f, "The undo journal was invalid at line {}", line)
+            }
+            PatcherError::UndoMismatch { offset } => write!(
+                f,
+                "The binary doesn't match the undo journal at offset {}",
+                offset
+            ),
+            PatcherError::Cancelled => write!(f, "The operation was cancelled"),
+            PatcherError::PatchInCodeSection {
+                target,
+                section,
+                range,
+            } => write!(
+                f,
+                "The {} patch at {:?} is inside the executable section {}",
+                target, range, section
+            ),
+            PatcherError::TooFewPatches { expected, found } => write!(
+                f,
+                "Expected to patch at least {} targets, but only {} were found",
+                expected, found
+            ),
+            PatcherError::Io { path, message, .. } => {
+                write!(f, "Failed to access {}: {}", path.display(), message)
+            }
+            PatcherError::NoPath => write!(
+                f,
+                "The application wasn't read from a file, so it can't be written back"
+            ),
+            PatcherError::SignatureInvalidated => write!(
+                f,
+                "Modifying the application would invalidate its code signature"
+            ),
+            #[cfg(feature = "codesign")]
+            PatcherError::CodesignFailed { stderr, .. } => {
+                write!(f, "Failed to sign the application: {}", stderr)
+            }
         }
     }
 }
 
 impl std::error::Error for PatcherError {}
+
+/// An error that a hand-written [policy](crate::HardeningPolicy) contradicts itself, returned when it's
+/// [built](crate::HardeningPolicy::build).
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PolicyError {
+    /// A fuse is set to both enabled and disabled.
+    ConflictingFuse(crate::Fuse),
+    /// A target is patched with more than one severity.
+    ConflictingTarget {
+        /// The target that's patched more than once.
+        target: crate::patcher::PatchTarget,
+        /// The severities it was given, in policy order.
+        severities: Vec<crate::Severity>,
+    },
+}
+
+#[cfg(feature = "patch")]
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::ConflictingFuse(fuse) => {
+                write!(f, "The {} fuse is set to both enabled and disabled", fuse)
+            }
+            PolicyError::ConflictingTarget { target, severities } => {
+                let severities: Vec<String> = severities.iter().map(|s| s.to_string()).collect();
+                write!(
+                    f,
+                    "The {} target is patched with conflicting severities: {}",
+                    target,
+                    severities.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+impl std::error::Error for PolicyError {}
+
+/// An error that can result from hardening a [binary source](crate::source::BinarySource).
+#[cfg(feature = "patch")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SourceError {
+    /// The source couldn't be read or written.
+    Io(std::io::Error),
+    /// The binary couldn't be hardened.
+    Patcher(PatcherError),
+    /// The binary was hardened, but couldn't be written because of how it's stored.
+    ///
+    /// Nothing is written to the binary when this is returned.
+    ReadOnly {
+        /// The binary that couldn't be written.
+        path: std::path::PathBuf,
+        /// Why it couldn't be written.
+        reason: ReadOnlyReason,
+        /// The error returned by the operating system.
+        error: std::io::Error,
+    },
+}
+
+/// Why a binary couldn't be written, as reported by [ReadOnly](SourceError::ReadOnly).
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReadOnlyReason {
+    /// The binary is on a filesystem that's mounted read-only.
+    ReadOnlyFilesystem,
+    /// The binary has an immutable flag set, such as with `chflags uchg` on macOS or `chattr +i` on Linux.
+    Immutable,
+    /// The current user isn't allowed to write the binary or the directory it's in, or the operating system refused
+    /// to let it be modified.
+    PermissionDenied,
+}
+
+#[cfg(feature = "patch")]
+impl ReadOnlyReason {
+    /// Returns how the condition can usually be fixed.
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            ReadOnlyReason::ReadOnlyFilesystem => {
+                "Copy the application to a writable location, or remount its filesystem as writable"
+            }
+            ReadOnlyReason::Immutable => {
+                "Clear the flag with `chflags nouchg` on macOS or `chattr -i` on Linux, which may need administrator privileges"
+            }
+            ReadOnlyReason::PermissionDenied => {
+                "Run as a user that can write both the binary and its directory, such as with `sudo`. On macOS, the terminal may also need to be allowed to modify applications under Privacy & Security > App Management"
+            }
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+impl fmt::Display for ReadOnlyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReadOnlyReason::ReadOnlyFilesystem => "it's on a read-only filesystem",
+            ReadOnlyReason::Immutable => "it's marked as immutable",
+            ReadOnlyReason::PermissionDenied => "permission was denied",
+        })
+    }
+}
+
+#[cfg(feature = "patch")]
+impl SourceError {
+    /// Classifies an error from writing the binary at `path`, returning [ReadOnly](SourceError::ReadOnly) if the
+    /// reason it couldn't be written can be told, or [Io](SourceError::Io) otherwise.
+    ///
+    /// This is used by [harden_file](crate::harden_file), and can be used by tools that write binaries themselves.
+    pub fn from_write_error(path: &std::path::Path, error: std::io::Error) -> Self {
+        // EROFS has the same value on Linux and macOS.
+        const EROFS: i32 = 30;
+
+        // EPERM is returned for immutable files, but also for many other reasons, such as macOS's App Management
+        // protecting applications, so the flags are checked instead of trusting the error.
+        let reason = match error.raw_os_error() {
+            Some(EROFS) if cfg!(unix) => ReadOnlyReason::ReadOnlyFilesystem,
+            _ if is_immutable(path) => ReadOnlyReason::Immutable,
+            _ if error.kind() == std::io::ErrorKind::PermissionDenied => {
+                ReadOnlyReason::PermissionDenied
+            }
+            _ => return SourceError::Io(error),
+        };
+
+        SourceError::ReadOnly {
+            path: path.to_path_buf(),
+            reason,
+            error,
+        }
+    }
+}
+
+/// Returns whether a file has one of macOS's immutable flags set.
+#[cfg(all(feature = "patch", target_os = "macos"))]
+fn is_immutable(path: &std::path::Path) -> bool {
+    use std::os::macos::fs::MetadataExt;
+
+    const UF_IMMUTABLE: u32 = 0x0000_0002;
+    const SF_IMMUTABLE: u32 = 0x0002_0000;
+
+    std::fs::metadata(path)
+        .map(|metadata| metadata.st_flags() & (UF_IMMUTABLE | SF_IMMUTABLE) != 0)
+        .unwrap_or(false)
+}
+
+/// Returns whether a file has Linux's immutable or append-only attribute set.
+#[cfg(all(feature = "patch", target_os = "linux"))]
+fn is_immutable(path: &std::path::Path) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    const FS_IMMUTABLE_FL: libc::c_int = 0x0000_0010;
+    const FS_APPEND_FL: libc::c_int = 0x0000_0020;
+
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+
+    let mut flags: libc::c_int = 0;
+    // SAFETY: The descriptor is valid while `file` is alive, and the kernel writes an int to `flags`.
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+    result == 0 && flags & (FS_IMMUTABLE_FL | FS_APPEND_FL) != 0
+}
+
+/// Returns whether a file has an immutable flag set, which can't be checked on other platforms.
+#[cfg(all(feature = "patch", not(any(target_os = "macos", target_os = "linux"))))]
+fn is_immutable(_path: &std::path::Path) -> bool {
+    false
+}
+
+#[cfg(feature = "patch")]
+impl From<std::io::Error> for SourceError {
+    fn from(e: std::io::Error) -> Self {
+        SourceError::Io(e)
+    }
+}
+
+#[cfg(feature = "patch")]
+impl From<PatcherError> for SourceError {
+    fn from(e: PatcherError) -> Self {
+        SourceError::Patcher(e)
+    }
+}
+
+#[cfg(feature = "patch")]
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io(e) => write!(f, "Failed to access the binary: {}", e),
+            SourceError::Patcher(e) => write!(f, "{}", e),
+            SourceError::ReadOnly { path, reason, .. } => write!(
+                f,
+                "Can't write {} because {}. {}",
+                path.display(),
+                reason,
+                reason.remediation()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+impl std::error::Error for SourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SourceError::Io(e) => Some(e),
+            SourceError::Patcher(e) => Some(e),
+            SourceError::ReadOnly { error, .. } => Some(error),
+        }
+    }
+}
+
+/// An error that can result from writing to or verifying an [audit log](crate::audit::AuditLog).
+#[cfg(feature = "audit")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AuditError {
+    /// The audit log couldn't be read or written.
+    Io(std::io::Error),
+    /// An entry in the audit log couldn't be parsed.
+    MalformedEntry {
+        /// The line of the invalid entry, starting from 1.
+        line: usize,
+    },
+    /// An entry's hash of the entry before it didn't match, so the log was modified after being written.
+    BrokenChain {
+        /// The line of the first entry that didn't match, starting from 1.
+        line: usize,
+    },
+}
+
+#[cfg(feature = "audit")]
+impl From<std::io::Error> for AuditError {
+    fn from(e: std::io::Error) -> Self {
+        AuditError::Io(e)
+    }
+}
+
+#[cfg(feature = "audit")]
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditError::Io(e) => write!(f, "Failed to access the audit log: {}", e),
+            AuditError::MalformedEntry { line } => {
+                write!(f, "The audit log entry at line {} was malformed", line)
+            }
+            AuditError::BrokenChain { line } => write!(
+                f,
+                "The audit log entry at line {} doesn't match the entry before it",
+                line
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "audit")]
+impl std::error::Error for AuditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuditError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Detection of the architecture slices in universal (fat) Mach-O binaries.
+//!
+//! macOS applications are usually shipped as universal binaries, which contain a complete Electron binary for each
+//! architecture. Every slice has its own fuse wire and copy of each patch target, so all of them need to be hardened.
+
+use crate::ElectronApp;
+use std::{convert::TryInto, ops::Range};
+
+/// The magic number of a fat binary with 32-bit offsets.
+const FAT_MAGIC: u32 = 0xCAFE_BABE;
+/// The magic number of a fat binary with 64-bit offsets.
+const FAT_MAGIC_64: u32 = 0xCAFE_BABF;
+
+/// The most architectures a fat binary is considered to contain.
+///
+/// Java class files share the 32-bit magic number, but have their version where the architecture count would be,
+/// which is always above this.
+const MAX_ARCHITECTURES: u32 = 20;
+
+/// Returns the range of each architecture slice in a fat binary, in the order they're listed in its header.
+///
+/// `None` is returned if the binary isn't a fat binary, or if its header is invalid.
+pub(crate) fn slices(binary: &[u8]) -> Option<Vec<Range<usize>>> {
+    let read_u32 = |pos: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(
+            binary.get(pos..(pos + 4))?.try_into().ok()?,
+        ))
+    };
+    let read_u64 = |pos: usize| -> Option<u64> {
+        Some(u64::from_be_bytes(
+            binary.get(pos..(pos + 8))?.try_into().ok()?,
+        ))
+    };
+
+    let is_64 = match read_u32(0)? {
+        FAT_MAGIC => false,
+        FAT_MAGIC_64 => true,
+        _ => return None,
+    };
+
+    let count = read_u32(4)?;
+    if count == 0 || count > MAX_ARCHITECTURES {
+        return None;
+    }
+
+    // Each entry starts with the CPU type and subtype, followed by the slice's offset and size.
+    let entry_len = if is_64 { 32 } else { 20 };
+    let header_end = 8 + entry_len * count as usize;
+
+    let mut slices = Vec::with_capacity(count as usize);
+    for i in 0..(count as usize) {
+        let entry = 8 + entry_len * i;
+        let (offset, size) = if is_64 {
+            (read_u64(entry + 8)?, read_u64(entry + 16)?)
+        } else {
+            (
+                u64::from(read_u32(entry + 8)?),
+                u64::from(read_u32(entry + 12)?),
+            )
+        };
+
+        let start: usize = offset.try_into().ok()?;
+        let end = start.checked_add(size.try_into().ok()?)?;
+        if start < header_end || end > binary.len() {
+            return None;
+        }
+
+        slices.push(start..end);
+    }
+
+    Some(slices)
+}
+
+impl ElectronApp<'_> {
+    /// Returns how many architectures the application contains.
+    ///
+    /// This is more than one for universal macOS binaries. Fuses are changed and targets are patched in every one of
+    /// them.
+    pub fn architecture_count(&self) -> usize {
+        self.slices.len().max(1)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Wraps each binary in a slice of a fat binary with 32-bit offsets.
+    pub(crate) fn fat_binary(binaries: &[&[u8]]) -> Vec<u8> {
+        let header_len = 8 + 20 * binaries.len();
+        let mut fat = FAT_MAGIC.to_be_bytes().to_vec();
+        fat.extend_from_slice(&(binaries.len() as u32).to_be_bytes());
+
+        let mut offset = header_len;
+        for binary in binaries {
+            // x86_64 and its subtype, then the slice's offset, size and alignment.
+            for field in &[0x0100_0007, 3, offset as u32, binary.len() as u32, 0] {
+                fat.extend_from_slice(&u32::to_be_bytes(*field));
+            }
+            offset += binary.len();
+        }
+
+        for binary in binaries {
+            fat.extend_from_slice(binary);
+        }
+        fat
+    }
+
+    #[test]
+    fn fat_slices_are_found() {
+        let fat = fat_binary(&[b"first", b"second"]);
+        assert_eq!(slices(&fat), Some(vec![48..53, 53..59]));
+    }
+
+    #[test]
+    fn invalid_fat_headers_are_ignored() {
+        assert_eq!(slices(b"not a fat binary"), None);
+
+        // A Java class file, whose version is where the architecture count would be.
+        let mut class = FAT_MAGIC.to_be_bytes().to_vec();
+        class.extend_from_slice(&[0, 0, 0, 52]);
+        class.resize(4096, 0);
+        assert_eq!(slices(&class), None);
+
+        // A slice that extends past the end of the binary.
+        let mut fat = fat_binary(&[b"first"]);
+        fat.truncate(fat.len() - 1);
+        assert_eq!(slices(&fat), None);
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! [fuses]: https://www.electronjs.org/docs/tutorial/fuses
 
-use crate::{BinaryError, ElectronApp, PatcherError};
-use std::ops::Range;
+use crate::{
+    BinaryError, ElectronApp, ElectronAppBuf, ElectronAppView, PatcherError, UnknownNameError,
+};
+use std::{fmt, fs, ops::Range, path::Path, str::FromStr};
+
+#[cfg(feature = "patch")]
+use crate::{
+    journal::{JournalEntry, ModifiedItem},
+    SourceError,
+};
 
 #[cfg(test)]
 use enum_iterator::IntoEnumIterator;
@@ -49,10 +57,21 @@ pub enum Fuse {
     EmbeddedAsarIntegrityValidation,
     /// Forces Electron to only load the application from `app.asar`. Other files and folders will be ignored
     /// if they exist in the search path.
+    ///
+    /// Without this, Electron [also searches](https://www.electronjs.org/docs/latest/tutorial/fuses#onlyloadappfromasar)
+    /// for an `app` directory and a `default_app.asar` archive, which aren't covered by ASAR integrity validation.
     OnlyLoadAppFromAsar,
+    /// Makes the browser process load its V8 snapshot from `browser_v8_context_snapshot.bin` instead of sharing the
+    /// snapshot used by other processes.
+    ///
+    /// Enabling this only makes sense for applications that ship a browser process snapshot, since the file is loaded
+    /// from disk next to the application without being covered by ASAR integrity validation. Applications that don't
+    /// should leave it disabled.
+    LoadBrowserProcessSpecificV8Snapshot,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 /// The result of an [operation](ElectronApp::set_fuse_status) on a fuse.
 pub enum FuseStatus {
@@ -66,14 +85,254 @@ pub enum FuseStatus {
     Removed,
 }
 
+/// The modifications [set_fuse_status](ElectronApp::set_fuse_status) would make, computed by
+/// [plan_fuse_change](ElectronApp::plan_fuse_change) without writing anything.
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FusePlan {
+    /// The fuse that would be changed.
+    pub fuse: Fuse,
+    /// The value the fuse would be set to.
+    pub enabled: bool,
+    /// The status `set_fuse_status` would return.
+    pub status: FuseStatus,
+    /// The byte that would be written in each fuse wire where the fuse doesn't have the value yet, in order.
+    pub changes: Vec<JournalEntry>,
+}
+
+/// Formats the fuse with the name the official [`@electron/fuses`](https://github.com/electron/fuses) package uses for
+/// it, such as `RunAsNode`.
+impl fmt::Display for Fuse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a fuse from its [displayed](fmt::Display) name, such as `RunAsNode`.
+///
+/// The same name starting with a lowercase letter (`runAsNode`), the fuse's name in Electron's schema
+/// (`run_as_node`), and the name of its variant in this library are also accepted.
+impl FromStr for Fuse {
+    type Err = UnknownNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|fuse| {
+                let name = fuse.name();
+                let same_but_first = matches!(s.get(..1), Some(first) if first.eq_ignore_ascii_case(&name[..1]))
+                    && s.get(1..) == Some(&name[1..]);
+
+                same_but_first || s == fuse.schema_name() || s == format!("{:?}", fuse)
+            })
+            .ok_or_else(|| {
+                UnknownNameError::new("fuse", s, Self::ALL.iter().map(Fuse::name).collect())
+            })
+    }
+}
+
+/// Serializes the fuse as its [displayed](fmt::Display) name, so the output doesn't depend on the order of the
+/// variants.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fuse {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Deserializes the fuse from any name its [FromStr] implementation accepts.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fuse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The status of every fuse in an application's fuse wire, as returned by
+/// [get_all_fuse_statuses](ElectronApp::get_all_fuse_statuses).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FuseStatuses {
+    /// The status of every known fuse that's in the wire, in the order of the wire.
+    pub fuses: Vec<(Fuse, FuseStatus)>,
+    /// The known fuses that the wire is too short to contain, because the application was built with an older version
+    /// of Electron.
+    pub absent: Vec<Fuse>,
+    /// The positions in the wire that don't belong to any fuse known by this library, and their status.
+    ///
+    /// These are usually fuses added by a newer version of Electron.
+    pub unknown: Vec<(usize, FuseStatus)>,
+}
+
+/// How a [fuse configuration](FuseConfig) wants a fuse to be set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum FuseSetting {
+    /// The fuse is enabled.
+    On,
+    /// The fuse is disabled.
+    Off,
+    /// The fuse keeps whatever value the application was built with.
+    Inherit,
+}
+
+/// Deserializes a setting from `"on"`, `"off"` or `"inherit"`, or from `true` or `false` like the configurations of
+/// [`@electron/fuses`](https://github.com/electron/fuses).
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FuseSetting {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            Name(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Bool(true) => Ok(Self::On),
+            Raw::Bool(false) => Ok(Self::Off),
+            Raw::Name(name) => match name.to_ascii_lowercase().as_str() {
+                "on" => Ok(Self::On),
+                "off" => Ok(Self::Off),
+                "inherit" => Ok(Self::Inherit),
+                _ => Err(serde::de::Error::unknown_variant(
+                    &name,
+                    &["on", "off", "inherit"],
+                )),
+            },
+        }
+    }
+}
+
+/// A desired value for some of an application's fuses, applied with
+/// [apply_fuse_config](ElectronApp::apply_fuse_config).
+///
+/// With the `serde` feature, configurations can be deserialized from the same map of fuse names to settings that the
+/// official [`@electron/fuses`](https://github.com/electron/fuses) tooling consumes, so a single hardening spec can be
+/// shared with a JavaScript build step:
+///
+/// ```json
+/// { "version": "1", "RunAsNode": "off", "EnableCookieEncryption": true, "OnlyLoadAppFromAsar": "inherit" }
+/// ```
+///
+/// Fuses can be named by anything [Fuse]'s [FromStr] implementation accepts, or by their position in the fuse wire
+/// like `@electron/fuses` does. The `version` is optional, but has to match the fuse wire version this library
+/// supports if it's present. The `@electron/fuses` options `resetAdHocDarwinSignature` and `strictlyRequireAllFuses`
+/// are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FuseConfig {
+    settings: Vec<(Fuse, FuseSetting)>,
+}
+
+impl FuseConfig {
+    /// Creates an empty configuration that leaves every fuse alone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how a fuse should be set, replacing any setting it already had.
+    pub fn set(mut self, fuse: Fuse, setting: FuseSetting) -> Self {
+        match self.settings.iter_mut().find(|(f, _)| *f == fuse) {
+            Some(existing) => existing.1 = setting,
+            None => self.settings.push((fuse, setting)),
+        }
+        self
+    }
+
+    /// Returns the setting of every fuse in the configuration, in the order they were added.
+    pub fn settings(&self) -> &[(Fuse, FuseSetting)] {
+        &self.settings
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FuseConfig {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, MapAccess, Visitor};
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Version {
+            Number(u8),
+            Name(String),
+        }
+
+        struct ConfigVisitor;
+
+        impl<'de> Visitor<'de> for ConfigVisitor {
+            type Value = FuseConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of fuse names to settings")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FuseConfig, A::Error> {
+                let mut config = FuseConfig::new();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "version" => {
+                            let version = match map.next_value::<Version>()? {
+                                Version::Number(version) => Some(version),
+                                Version::Name(name) => name.parse().ok(),
+                            };
+                            if version != Some(Fuse::EXPECTED_VERSION) {
+                                return Err(A::Error::custom(format!(
+                                    "unsupported fuse config version, expected {}",
+                                    Fuse::EXPECTED_VERSION
+                                )));
+                            }
+                        }
+                        "resetAdHocDarwinSignature" | "strictlyRequireAllFuses" => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                        _ => {
+                            let fuse = match key.parse::<usize>() {
+                                Ok(pos) => Fuse::ALL
+                                    .iter()
+                                    .copied()
+                                    .find(|fuse| fuse.schema_pos() == pos)
+                                    .ok_or_else(|| {
+                                        A::Error::custom(format!("unknown fuse position {}", pos))
+                                    })?,
+                                Err(_) => key.parse().map_err(A::Error::custom)?,
+                            };
+                            config = config.set(fuse, map.next_value()?);
+                        }
+                    }
+                }
+
+                Ok(config)
+            }
+        }
+
+        deserializer.deserialize_map(ConfigVisitor)
+    }
+}
+
 impl Fuse {
+    /// Every fuse known by this library.
+    pub(crate) const ALL: &'static [Fuse] = &[
+        Self::RunAsNode,
+        Self::EncryptedCookies,
+        Self::NodeOptions,
+        Self::NodeCliInspect,
+        Self::EmbeddedAsarIntegrityValidation,
+        Self::OnlyLoadAppFromAsar,
+        Self::LoadBrowserProcessSpecificV8Snapshot,
+    ];
+
     /// Marker bytes that signal where the fuse wires start inside an Electron app's bytes.
-    const SENTINEL: &'static [u8] = b"dL7pKGdnNz796PbbjQWNKmHXBZaB9tsX";
+    pub(crate) const SENTINEL: &'static [u8] = b"dL7pKGdnNz796PbbjQWNKmHXBZaB9tsX";
 
     /// Marked as disabled and the feature it controls can't be used.
-    const DISABLED: u8 = b'0';
+    pub(crate) const DISABLED: u8 = b'0';
     /// Marked as enabled and the feature can be used.
-    const ENABLED: u8 = b'1';
+    pub(crate) const ENABLED: u8 = b'1';
     /// The fuse was removed from the [Electron schema] and marked as such.
     ///
     /// Disabling or enabling a fuse that has been removed will have no effect.
@@ -82,72 +341,154 @@ impl Fuse {
     const REMOVED: u8 = b'r';
 
     /// The version of the fuse schema this tool can work with.
-    const EXPECTED_VERSION: u8 = 1;
-
-    /// Returns where in the fuse wire this fuse is located.
-    fn schema_pos(&self) -> usize {
-        let wire_pos = match self {
-            Self::RunAsNode => 1,
-            Self::EncryptedCookies => 2,
-            Self::NodeOptions => 3,
-            Self::NodeCliInspect => 4,
-            Self::EmbeddedAsarIntegrityValidation => 5,
-            Self::OnlyLoadAppFromAsar => 6,
-        };
+    pub(crate) const EXPECTED_VERSION: u8 = 1;
 
-        wire_pos - 1
+    /// Returns the first major version of Electron that implements the feature controlled by this fuse, if
+    /// it isn't implemented by every version that has the fuse in its wire.
+    ///
+    /// On older versions, the fuse can be present and modified without having any effect.
+    pub const fn effective_since(&self) -> Option<u32> {
+        match self {
+            Self::EncryptedCookies => Some(13),
+            _ => None,
+        }
     }
 
-    /// Locates the start of the fuses binary section.
+    /// Returns the value of this fuse that makes the application harder to tamper with.
     ///
-    /// Returns the position of the fuse wire.
-    pub(crate) fn find_wire(binary: &[u8]) -> Result<Range<usize>, PatcherError> {
-        let sentinel_len = Self::SENTINEL.len();
+    /// Fuses that disable a feature are secure when disabled, and fuses that enable a protection are secure when
+    /// enabled.
+    pub const fn secure_value(&self) -> bool {
+        match self {
+            Self::RunAsNode
+            | Self::NodeOptions
+            | Self::NodeCliInspect
+            | Self::LoadBrowserProcessSpecificV8Snapshot => false,
+            Self::EncryptedCookies
+            | Self::EmbeddedAsarIntegrityValidation
+            | Self::OnlyLoadAppFromAsar => true,
+        }
+    }
 
-        let pos = binary
-            .windows(sentinel_len)
-            .position(|slice| slice == Self::SENTINEL)
-            .ok_or(BinaryError::NoSentinel)?;
+    /// Returns a stable identifier for this fuse, for referring to it in configuration files and other languages.
+    ///
+    /// Identifiers are never changed or reused, and new fuses are assigned the next unused one. They match the
+    /// numbering of Electron's `FuseV1Options`.
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::RunAsNode => 0,
+            Self::EncryptedCookies => 1,
+            Self::NodeOptions => 2,
+            Self::NodeCliInspect => 3,
+            Self::EmbeddedAsarIntegrityValidation => 4,
+            Self::OnlyLoadAppFromAsar => 5,
+            Self::LoadBrowserProcessSpecificV8Snapshot => 6,
+        }
+    }
 
-        let start = pos + sentinel_len;
+    /// Returns every fuse known by this library, in the order they appear in the fuse wire.
+    ///
+    /// New fuses are added to the end as this library learns about them, so code that iterates over this doesn't need
+    /// to change when they are.
+    pub const fn all() -> &'static [Fuse] {
+        Self::ALL
+    }
 
-        let version = binary.get(start).ok_or(BinaryError::NoFuseVersion)?;
+    /// Returns the name `@electron/fuses` uses for this fuse, such as `RunAsNode`.
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::RunAsNode => "RunAsNode",
+            Self::EncryptedCookies => "EnableCookieEncryption",
+            Self::NodeOptions => "EnableNodeOptionsEnvironmentVariable",
+            Self::NodeCliInspect => "EnableNodeCliInspectArguments",
+            Self::EmbeddedAsarIntegrityValidation => "EnableEmbeddedAsarIntegrityValidation",
+            Self::OnlyLoadAppFromAsar => "OnlyLoadAppFromAsar",
+            Self::LoadBrowserProcessSpecificV8Snapshot => "LoadBrowserProcessSpecificV8Snapshot",
+        }
+    }
 
-        if *version != Self::EXPECTED_VERSION {
-            return Err(PatcherError::FuseVersion {
-                expected: Self::EXPECTED_VERSION,
-                found: *version,
-            });
+    /// Returns the name of this fuse in Electron's [schema](https://github.com/electron/electron/blob/main/build/fuses/fuses.json5),
+    /// such as `run_as_node`.
+    const fn schema_name(&self) -> &'static str {
+        match self {
+            Self::RunAsNode => "run_as_node",
+            Self::EncryptedCookies => "cookie_encryption",
+            Self::NodeOptions => "node_options",
+            Self::NodeCliInspect => "node_cli_inspect",
+            Self::EmbeddedAsarIntegrityValidation => "embedded_asar_integrity_validation",
+            Self::OnlyLoadAppFromAsar => "only_load_app_from_asar",
+            Self::LoadBrowserProcessSpecificV8Snapshot => {
+                "load_browser_process_specific_v8_snapshot"
+            }
         }
+    }
 
-        let len_pos = start + 1;
-        let wire_len = binary.get(len_pos).ok_or(BinaryError::NoFuseLength)?;
+    /// Returns the fuse with the provided [identifier](Fuse::id), if it is known by this library.
+    pub fn from_id(id: u16) -> Option<Fuse> {
+        Self::ALL.iter().copied().find(|fuse| fuse.id() == id)
+    }
 
-        let wire_start = len_pos + 1;
-        let fuse_bytes = (wire_start)..(wire_start + usize::from(*wire_len));
+    /// Returns where in the fuse wire this fuse is located.
+    pub(crate) fn schema_pos(&self) -> usize {
+        let wire_pos = match self {
+            Self::RunAsNode => 1,
+            Self::EncryptedCookies => 2,
+            Self::NodeOptions => 3,
+            Self::NodeCliInspect => 4,
+            Self::EmbeddedAsarIntegrityValidation => 5,
+            Self::OnlyLoadAppFromAsar => 6,
+            Self::LoadBrowserProcessSpecificV8Snapshot => 7,
+        };
 
-        Ok(fuse_bytes)
+        wire_pos - 1
     }
 
-    fn fuse_status(&self, wire: &[u8]) -> Result<FuseStatus, PatcherError> {
+    pub(crate) fn fuse_status(&self, wire: &[u8]) -> Result<FuseStatus, PatcherError> {
         let status = wire
             .get(self.schema_pos())
             .ok_or(BinaryError::FuseDoesNotExist(*self))?;
 
-        let status = match *status {
-            Self::ENABLED => FuseStatus::Present(true),
-            Self::DISABLED => FuseStatus::Present(false),
-            Self::REMOVED => FuseStatus::Removed,
-            s => {
-                return Err(BinaryError::UnknownFuse {
-                    fuse: *self,
-                    value: s,
-                }
-                .into())
+        Self::decode(*status).ok_or_else(|| {
+            BinaryError::UnknownFuse {
+                fuse: *self,
+                value: *status,
+                offset: self.schema_pos(),
             }
-        };
+            .into()
+        })
+    }
+
+    /// Decodes the status stored in a byte of a fuse wire, or returns `None` if it isn't a valid status.
+    fn decode(status: u8) -> Option<FuseStatus> {
+        match status {
+            Self::ENABLED => Some(FuseStatus::Present(true)),
+            Self::DISABLED => Some(FuseStatus::Present(false)),
+            Self::REMOVED => Some(FuseStatus::Removed),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+impl Fuse {
+    /// Writes a new value for this fuse into a copy of the wire, which is used to plan changes to it.
+    ///
+    /// The value is only written to the application by [write_bytes](ElectronApp::write_bytes), which checks it.
+    fn write(&self, wire: &mut [u8], value: u8) -> Result<(), PatcherError> {
+        let pos = self.schema_pos();
+        *wire
+            .get_mut(pos)
+            .ok_or(BinaryError::FuseDoesNotExist(*self))? = value;
+        Ok(())
+    }
 
-        Ok(status)
+    /// Returns whether changing a fuse from one value to another is a change this library should ever make, which
+    /// are only between enabled and disabled.
+    fn is_legal_change(from: u8, to: u8) -> bool {
+        matches!(
+            (from, to),
+            (Self::DISABLED, Self::ENABLED) | (Self::ENABLED, Self::DISABLED)
+        )
     }
 
     fn disable(&self, wire: &mut [u8]) -> Result<FuseStatus, PatcherError> {
@@ -155,7 +496,7 @@ impl Fuse {
 
         match enabled {
             FuseStatus::Present(e) if e => {
-                wire[self.schema_pos()] = Self::DISABLED;
+                self.write(wire, Self::DISABLED)?;
                 enabled = FuseStatus::Modified
             }
             FuseStatus::Removed => return Err(PatcherError::RemovedFuse(*self)),
@@ -170,7 +511,7 @@ impl Fuse {
 
         match enabled {
             FuseStatus::Present(e) if !e => {
-                wire[self.schema_pos()] = Self::ENABLED;
+                self.write(wire, Self::ENABLED)?;
                 enabled = FuseStatus::Modified
             }
             FuseStatus::Removed => return Err(PatcherError::RemovedFuse(*self)),
@@ -181,160 +522,1658 @@ impl Fuse {
     }
 }
 
-impl<'a> ElectronApp<'a> {
-    /// Constructs a new [electron app](Self) and verifies that the bytes came from
-    /// a packaged Electron app binary file.
-    ///
-    /// # Errors
-    ///
-    /// This function returns an error if the bytes couldn't be validated to contain an Electron application.
-    pub fn from_bytes(application_bytes: &'a mut [u8]) -> Result<ElectronApp<'a>, PatcherError> {
-        let wire_pos = Fuse::find_wire(application_bytes)?;
+/// A transform that a repackaged application may have applied to its fuse sentinel to obfuscate it.
+///
+/// Only the sentinel is expected to be transformed. The fuse wire that follows it must be unmodified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SentinelTransform {
+    /// Every byte of the sentinel was XORed with the same key.
+    Xor(u8),
+}
 
-        Ok(Self {
-            contents: application_bytes,
-            wire_start: wire_pos.start,
-            wire_end: wire_pos.end,
-        })
+impl SentinelTransform {
+    /// Applies the transform to the plain sentinel, producing what it looks like in an obfuscated binary.
+    fn apply(&self, sentinel: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Xor(key) => sentinel.iter().map(|b| b ^ key).collect(),
+        }
     }
+}
 
-    /// Parses and returns this fuse type's status in the provided binary.
+/// A fuse wire layout that differs from the one Electron writes, but can still be recognized.
+///
+/// These have been seen in binaries produced by unusual toolchains. They are only detected to explain why a binary
+/// couldn't be loaded: modifying them is unsupported, because it isn't known how Electron reads them, if at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum WireLayout {
+    /// The sentinel, version, length and fuses are all stored with their bytes in reverse order.
+    Reversed,
+    /// The length of the fuse wire is stored before its version instead of after it.
+    SwappedHeader,
+}
+
+/// Options for how strictly an application's fuse wire is parsed by
+/// [from_bytes_with_options](ElectronApp::from_bytes_with_options).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Accepts fuse wires with a newer schema version than this library supports, instead of returning
+    /// [FuseVersion](PatcherError::FuseVersion).
     ///
-    /// # Return
+    /// Newer versions are assumed to keep the positions of the fuses this library knows about. Their contents aren't
+    /// validated when the application is loaded, and reading or writing a fuse with a value this library doesn't
+    /// understand returns [UnknownFuse](BinaryError::UnknownFuse). Older versions are always rejected.
+    pub allow_unknown_fuse_version: bool,
+}
+
+/// The location of a fuse wire inside of an application binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuseWire {
+    version: u8,
+    range: Range<usize>,
+    candidates: usize,
+}
+
+impl FuseWire {
+    /// The longest fuse wire that is considered plausible.
+    const MAX_LEN: u8 = 64;
+
+    /// Locates the fuse wire inside of a section of an application binary.
     ///
-    /// Returns the current fuse status. This will not return a [modification result](FuseResult::Modified).
+    /// `section_offset` is the position of `section` in the whole file, which lets this be used with sections
+    /// extracted by an object file parser. The returned wire has positions relative to the start of the file.
     ///
     /// # Errors
     ///
-    /// This function will return an error if an invalid binary is provided or one that is not an Electron application.
-    pub fn get_fuse_status(&self, fuse: Fuse) -> Result<FuseStatus, PatcherError> {
-        let wire = &self.contents[self.wire_start..self.wire_end];
-        fuse.fuse_status(wire)
+    /// This function returns an error if no valid fuse wire could be found in the section. If the sentinel was found
+    /// more than once and none of the candidates were valid, [NoValidFuseWire](BinaryError::NoValidFuseWire) is
+    /// returned. If a wire with an [unsupported layout](WireLayout) was found instead,
+    /// [UnsupportedWireLayout](BinaryError::UnsupportedWireLayout) is returned. Otherwise, the error describes why the
+    /// only candidate was rejected.
+    ///
+    /// If more than one valid wire is found, it isn't known which of them is wanted, so
+    /// [AmbiguousSentinel](BinaryError::AmbiguousSentinel) is returned. Use [locate_all](FuseWire::locate_all) for
+    /// sections that are expected to contain several.
+    pub fn locate(section: &[u8], section_offset: usize) -> Result<FuseWire, PatcherError> {
+        Self::locate_with(section, section_offset, &[], &ParseOptions::default())
+            .and_then(Self::only)
     }
 
-    /// Toggles a fuse in the application binary based off the provided value.
+    /// Locates every valid fuse wire inside of a section of an application binary, in the order they appear.
     ///
-    /// # Return
+    /// Most binaries only contain one, but files such as self-extracting installers can contain several complete
+    /// Electron binaries.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [locate](FuseWire::locate) if no valid fuse wire could be found.
+    pub fn locate_all(
+        section: &[u8],
+        section_offset: usize,
+    ) -> Result<Vec<FuseWire>, PatcherError> {
+        Self::locate_with(section, section_offset, &[], &ParseOptions::default())
+    }
+
+    /// Locates the fuse wire inside of a section of an application binary, trying each of the provided
+    /// [transforms](SentinelTransform) in order if the sentinel can't be found as-is.
     ///
-    /// Returns the [result](FuseResult) of the operation if it succeeded.
+    /// This is a best-effort attempt for binaries that have had their sentinel obfuscated with one of a small number of
+    /// known schemes. The fuse wire itself is expected to be unmodified.
     ///
     /// # Errors
     ///
-    /// This function will return an error if a fuse wire couldn't be found in the provided binary or
-    /// if a modification of a removed fuse was attempted.
-    pub fn set_fuse_status(
-        &mut self,
-        fuse: Fuse,
-        enabled: bool,
-    ) -> Result<FuseStatus, PatcherError> {
-        let wire = &mut self.contents[self.wire_start..self.wire_end];
+    /// This function returns [NoSentinel](BinaryError::NoSentinel) if neither the sentinel nor any of its transformed
+    /// versions could be found. Otherwise, it returns the same errors as [locate](FuseWire::locate) for the first
+    /// version of the sentinel that was found.
+    pub fn locate_obfuscated(
+        section: &[u8],
+        section_offset: usize,
+        transforms: &[SentinelTransform],
+    ) -> Result<FuseWire, PatcherError> {
+        Self::locate_with(
+            section,
+            section_offset,
+            transforms,
+            &ParseOptions::default(),
+        )
+        .and_then(Self::only)
+    }
 
-        if enabled {
-            fuse.enable(wire)
-        } else {
-            fuse.disable(wire)
+    /// Returns the only wire that was found, or an error if there were several.
+    fn only(mut wires: Vec<FuseWire>) -> Result<FuseWire, PatcherError> {
+        match wires.len() {
+            1 => Ok(wires.remove(0)),
+            count => Err(BinaryError::AmbiguousSentinel { count }.into()),
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    const TEST_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
-    const FUSE: Fuse = Fuse::RunAsNode;
+    /// Checks that a whole binary is long enough to contain a fuse wire, so that degenerate inputs are rejected before
+    /// they're searched.
+    pub(crate) fn check_input_len(len: usize) -> Result<(), BinaryError> {
+        // The sentinel, version, length and a single fuse.
+        let minimum = Fuse::SENTINEL.len() + 3;
 
-    fn get_wire() -> &'static [u8] {
-        let wire_pos = Fuse::find_wire(TEST_BYTES).unwrap();
-        &TEST_BYTES[wire_pos]
+        match len {
+            0 => Err(BinaryError::EmptyInput),
+            len if len < minimum => Err(BinaryError::InputTooSmall { len, minimum }),
+            _ => Ok(()),
+        }
     }
 
-    #[test]
-    fn sentinal_is_found() {
-        assert!(Fuse::find_wire(TEST_BYTES).is_ok());
-    }
+    /// Searches for the sentinel, then each transformed version of it in order until one is found.
+    fn locate_with(
+        section: &[u8],
+        section_offset: usize,
+        transforms: &[SentinelTransform],
+        options: &ParseOptions,
+    ) -> Result<Vec<FuseWire>, PatcherError> {
+        if section.is_empty() {
+            return Err(BinaryError::EmptyInput.into());
+        }
 
-    #[test]
-    fn enabled_fuse_is_correct() {
-        assert_eq!(
-            FUSE.fuse_status(get_wire()).unwrap(),
-            FuseStatus::Present(true)
-        );
-    }
+        let mut result = Self::locate_sentinel(section, section_offset, Fuse::SENTINEL, options);
 
-    #[test]
-    fn disabled_fuse_is_correct() {
-        let mut wire = get_wire().to_vec();
-        assert_eq!(FUSE.disable(&mut wire).unwrap(), FuseStatus::Modified);
-        assert_eq!(FUSE.fuse_status(&wire).unwrap(), FuseStatus::Present(false));
-    }
+        for transform in transforms {
+            match result {
+                Err(PatcherError::Binary(BinaryError::NoSentinel)) => {
+                    let sentinel = transform.apply(Fuse::SENTINEL);
+                    result = Self::locate_sentinel(section, section_offset, &sentinel, options);
+                }
+                _ => break,
+            }
+        }
 
-    #[test]
-    fn removed_fuse_is_correct() {
-        let mut wire = get_wire().to_vec();
-        wire[FUSE.schema_pos()] = Fuse::REMOVED;
+        if result.is_err() {
+            if let Some((layout, offset)) = Self::detect_unsupported_layout(section) {
+                return Err(BinaryError::UnsupportedWireLayout {
+                    layout,
+                    offset: section_offset + offset,
+                }
+                .into());
+            }
+        }
 
-        assert_eq!(FUSE.fuse_status(&wire).unwrap(), FuseStatus::Removed);
+        result
     }
 
-    #[test]
-    fn unknown_fuse_value_is_correct() {
-        let value = 9;
-        let mut wire = get_wire().to_vec();
-        wire[FUSE.schema_pos()] = value;
+    /// Searches for a valid fuse wire stored in one of the [unsupported layouts](WireLayout), returning the layout
+    /// and the position of its sentinel.
+    fn detect_unsupported_layout(section: &[u8]) -> Option<(WireLayout, usize)> {
+        let sentinel_len = Fuse::SENTINEL.len();
+        // The version, length and longest plausible wire.
+        let max_wire_len = 2 + usize::from(Self::MAX_LEN);
 
-        assert_eq!(
-            FUSE.fuse_status(&wire),
-            Err(PatcherError::Binary(BinaryError::UnknownFuse {
-                fuse: FUSE,
-                value,
-            }))
-        );
-    }
+        // Each candidate is rearranged into the normal layout, without its sentinel, and validated.
+        for pos in memchr::memmem::find_iter(section, Fuse::SENTINEL) {
+            let header = pos + sentinel_len;
+            if let Some(&[len, version]) = section.get(header..(header + 2)) {
+                let end = section.len().min(header + max_wire_len);
+                let mut rearranged = vec![version, len];
+                rearranged.extend_from_slice(&section[(header + 2)..end]);
 
-    #[test]
-    fn modfying_removed_fuse_errors() {
-        let mut wire = get_wire().to_vec();
-        wire[FUSE.schema_pos()] = Fuse::REMOVED;
+                if Self::validate(&rearranged, 0, pos, &ParseOptions::default()).is_ok() {
+                    return Some((WireLayout::SwappedHeader, pos));
+                }
+            }
+        }
 
-        assert_eq!(
-            FUSE.disable(&mut wire),
-            Err(PatcherError::RemovedFuse(FUSE))
-        );
-        assert_eq!(FUSE.enable(&mut wire), Err(PatcherError::RemovedFuse(FUSE)));
-    }
+        let reversed_sentinel: Vec<u8> = Fuse::SENTINEL.iter().rev().copied().collect();
+        for pos in memchr::memmem::find_iter(section, &reversed_sentinel) {
+            let rearranged: Vec<u8> = section[pos.saturating_sub(max_wire_len)..pos]
+                .iter()
+                .rev()
+                .copied()
+                .collect();
 
-    #[test]
-    fn test_app_fuse_actions() {
-        let mut application_bytes = TEST_BYTES.to_vec();
-        let mut app = ElectronApp::from_bytes(&mut application_bytes).unwrap();
+            if Self::validate(&rearranged, 0, pos, &ParseOptions::default()).is_ok() {
+                return Some((WireLayout::Reversed, pos));
+            }
+        }
 
-        assert_eq!(
-            app.get_fuse_status(FUSE).unwrap(),
-            FuseStatus::Present(true)
-        );
+        None
+    }
 
-        // Setting the fuse to what it already is shouldn't modify anything.
-        assert_eq!(
-            app.set_fuse_status(FUSE, true).unwrap(),
-            FuseStatus::Present(true)
-        );
+    /// Finds every valid wire following the sentinel.
+    fn locate_sentinel(
+        section: &[u8],
+        section_offset: usize,
+        sentinel: &[u8],
+        options: &ParseOptions,
+    ) -> Result<Vec<FuseWire>, PatcherError> {
+        let sentinel_len = sentinel.len();
 
-        assert_eq!(
-            app.set_fuse_status(FUSE, false).unwrap(),
-            FuseStatus::Modified
-        );
-        assert_eq!(
-            app.get_fuse_status(FUSE).unwrap(),
-            FuseStatus::Present(false)
-        );
-    }
+        // The sentinel can also appear in unrelated data, so every occurrence is a candidate until its
+        // wire has been validated.
+        let mut candidates = 0;
+        let mut first_rejection = None;
+        let mut wires = Vec::new();
 
-    #[test]
-    fn can_read_all_fuses() {
-        let wire = get_wire();
+        for pos in section
+            .windows(sentinel_len)
+            .enumerate()
+            .filter(|(_, slice)| *slice == sentinel)
+            .map(|(pos, _)| pos)
+        {
+            candidates += 1;
 
-        for fuse in Fuse::into_enum_iter() {
+            match Self::validate(section, pos + sentinel_len, section_offset + pos, options) {
+                Ok((version, range)) => {
+                    wires.push(FuseWire {
+                        version,
+                        range: (section_offset + range.start)..(section_offset + range.end),
+                        candidates,
+                    });
+                }
+                Err(e) => {
+                    first_rejection.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_rejection {
+            _ if !wires.is_empty() => Ok(wires),
+            None => Err(BinaryError::NoSentinel.into()),
+            Some(e) if candidates == 1 => Err(e),
+            Some(_) => Err(BinaryError::NoValidFuseWire { candidates }.into()),
+        }
+    }
+
+    /// Checks that the bytes following a sentinel are a plausible fuse wire, returning its version and
+    /// position in the section.
+    ///
+    /// Wires with a newer schema version than this library supports may use fuse values it doesn't know about, so
+    /// their contents are only checked when each fuse is read.
+    ///
+    /// `sentinel` is the position of the sentinel in the binary, which errors refer to.
+    fn validate(
+        section: &[u8],
+        start: usize,
+        sentinel: usize,
+        options: &ParseOptions,
+    ) -> Result<(u8, Range<usize>), PatcherError> {
+        let version = *section
+            .get(start)
+            .ok_or(BinaryError::NoFuseVersion { sentinel })?;
+
+        let newer = version > Fuse::EXPECTED_VERSION;
+        if version != Fuse::EXPECTED_VERSION && !(newer && options.allow_unknown_fuse_version) {
+            return Err(PatcherError::FuseVersion {
+                expected: Fuse::EXPECTED_VERSION,
+                found: version,
+            });
+        }
+
+        let len_pos = start + 1;
+        let wire_len = *section
+            .get(len_pos)
+            .ok_or(BinaryError::NoFuseLength { sentinel })?;
+
+        let wire_start = len_pos + 1;
+        let range = wire_start..(wire_start + usize::from(wire_len));
+
+        if !(1..=Self::MAX_LEN).contains(&wire_len) {
+            return Err(BinaryError::ImplausibleFuseLength(wire_len).into());
+        }
+
+        let wire = section
+            .get(range.clone())
+            .ok_or_else(|| BinaryError::TruncatedFuseWire {
+                len: range.len(),
+                available: section.len() - wire_start,
+            })?;
+
+        if newer {
+            return Ok((version, range));
+        }
+
+        if let Some(position) = wire
+            .iter()
+            .position(|b| !matches!(*b, Fuse::ENABLED | Fuse::DISABLED | Fuse::REMOVED))
+        {
+            return Err(BinaryError::MalformedFuseWire {
+                position,
+                value: wire[position],
+                offset: sentinel + Fuse::SENTINEL.len() + 2 + position,
+            }
+            .into());
+        }
+
+        Ok((version, range))
+    }
+
+    /// Returns the fuse schema version of the wire.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the position of the fuse wire in the file.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns how many occurrences of the sentinel were considered before this wire was found, including its own.
+    pub fn candidates(&self) -> usize {
+        self.candidates
+    }
+
+    /// Returns the position of the wire's sentinel in the file, where its header starts.
+    #[cfg(feature = "patch")]
+    pub(crate) fn header_start(&self) -> usize {
+        self.range.start - Fuse::SENTINEL.len() - 2
+    }
+}
+
+impl<'a> ElectronApp<'a> {
+    /// Constructs a new [electron app](Self) and verifies that the bytes came from
+    /// a packaged Electron app binary file.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bytes couldn't be validated to contain an Electron application. Bytes
+    /// that are too short to contain one return [EmptyInput](BinaryError::EmptyInput) or
+    /// [InputTooSmall](BinaryError::InputTooSmall) without being searched.
+    #[cfg(feature = "patch")]
+    pub fn from_bytes(application_bytes: &'a mut [u8]) -> Result<ElectronApp<'a>, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+        let wires = FuseWire::locate_all(application_bytes, 0)?;
+        Ok(Self::with_wires(application_bytes, wires))
+    }
+
+    /// Constructs a [read-only view](ElectronAppView) of an application from bytes that can't be modified.
+    ///
+    /// The view can be inspected like any other application, without needing to copy the bytes into a mutable
+    /// buffer first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [from_bytes](Self::from_bytes).
+    pub fn from_bytes_readonly(
+        application_bytes: &'a [u8],
+    ) -> Result<ElectronAppView<'a>, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+        let wires = FuseWire::locate_all(application_bytes, 0)?;
+        Ok(ElectronAppView::new(Self::with_contents(
+            application_bytes.into(),
+            wires,
+        )))
+    }
+
+    /// Reads an application binary from a file and constructs an [electron app](Self) that owns its bytes.
+    ///
+    /// Modifications are only made in memory. Use [write_back](Self::write_back) to save them to the same file, or
+    /// [write_to_path](Self::write_to_path) to save them elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [Io](PatcherError::Io) if the file couldn't be read. Otherwise, it returns the same
+    /// errors as [from_bytes](Self::from_bytes).
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<ElectronAppBuf, PatcherError> {
+        let path = path.as_ref();
+        let application_bytes = fs::read(path).map_err(|e| PatcherError::io(path, &e))?;
+
+        let mut app = ElectronApp::from_vec(application_bytes)?;
+        app.path = Some(path.to_path_buf());
+        Ok(app)
+    }
+
+    /// Constructs a new [electron app](Self) that takes ownership of the application binary's bytes.
+    ///
+    /// Use [into_bytes](Self::into_bytes) to get them back once the application has been modified.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [from_bytes](Self::from_bytes).
+    pub fn from_vec(application_bytes: Vec<u8>) -> Result<ElectronAppBuf, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+        let wires = FuseWire::locate_all(&application_bytes, 0)?;
+        Ok(ElectronApp::with_contents(application_bytes.into(), wires))
+    }
+
+    /// Constructs a new [electron app](Self) like [from_bytes](Self::from_bytes), but parses its fuse wire according
+    /// to the provided [options](ParseOptions).
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [from_bytes](Self::from_bytes), except for the ones the options
+    /// allow.
+    #[cfg(feature = "patch")]
+    pub fn from_bytes_with_options(
+        application_bytes: &'a mut [u8],
+        options: &ParseOptions,
+    ) -> Result<ElectronApp<'a>, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+        let wires = FuseWire::locate_with(application_bytes, 0, &[], options)?;
+        Ok(Self::with_wires(application_bytes, wires))
+    }
+
+    /// Constructs a new [electron app](Self) like [from_bytes](Self::from_bytes), but tries each of the provided
+    /// [transforms](SentinelTransform) in order if the fuse sentinel can't be found as-is.
+    ///
+    /// This is a best-effort attempt that only supports known obfuscation schemes. See
+    /// [FuseWire::locate_obfuscated] for details.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bytes couldn't be validated to contain an Electron application with any
+    /// of the transforms.
+    #[cfg(feature = "patch")]
+    pub fn from_bytes_with_deobfuscation(
+        application_bytes: &'a mut [u8],
+        transforms: &[SentinelTransform],
+    ) -> Result<ElectronApp<'a>, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+        let wires =
+            FuseWire::locate_with(application_bytes, 0, transforms, &ParseOptions::default())?;
+        Ok(Self::with_wires(application_bytes, wires))
+    }
+
+    #[cfg(feature = "patch")]
+    pub(crate) fn with_wires(application_bytes: &'a mut [u8], wires: Vec<FuseWire>) -> Self {
+        Self::with_contents(application_bytes.into(), wires)
+    }
+
+    pub(crate) fn with_contents(contents: crate::Contents<'a>, wires: Vec<FuseWire>) -> Self {
+        let slices = crate::fat::slices(&contents).unwrap_or_default();
+        #[cfg(feature = "object-parser")]
+        let (search_sections, section_names) =
+            match crate::sections::string_sections(&contents, &slices) {
+                Some((ranges, names)) => (Some(ranges), names),
+                None => (None, Vec::new()),
+            };
+
+        Self {
+            slices,
+            contents,
+            path: None,
+            wires,
+            search_hint: None,
+            #[cfg(feature = "patch")]
+            cancel: None,
+            #[cfg(feature = "patch")]
+            app_layout: None,
+            #[cfg(feature = "patch")]
+            journal: None,
+            #[cfg(feature = "patch")]
+            modifications: Vec::new(),
+            #[cfg(feature = "patch")]
+            patch_fill: crate::patcher::DEFAULT_PATCH_FILL,
+            #[cfg(feature = "patch")]
+            context_bytes: crate::report::DEFAULT_CONTEXT_BYTES,
+            #[cfg(feature = "patch")]
+            deny_signature_invalidation: false,
+            #[cfg(feature = "object-parser")]
+            allow_code_patches: false,
+            #[cfg(feature = "object-parser")]
+            search_sections,
+            #[cfg(feature = "object-parser")]
+            section_names,
+            #[cfg(feature = "object-parser")]
+            restrict_search: true,
+        }
+    }
+
+    /// Returns every fuse wire found in the application, in the order they appear.
+    ///
+    /// There is always at least one. Files that contain more than one Electron binary have a wire for each of them.
+    pub fn wires(&self) -> &[FuseWire] {
+        &self.wires
+    }
+
+    /// Returns the version of the fuse schema used by the application's first fuse wire.
+    pub fn schema_version(&self) -> u8 {
+        self.wires[0].version
+    }
+
+    /// Returns the version byte stored before the application's first fuse wire.
+    ///
+    /// This is the same as [schema_version](ElectronApp::schema_version), under the name used alongside the other
+    /// wire accessors.
+    pub fn fuse_wire_version(&self) -> u8 {
+        self.schema_version()
+    }
+
+    /// Returns how many fuses the application's first fuse wire contains, as stored before it.
+    pub fn fuse_wire_len(&self) -> usize {
+        self.wires[0].range.len()
+    }
+
+    /// Writes the application's bytes, including any modifications made to them, to a file.
+    ///
+    /// The file is created if it doesn't exist, and [replaced](crate::replace_binary) if it does.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [replace_binary](crate::replace_binary).
+    #[cfg(feature = "patch")]
+    pub fn write_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), SourceError> {
+        crate::replace_binary(path, &self.contents)
+    }
+
+    /// Writes the application's bytes, including any modifications made to them, back to the file it was
+    /// [read from](Self::from_path).
+    ///
+    /// # Errors
+    ///
+    /// This function returns [NoPath](PatcherError::NoPath) if the application wasn't read from a file, or the same
+    /// errors as [replace_binary](crate::replace_binary).
+    ///
+    /// Applications [opened through a memory map](Self::open_mmap) are [flushed](Self::flush) instead.
+    #[cfg(feature = "patch")]
+    pub fn write_back(&self) -> Result<(), SourceError> {
+        #[cfg(feature = "mmap")]
+        {
+            if let crate::Contents::Mapped(_) = self.contents {
+                return Ok(self.flush()?);
+            }
+        }
+
+        let path = self.path.as_ref().ok_or(PatcherError::NoPath)?;
+        self.write_to_path(path)
+    }
+
+    /// Returns the file the application was [read from](Self::from_path) or mapped from, if it was.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns the application's bytes, including any modifications made to them.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.contents
+    }
+
+    /// Consumes the application and returns its bytes, including any modifications made to them.
+    ///
+    /// Applications that borrow their bytes return a copy of them.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self.contents {
+            crate::Contents::Owned(bytes) => bytes,
+            crate::Contents::Borrowed(bytes) => bytes.to_vec(),
+            crate::Contents::Shared(bytes) => bytes.to_vec(),
+            #[cfg(feature = "mmap")]
+            crate::Contents::Mapped(file) => file.to_vec(),
+        }
+    }
+
+    /// Returns the contents of the application's first fuse wire, with one byte per fuse.
+    ///
+    /// This uses the position found when the application was loaded, so the binary isn't searched again.
+    pub fn fuse_wire(&self) -> &[u8] {
+        &self.contents[self.wires[0].range()]
+    }
+
+    /// Converts the position of a fuse in an error about one of the fuse wires into its position in the binary.
+    fn offset_in_binary(&self, wire: usize, e: PatcherError) -> PatcherError {
+        match e {
+            PatcherError::Binary(BinaryError::UnknownFuse {
+                fuse,
+                value,
+                offset,
+            }) => BinaryError::UnknownFuse {
+                fuse,
+                value,
+                offset: self.wires[wire].range.start + offset,
+            }
+            .into(),
+            PatcherError::Binary(BinaryError::MalformedFuseWire {
+                position,
+                value,
+                offset,
+            }) => BinaryError::MalformedFuseWire {
+                position,
+                value,
+                offset: self.wires[wire].range.start + offset,
+            }
+            .into(),
+            e => e,
+        }
+    }
+
+    /// Returns the contents of a fuse wire.
+    fn wire(&self, wire: usize) -> Result<&[u8], PatcherError> {
+        let wire = self
+            .wires
+            .get(wire)
+            .ok_or(BinaryError::FuseWireDoesNotExist(wire))?;
+
+        self.contents.get(wire.range()).ok_or_else(|| {
+            BinaryError::TruncatedFuseWire {
+                len: wire.range.len(),
+                available: self.contents.len().saturating_sub(wire.range.start),
+            }
+            .into()
+        })
+    }
+
+    /// Parses and returns this fuse type's status in the provided binary.
+    ///
+    /// If the application contains more than one [fuse wire](ElectronApp::wires), such as a universal macOS binary
+    /// with one for each architecture, the fuse has to have the same status in all of them. Use
+    /// [get_fuse_statuses](ElectronApp::get_fuse_statuses) to read each of them.
+    ///
+    /// # Return
+    ///
+    /// Returns the current fuse status. This will not return a [modification result](FuseResult::Modified).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an invalid binary is provided or one that is not an Electron application,
+    /// or [FuseWiresDisagree](BinaryError::FuseWiresDisagree) if the fuse's status differs between wires.
+    pub fn get_fuse_status(&self, fuse: Fuse) -> Result<FuseStatus, PatcherError> {
+        let statuses = self.get_fuse_statuses(fuse)?;
+
+        if statuses.iter().any(|status| *status != statuses[0]) {
+            return Err(BinaryError::FuseWiresDisagree { fuse, statuses }.into());
+        }
+
+        Ok(statuses[0])
+    }
+
+    /// Parses and returns this fuse type's status in each of the application's [fuse wires](ElectronApp::wires), in
+    /// the order of the wires.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [get_fuse_status_in](ElectronApp::get_fuse_status_in) for the first
+    /// wire that can't be read.
+    pub fn get_fuse_statuses(&self, fuse: Fuse) -> Result<Vec<FuseStatus>, PatcherError> {
+        (0..self.wires.len())
+            .map(|wire| self.get_fuse_status_in(wire, fuse))
+            .collect()
+    }
+
+    /// Parses and returns this fuse type's status in one of the application's [fuse wires](ElectronApp::wires).
+    ///
+    /// # Errors
+    ///
+    /// This function will return [FuseWireDoesNotExist](BinaryError::FuseWireDoesNotExist) if there is no wire at the
+    /// provided index. Other errors are the same as [get_fuse_status](ElectronApp::get_fuse_status).
+    pub fn get_fuse_status_in(&self, wire: usize, fuse: Fuse) -> Result<FuseStatus, PatcherError> {
+        fuse.fuse_status(self.wire(wire)?)
+            .map_err(|e| self.offset_in_binary(wire, e))
+    }
+
+    /// Parses and returns the status of every fuse in the application's first [fuse wire](ElectronApp::wires).
+    ///
+    /// Unlike [get_fuse_status](ElectronApp::get_fuse_status), fuses that the wire is too short to contain aren't an
+    /// error. They are listed as [absent](FuseStatuses::absent) instead, and positions that don't belong to a known
+    /// fuse are listed as [unknown](FuseStatuses::unknown).
+    ///
+    /// # Errors
+    ///
+    /// This function will return [UnknownFuse](BinaryError::UnknownFuse) or
+    /// [MalformedFuseWire](BinaryError::MalformedFuseWire) if a position in the wire doesn't contain a valid status.
+    pub fn get_all_fuse_statuses(&self) -> Result<FuseStatuses, PatcherError> {
+        let wire = self.wire(0)?;
+        let mut statuses = FuseStatuses {
+            fuses: Vec::new(),
+            absent: Vec::new(),
+            unknown: Vec::new(),
+        };
+
+        for &fuse in Fuse::ALL {
+            match fuse
+                .fuse_status(wire)
+                .map_err(|e| self.offset_in_binary(0, e))
+            {
+                Ok(status) => statuses.fuses.push((fuse, status)),
+                Err(PatcherError::Binary(BinaryError::FuseDoesNotExist(_))) => {
+                    statuses.absent.push(fuse)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        statuses.fuses.sort_by_key(|(fuse, _)| fuse.schema_pos());
+
+        for (position, &value) in wire.iter().enumerate() {
+            if Fuse::ALL.iter().any(|fuse| fuse.schema_pos() == position) {
+                continue;
+            }
+            let status = Fuse::decode(value).ok_or(BinaryError::MalformedFuseWire {
+                position,
+                value,
+                offset: self.wires[0].range.start + position,
+            })?;
+            statuses.unknown.push((position, status));
+        }
+
+        Ok(statuses)
+    }
+
+    /// Returns the status of every fuse in [Fuse::all] that the application's first [fuse wire](ElectronApp::wires)
+    /// contains, in the order of the wire.
+    ///
+    /// Fuses that the wire is too short to contain, or that have a value this library doesn't understand, are left
+    /// out. Use [get_all_fuse_statuses](ElectronApp::get_all_fuse_statuses) to find out why a fuse is missing.
+    pub fn all_fuse_statuses(&self) -> Vec<(Fuse, FuseStatus)> {
+        Fuse::all()
+            .iter()
+            .filter_map(|&fuse| self.get_fuse_status(fuse).ok().map(|status| (fuse, status)))
+            .collect()
+    }
+
+    /// Parses and returns the status of the fuse at a position in the application's first
+    /// [fuse wire](ElectronApp::wires), whether or not it's a fuse known by this library.
+    ///
+    /// This allows fuses added by newer versions of Electron to be read before this library has a [Fuse] for them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [FuseIndexOutOfRange](BinaryError::FuseIndexOutOfRange) if the wire is too short to
+    /// contain the position, or [MalformedFuseWire](BinaryError::MalformedFuseWire) if it doesn't contain a valid
+    /// status.
+    pub fn raw_fuse(&self, index: usize) -> Result<FuseStatus, PatcherError> {
+        Self::raw_fuse_status(self.wire(0)?, index).map_err(|e| self.offset_in_binary(0, e))
+    }
+
+    /// Parses the fuse at a position in a fuse wire. Errors refer to the position in the wire, which
+    /// [offset_in_binary](Self::offset_in_binary) converts to its position in the binary.
+    fn raw_fuse_status(wire: &[u8], index: usize) -> Result<FuseStatus, PatcherError> {
+        let value = *wire.get(index).ok_or(BinaryError::FuseIndexOutOfRange {
+            index,
+            len: wire.len(),
+        })?;
+
+        Fuse::decode(value).ok_or_else(|| {
+            BinaryError::MalformedFuseWire {
+                position: index,
+                value,
+                offset: index,
+            }
+            .into()
+        })
+    }
+
+    /// Returns whether the application encrypts its cookies on disk, as controlled by the
+    /// [EncryptedCookies](Fuse::EncryptedCookies) fuse.
+    ///
+    /// If the fuse wire is too short to contain the fuse, the application was built with a version of Electron that
+    /// doesn't support cookie encryption and `false` is returned.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [RemovedFuse](PatcherError::RemovedFuse) if the fuse is marked as removed, since the
+    /// wire no longer describes whether cookies are encrypted. Other errors are the same as
+    /// [get_fuse_status](ElectronApp::get_fuse_status).
+    pub fn cookie_encryption_enabled(&self) -> Result<bool, PatcherError> {
+        match self.get_fuse_status(Fuse::EncryptedCookies) {
+            Ok(FuseStatus::Present(enabled)) => Ok(enabled),
+            Ok(FuseStatus::Removed) => Err(PatcherError::RemovedFuse(Fuse::EncryptedCookies)),
+            // Reading a fuse never reports a modification.
+            Ok(FuseStatus::Modified) => unreachable!(),
+            Err(PatcherError::Binary(BinaryError::FuseDoesNotExist(_))) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks that every byte in every fuse wire is a valid fuse status, including positions that don't belong to a
+    /// fuse known by this library.
+    ///
+    /// This is stricter than reading each fuse, and can be used as a final check for corruption or tampering
+    /// before an application is signed.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error describing the first invalid byte found.
+    pub fn assert_wire_well_formed(&self) -> Result<(), PatcherError> {
+        for index in 0..self.wires.len() {
+            let wire = self.wire(index)?;
+
+            if let Some(position) = wire
+                .iter()
+                .position(|b| !matches!(*b, Fuse::ENABLED | Fuse::DISABLED | Fuse::REMOVED))
+            {
+                return Err(BinaryError::MalformedFuseWire {
+                    position,
+                    value: wire[position],
+                    offset: self.wires[index].range.start + position,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the positions in the longest fuse wire that don't belong to any known fuse.
+    #[cfg(feature = "patch")]
+    pub(crate) fn unknown_fuse_positions(&self) -> Vec<usize> {
+        let longest = self.wires.iter().map(|wire| wire.range.len()).max();
+
+        (0..longest.unwrap_or_default())
+            .filter(|pos| !Fuse::ALL.iter().any(|fuse| fuse.schema_pos() == *pos))
+            .collect()
+    }
+
+    /// Searches for embedded paths that suggest the application loads its code from a directory instead of an asar
+    /// archive, returning the first one found and its position.
+    ///
+    /// This is only a heuristic. Applications that do this will fail to start once
+    /// [OnlyLoadAppFromAsar](Fuse::OnlyLoadAppFromAsar) is enabled.
+    #[cfg(feature = "patch")]
+    pub(crate) fn non_asar_app_path(&self) -> Option<(usize, &'static str)> {
+        const NON_ASAR_APP_PATHS: &[&str] =
+            &["resources/app/", "resources\\app\\", "Resources/app/"];
+
+        NON_ASAR_APP_PATHS
+            .iter()
+            .filter_map(|path| {
+                memchr::memmem::find(&self.contents, path.as_bytes()).map(|offset| (offset, *path))
+            })
+            .min()
+    }
+
+    /// Toggles the fuse at a position in every [fuse wire](ElectronApp::wires), whether or not it's a fuse known by
+    /// this library.
+    ///
+    /// This works the same way as [set_fuse_status](ElectronApp::set_fuse_status), and allows fuses added by newer
+    /// versions of Electron to be changed before this library has a [Fuse] for them.
+    ///
+    /// This is the one forced fuse write: the position doesn't need to be a known fuse, so it isn't checked against
+    /// the schema. It still can't write outside of a fuse wire, over a removed fuse, or anything other than enabled
+    /// or disabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [RemovedRawFuse](PatcherError::RemovedRawFuse) if the fuse is marked as removed.
+    /// Other errors are the same as [raw_fuse](ElectronApp::raw_fuse). Nothing is modified if any of the wires return
+    /// an error.
+    #[cfg(feature = "patch")]
+    pub fn set_raw_fuse(
+        &mut self,
+        index: usize,
+        enabled: bool,
+    ) -> Result<FuseStatus, PatcherError> {
+        let mut writes = Vec::new();
+
+        for wire in 0..self.wires.len() {
+            match Self::raw_fuse_status(self.wire(wire)?, index)
+                .map_err(|e| self.offset_in_binary(wire, e))?
+            {
+                FuseStatus::Present(current) if current != enabled => {
+                    writes.push(self.wires[wire].range.start + index)
+                }
+                FuseStatus::Removed => return Err(PatcherError::RemovedRawFuse(index)),
+                _ => {}
+            }
+        }
+
+        if !writes.is_empty() {
+            self.check_signature()?;
+        }
+
+        let value = if enabled {
+            Fuse::ENABLED
+        } else {
+            Fuse::DISABLED
+        };
+        for &offset in &writes {
+            self.check_write(ModifiedItem::RawFuse(index), offset, &[value])?;
+        }
+        for &offset in &writes {
+            self.write_bytes(ModifiedItem::RawFuse(index), offset, &[value])?;
+        }
+
+        if writes.is_empty() {
+            Ok(FuseStatus::Present(enabled))
+        } else {
+            Ok(FuseStatus::Modified)
+        }
+    }
+
+    /// Toggles a fuse in the application binary based off the provided value.
+    ///
+    /// If the application contains more than one [fuse wire](ElectronApp::wires), the fuse is changed in all of
+    /// them. Use [set_fuse_status_in](ElectronApp::set_fuse_status_in) to change a single wire.
+    ///
+    /// # Return
+    ///
+    /// Returns the [result](FuseResult) of the operation if it succeeded. If the fuse was modified in any wire,
+    /// [Modified](FuseStatus::Modified) is returned.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a fuse wire couldn't be found in the provided binary or
+    /// if a modification of a removed fuse was attempted. Nothing is modified if any of the wires return an error.
+    #[cfg(feature = "patch")]
+    pub fn set_fuse_status(
+        &mut self,
+        fuse: Fuse,
+        enabled: bool,
+    ) -> Result<FuseStatus, PatcherError> {
+        let plan = self.plan_fuse_change(fuse, enabled)?;
+        self.apply_fuse_plan(&plan)
+    }
+
+    /// Computes what [set_fuse_status](ElectronApp::set_fuse_status) would do, without modifying the application.
+    ///
+    /// The plan holds the status `set_fuse_status` would return and every byte it would write, with its offset and
+    /// current value, so the changes can be reviewed before they're made. Calling `set_fuse_status` afterwards makes
+    /// exactly these changes, as long as the application wasn't modified in between.
+    ///
+    /// ```
+    /// # use electron_hardener::{ElectronApp, Fuse, fuses::FuseStatus};
+    /// # let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+    /// let mut app = ElectronApp::from_bytes(&mut bytes)?;
+    ///
+    /// let plan = app.plan_fuse_change(Fuse::RunAsNode, false)?;
+    /// assert_eq!(plan.status, FuseStatus::Modified);
+    /// for change in &plan.changes {
+    ///     println!("{:#x}: {:?} -> {:?}", change.offset, change.original, change.modified);
+    /// }
+    /// assert!(!app.is_dirty());
+    /// # Ok::<(), electron_hardener::PatcherError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [set_fuse_status](ElectronApp::set_fuse_status), except for
+    /// [SignatureInvalidated](PatcherError::SignatureInvalidated), which is only checked when the changes are made.
+    #[cfg(feature = "patch")]
+    pub fn plan_fuse_change(&self, fuse: Fuse, enabled: bool) -> Result<FusePlan, PatcherError> {
+        self.plan_fuse_change_in(0..self.wires.len(), fuse, enabled, &[])
+    }
+
+    /// Plans a fuse change in some of the application's fuse wires, as if the `earlier` changes had already been made.
+    #[cfg(feature = "patch")]
+    pub(crate) fn plan_fuse_change_in(
+        &self,
+        wires: impl IntoIterator<Item = usize>,
+        fuse: Fuse,
+        enabled: bool,
+        earlier: &[JournalEntry],
+    ) -> Result<FusePlan, PatcherError> {
+        let mut status = None;
+        let mut changes = Vec::new();
+
+        for wire in wires {
+            let (wire_status, write) = self.plan_wire_change(wire, fuse, enabled, earlier)?;
+
+            if let Some(change) = write {
+                changes.push(change);
+                status = Some(wire_status);
+            }
+            status.get_or_insert(wire_status);
+        }
+
+        Ok(FusePlan {
+            fuse,
+            enabled,
+            status: status.expect("applications have at least one fuse wire"),
+            changes,
+        })
+    }
+
+    /// Makes the changes of a fuse plan, returning its status.
+    #[cfg(feature = "patch")]
+    fn apply_fuse_plan(&mut self, plan: &FusePlan) -> Result<FuseStatus, PatcherError> {
+        if !plan.changes.is_empty() {
+            self.check_signature()?;
+        }
+
+        for change in &plan.changes {
+            self.check_write(
+                ModifiedItem::Fuse(plan.fuse),
+                change.offset,
+                &change.modified,
+            )?;
+        }
+        for change in &plan.changes {
+            self.write_bytes(
+                ModifiedItem::Fuse(plan.fuse),
+                change.offset,
+                &change.modified,
+            )?;
+        }
+
+        Ok(plan.status)
+    }
+
+    /// Toggles a fuse in one of the application's [fuse wires](ElectronApp::wires) based off the provided value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [FuseWireDoesNotExist](BinaryError::FuseWireDoesNotExist) if there is no wire at the
+    /// provided index. Other errors are the same as [set_fuse_status](ElectronApp::set_fuse_status).
+    #[cfg(feature = "patch")]
+    pub fn set_fuse_status_in(
+        &mut self,
+        wire: usize,
+        fuse: Fuse,
+        enabled: bool,
+    ) -> Result<FuseStatus, PatcherError> {
+        let plan = self.plan_fuse_change_in(std::iter::once(wire), fuse, enabled, &[])?;
+        self.apply_fuse_plan(&plan)
+    }
+
+    /// Sets every fuse in a [configuration](FuseConfig) that isn't marked as [Inherit](FuseSetting::Inherit), in
+    /// every [fuse wire](ElectronApp::wires).
+    ///
+    /// # Return
+    ///
+    /// Returns the [result](FuseStatus) for each fuse that was set, in the order of the configuration. Fuses marked as
+    /// removed in the application are reported as [Removed](FuseStatus::Removed) instead of failing.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [set_fuse_status](ElectronApp::set_fuse_status). Nothing is modified
+    /// if any of the fuses return an error.
+    #[cfg(feature = "patch")]
+    pub fn apply_fuse_config(
+        &mut self,
+        config: &FuseConfig,
+    ) -> Result<Vec<(Fuse, FuseStatus)>, PatcherError> {
+        let mut results = Vec::new();
+        let mut writes = Vec::new();
+
+        for &(fuse, setting) in config.settings() {
+            let enabled = match setting {
+                FuseSetting::On => true,
+                FuseSetting::Off => false,
+                FuseSetting::Inherit => continue,
+            };
+
+            let mut status = None;
+            for wire in 0..self.wires.len() {
+                let (wire_status, write) = match self.plan_wire_change(wire, fuse, enabled, &[]) {
+                    Err(PatcherError::RemovedFuse(_)) => (FuseStatus::Removed, None),
+                    change => change?,
+                };
+
+                if let Some(change) = write {
+                    writes.push((fuse, change.offset, change.modified[0]));
+                    status = Some(wire_status);
+                }
+                status.get_or_insert(wire_status);
+            }
+
+            if let Some(status) = status {
+                results.push((fuse, status));
+            }
+        }
+
+        if !writes.is_empty() {
+            self.check_signature()?;
+        }
+        for &(fuse, offset, value) in &writes {
+            self.check_write(ModifiedItem::Fuse(fuse), offset, &[value])?;
+        }
+        for (fuse, offset, value) in writes {
+            self.write_bytes(ModifiedItem::Fuse(fuse), offset, &[value])?;
+        }
+
+        Ok(results)
+    }
+
+    /// Computes how a fuse change would affect a wire once the `earlier` changes are made, returning its status and
+    /// the byte that would be written.
+    #[cfg(feature = "patch")]
+    fn plan_wire_change(
+        &self,
+        wire: usize,
+        fuse: Fuse,
+        enabled: bool,
+        earlier: &[JournalEntry],
+    ) -> Result<(FuseStatus, Option<JournalEntry>), PatcherError> {
+        let mut contents = self.wire(wire)?.to_vec();
+        let start = self.wires[wire].range.start;
+        for change in earlier {
+            for (i, &value) in change.modified.iter().enumerate() {
+                if let Some(byte) = (change.offset + i)
+                    .checked_sub(start)
+                    .and_then(|pos| contents.get_mut(pos))
+                {
+                    *byte = value;
+                }
+            }
+        }
+        let pos = fuse.schema_pos();
+        let original = contents.get(pos).copied();
+
+        let status = if enabled {
+            fuse.enable(&mut contents)
+        } else {
+            fuse.disable(&mut contents)
+        }
+        .map_err(|e| self.offset_in_binary(wire, e))?;
+
+        let write = match original {
+            Some(original) if status == FuseStatus::Modified => Some(JournalEntry {
+                offset: start + pos,
+                original: vec![original],
+                modified: vec![contents[pos]],
+            }),
+            _ => None,
+        };
+
+        Ok((status, write))
+    }
+
+    /// Checks a write against the only changes this library should ever make to a fuse wire.
+    ///
+    /// Fuses are only switched between enabled and disabled, at their position in one of the fuse wires. Raw fuses
+    /// are [forced](Self::set_raw_fuse): any position in a wire can be written, but only with enabled or disabled
+    /// and never over a removed fuse. Reverted bytes can only switch fuses back, and nothing else may be written
+    /// inside a fuse wire.
+    #[cfg(feature = "patch")]
+    pub(crate) fn check_write(
+        &self,
+        item: ModifiedItem,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<(), PatcherError> {
+        let illegal = |pos: usize, to: u8| PatcherError::IllegalFuseWrite {
+            offset: pos,
+            from: self.contents.get(pos).copied().unwrap_or_default(),
+            to,
+        };
+
+        let fuse_pos = match item {
+            ModifiedItem::Fuse(fuse) => Some(fuse.schema_pos()),
+            ModifiedItem::RawFuse(index) => Some(index),
+            _ => None,
+        };
+
+        if let Some(pos) = fuse_pos {
+            let to = match bytes {
+                [to] => *to,
+                _ => return Err(illegal(offset, bytes.first().copied().unwrap_or_default())),
+            };
+            let in_wire = self
+                .wires
+                .iter()
+                .any(|wire| wire.range.contains(&offset) && offset - wire.range.start == pos);
+            let from = self.contents.get(offset).copied();
+
+            let legal = in_wire
+                && match (item, from) {
+                    (ModifiedItem::RawFuse(_), Some(from)) => {
+                        from != Fuse::REMOVED && matches!(to, Fuse::ENABLED | Fuse::DISABLED)
+                    }
+                    (_, Some(from)) => Fuse::is_legal_change(from, to),
+                    (_, None) => false,
+                };
+
+            return if legal {
+                Ok(())
+            } else {
+                Err(illegal(offset, to))
+            };
+        }
+
+        let range = offset..(offset + bytes.len());
+        for wire in &self.wires {
+            let start = range.start.max(wire.range.start);
+            let end = range.end.min(wire.range.end);
+
+            for pos in start..end {
+                let (from, to) = (self.contents[pos], bytes[pos - offset]);
+                let legal = item == ModifiedItem::Reverted
+                    && (from == to || Fuse::is_legal_change(from, to));
+                if !legal {
+                    return Err(illegal(pos, to));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+    const FUSE: Fuse = Fuse::RunAsNode;
+
+    fn get_wire() -> &'static [u8] {
+        let wire_pos = FuseWire::locate(TEST_BYTES, 0).unwrap().range();
+        &TEST_BYTES[wire_pos]
+    }
+
+    #[test]
+    fn sentinal_is_found() {
+        assert!(FuseWire::locate(TEST_BYTES, 0).is_ok());
+    }
+
+    #[test]
+    fn schema_version_is_read() {
+        let application_bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+        assert_eq!(app.schema_version(), Fuse::EXPECTED_VERSION);
+    }
+
+    #[test]
+    fn wire_is_found_in_section() {
+        let section_offset = 0x60;
+        let section = &TEST_BYTES[section_offset..0xC0];
+
+        let wire = FuseWire::locate(section, section_offset).unwrap();
+
+        assert_eq!(wire.version(), Fuse::EXPECTED_VERSION);
+        assert_eq!(
+            wire.range(),
+            FuseWire::locate(TEST_BYTES, 0).unwrap().range()
+        );
+    }
+
+    #[test]
+    fn enabled_fuse_is_correct() {
+        assert_eq!(
+            FUSE.fuse_status(get_wire()).unwrap(),
+            FuseStatus::Present(true)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn disabled_fuse_is_correct() {
+        let mut wire = get_wire().to_vec();
+        assert_eq!(FUSE.disable(&mut wire).unwrap(), FuseStatus::Modified);
+        assert_eq!(FUSE.fuse_status(&wire).unwrap(), FuseStatus::Present(false));
+    }
+
+    #[test]
+    fn disabled_fuse_value_is_read() {
+        let mut wire = get_wire().to_vec();
+        wire[FUSE.schema_pos()] = Fuse::DISABLED;
+        assert_eq!(FUSE.fuse_status(&wire).unwrap(), FuseStatus::Present(false));
+    }
+
+    #[test]
+    fn removed_fuse_is_correct() {
+        let mut wire = get_wire().to_vec();
+        wire[FUSE.schema_pos()] = Fuse::REMOVED;
+
+        assert_eq!(FUSE.fuse_status(&wire).unwrap(), FuseStatus::Removed);
+    }
+
+    #[test]
+    fn unknown_fuse_value_is_correct() {
+        let value = 9;
+        let mut wire = get_wire().to_vec();
+        wire[FUSE.schema_pos()] = value;
+
+        assert_eq!(
+            FUSE.fuse_status(&wire),
+            Err(PatcherError::Binary(BinaryError::UnknownFuse {
+                fuse: FUSE,
+                value,
+                offset: FUSE.schema_pos(),
+            }))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn modfying_removed_fuse_errors() {
+        let mut wire = get_wire().to_vec();
+        wire[FUSE.schema_pos()] = Fuse::REMOVED;
+
+        assert_eq!(
+            FUSE.disable(&mut wire),
+            Err(PatcherError::RemovedFuse(FUSE))
+        );
+        assert_eq!(FUSE.enable(&mut wire), Err(PatcherError::RemovedFuse(FUSE)));
+    }
+
+    #[test]
+    fn fuse_wire_metadata_is_exposed() {
+        let bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        assert_eq!(app.fuse_wire_version(), 1);
+        assert_eq!(app.fuse_wire_len(), Fuse::all().len());
+        assert_eq!(app.fuse_wire(), get_wire());
+        assert_eq!(app.fuse_wire().len(), app.fuse_wire_len());
+
+        // The length comes from the byte stored before the wire.
+        let header = app.wires()[0].range().start - 1;
+        assert_eq!(usize::from(TEST_BYTES[header]), app.fuse_wire_len());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn newer_fuse_versions_are_opt_in() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start - 2] = Fuse::EXPECTED_VERSION + 1;
+        bytes[wire.start + Fuse::LoadBrowserProcessSpecificV8Snapshot.schema_pos()] = b'x';
+
+        assert!(matches!(
+            ElectronApp::from_bytes(&mut bytes),
+            Err(PatcherError::FuseVersion {
+                expected: 1,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            ElectronApp::from_bytes_with_options(&mut bytes, &ParseOptions::default()),
+            Err(PatcherError::FuseVersion { .. })
+        ));
+
+        let options = ParseOptions {
+            allow_unknown_fuse_version: true,
+        };
+        let app = ElectronApp::from_bytes_with_options(&mut bytes, &options).unwrap();
+        assert_eq!(app.fuse_wire_version(), 2);
+        assert_eq!(
+            app.get_fuse_status(Fuse::RunAsNode),
+            Ok(FuseStatus::Present(true))
+        );
+        assert_eq!(
+            app.get_fuse_status(Fuse::LoadBrowserProcessSpecificV8Snapshot),
+            Err(PatcherError::Binary(BinaryError::UnknownFuse {
+                fuse: Fuse::LoadBrowserProcessSpecificV8Snapshot,
+                value: b'x',
+                offset: wire.start + Fuse::LoadBrowserProcessSpecificV8Snapshot.schema_pos(),
+            }))
+        );
+        assert_eq!(
+            app.get_fuse_status(Fuse::LoadBrowserProcessSpecificV8Snapshot)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "The LoadBrowserProcessSpecificV8Snapshot fuse returned an unknown value of '120' at offset {}",
+                wire.start + 6
+            )
+        );
+
+        // Older versions are never accepted.
+        bytes[wire.start - 2] = 0;
+        assert!(matches!(
+            ElectronApp::from_bytes_with_options(&mut bytes, &options),
+            Err(PatcherError::FuseVersion { found: 0, .. })
+        ));
+    }
+
+    #[cfg(feature = "patch")]
+    #[test]
+    fn fuse_config_is_applied() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start + Fuse::NodeCliInspect.schema_pos()] = Fuse::REMOVED;
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let config = FuseConfig::new()
+            .set(Fuse::RunAsNode, FuseSetting::On)
+            .set(Fuse::EncryptedCookies, FuseSetting::On)
+            .set(Fuse::NodeOptions, FuseSetting::Inherit)
+            .set(Fuse::NodeCliInspect, FuseSetting::Off)
+            .set(Fuse::LoadBrowserProcessSpecificV8Snapshot, FuseSetting::On)
+            .set(Fuse::RunAsNode, FuseSetting::Off);
+
+        assert_eq!(
+            app.apply_fuse_config(&config),
+            Ok(vec![
+                (Fuse::RunAsNode, FuseStatus::Modified),
+                (Fuse::EncryptedCookies, FuseStatus::Modified),
+                (Fuse::NodeCliInspect, FuseStatus::Removed),
+                (
+                    Fuse::LoadBrowserProcessSpecificV8Snapshot,
+                    FuseStatus::Present(true)
+                ),
+            ])
+        );
+        assert_eq!(&bytes[wire], b"011r001");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn fuse_config_is_deserialized() {
+        let config: FuseConfig = serde_json::from_str(
+            r#"{
+                "version": "1",
+                "resetAdHocDarwinSignature": true,
+                "RunAsNode": "off",
+                "enableCookieEncryption": true,
+                "2": "inherit",
+                "only_load_app_from_asar": "ON"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.settings(),
+            &[
+                (Fuse::RunAsNode, FuseSetting::Off),
+                (Fuse::EncryptedCookies, FuseSetting::On),
+                (Fuse::NodeOptions, FuseSetting::Inherit),
+                (Fuse::OnlyLoadAppFromAsar, FuseSetting::On),
+            ]
+        );
+
+        for invalid in &[
+            r#"{"version": "2"}"#,
+            r#"{"RunAsNod": "off"}"#,
+            r#"{"RunAsNode": "maybe"}"#,
+            r#"{"99": true}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<FuseConfig>(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn raw_fuses_agree_with_typed_fuses() {
+        let bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        for &fuse in Fuse::all() {
+            assert_eq!(app.raw_fuse(fuse.schema_pos()), app.get_fuse_status(fuse));
+        }
+
+        let len = app.wires()[0].range().len();
+        assert_eq!(
+            app.raw_fuse(len),
+            Err(PatcherError::Binary(BinaryError::FuseIndexOutOfRange {
+                index: len,
+                len
+            }))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn raw_fuses_are_set_like_typed_fuses() {
+        for &fuse in Fuse::all() {
+            for &enabled in &[true, false] {
+                let mut typed = TEST_BYTES.to_vec();
+                let mut raw = TEST_BYTES.to_vec();
+
+                let typed_status = ElectronApp::from_bytes(&mut typed)
+                    .unwrap()
+                    .set_fuse_status(fuse, enabled);
+                let raw_status = ElectronApp::from_bytes(&mut raw)
+                    .unwrap()
+                    .set_raw_fuse(fuse.schema_pos(), enabled);
+
+                assert_eq!(raw_status, typed_status);
+                assert!(raw == typed, "{:?} was set differently", fuse);
+            }
+        }
+
+        let mut bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start + FUSE.schema_pos()] = Fuse::REMOVED;
+        let original = bytes.clone();
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(
+            app.set_raw_fuse(FUSE.schema_pos(), false),
+            Err(PatcherError::RemovedRawFuse(FUSE.schema_pos()))
+        );
+        assert!(app.set_raw_fuse(wire.len(), true).is_err());
+        assert!(bytes == original);
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn illegal_fuse_writes_are_rejected() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        let pos = wire.start + FUSE.schema_pos();
+        bytes[pos] = Fuse::REMOVED;
+        let original = bytes.clone();
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(
+            app.write_bytes(ModifiedItem::Fuse(FUSE), pos, &[Fuse::DISABLED]),
+            Err(PatcherError::IllegalFuseWrite {
+                offset: pos,
+                from: Fuse::REMOVED,
+                to: Fuse::DISABLED
+            })
+        );
+        // Forced writes still can't bring a removed fuse back.
+        assert!(app
+            .write_bytes(
+                ModifiedItem::RawFuse(FUSE.schema_pos()),
+                pos,
+                &[Fuse::ENABLED]
+            )
+            .is_err());
+        assert!(app.modifications().is_empty());
+        assert!(bytes == original);
+
+        let mut bytes = TEST_BYTES.to_vec();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let pos = wire.start + FUSE.schema_pos();
+        let other = wire.start + Fuse::NodeOptions.schema_pos();
+
+        // The fuse is enabled, so only disabling it is a change.
+        assert!(app
+            .write_bytes(ModifiedItem::Fuse(FUSE), pos, &[Fuse::REMOVED])
+            .is_err());
+        assert!(app
+            .write_bytes(ModifiedItem::Fuse(FUSE), pos, &[Fuse::ENABLED])
+            .is_err());
+        // A fuse is only written at its own position in a wire.
+        assert!(app
+            .write_bytes(ModifiedItem::Fuse(FUSE), other, &[Fuse::DISABLED])
+            .is_err());
+        assert!(app
+            .write_bytes(ModifiedItem::Fuse(FUSE), wire.start - 1, &[Fuse::DISABLED])
+            .is_err());
+        assert!(app
+            .write_bytes(
+                ModifiedItem::RawFuse(wire.len()),
+                wire.end,
+                &[Fuse::DISABLED]
+            )
+            .is_err());
+        // Patch targets and reverted bytes can't write into a wire.
+        assert_eq!(
+            app.write_bytes(
+                ModifiedItem::Target(crate::patcher::PatchTarget::NodeInspect),
+                wire.start,
+                b"x"
+            ),
+            Err(PatcherError::IllegalFuseWrite {
+                offset: wire.start,
+                from: app.contents[wire.start],
+                to: b'x'
+            })
+        );
+        assert!(app.write_bytes(ModifiedItem::Reverted, pos, b"x").is_err());
+        assert!(app.modifications().is_empty());
+
+        assert!(app
+            .write_bytes(ModifiedItem::Fuse(FUSE), pos, &[Fuse::DISABLED])
+            .is_ok());
+        assert!(app
+            .write_bytes(ModifiedItem::Reverted, pos, &[Fuse::ENABLED])
+            .is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn random_fuse_changes_keep_wire_valid() {
+        // A small xorshift generator keeps this test deterministic without extra dependencies.
+        let mut state: u32 = 0x9E37_79B9;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+
+        let wire = FuseWire::locate(TEST_BYTES, 0).unwrap().range();
+        for _ in 0..64 {
+            let mut bytes = TEST_BYTES.to_vec();
+            // Mark a random fuse as removed to make sure it's never touched.
+            let removed = Fuse::ALL[next() as usize % Fuse::ALL.len()];
+            bytes[wire.start + removed.schema_pos()] = Fuse::REMOVED;
+            let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+            for _ in 0..256 {
+                let fuse = Fuse::ALL[next() as usize % Fuse::ALL.len()];
+                let enabled = next() % 2 == 0;
+                let (result, removed_error) = if next() % 4 == 0 {
+                    (
+                        app.set_raw_fuse(fuse.schema_pos(), enabled),
+                        PatcherError::RemovedRawFuse(fuse.schema_pos()),
+                    )
+                } else {
+                    (
+                        app.set_fuse_status(fuse, enabled),
+                        PatcherError::RemovedFuse(fuse),
+                    )
+                };
+
+                if fuse == removed {
+                    assert_eq!(result, Err(removed_error));
+                } else {
+                    assert!(result.is_ok());
+                }
+
+                let contents = &app.contents[wire.clone()];
+                assert!(contents
+                    .iter()
+                    .all(|b| matches!(*b, Fuse::ENABLED | Fuse::DISABLED | Fuse::REMOVED)));
+                assert_eq!(contents[removed.schema_pos()], Fuse::REMOVED);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn test_app_fuse_actions() {
+        let mut application_bytes = TEST_BYTES.to_vec();
+        let mut app = ElectronApp::from_bytes(&mut application_bytes).unwrap();
+
+        assert_eq!(
+            app.get_fuse_status(FUSE).unwrap(),
+            FuseStatus::Present(true)
+        );
+
+        // Setting the fuse to what it already is shouldn't modify anything.
+        assert_eq!(
+            app.set_fuse_status(FUSE, true).unwrap(),
+            FuseStatus::Present(true)
+        );
+
+        assert_eq!(
+            app.set_fuse_status(FUSE, false).unwrap(),
+            FuseStatus::Modified
+        );
+        assert_eq!(
+            app.get_fuse_status(FUSE).unwrap(),
+            FuseStatus::Present(false)
+        );
+    }
+
+    #[test]
+    fn can_read_all_fuses() {
+        let wire = get_wire();
+
+        for fuse in Fuse::into_enum_iter() {
             assert!(matches!(
                 fuse.fuse_status(wire).unwrap(),
                 FuseStatus::Present(_)
@@ -343,6 +2182,810 @@ mod tests {
     }
 
     #[test]
+    fn all_fuses_are_listed() {
+        assert_eq!(
+            Fuse::all().to_vec(),
+            Fuse::into_enum_iter().collect::<Vec<_>>()
+        );
+        assert!(Fuse::all()
+            .windows(2)
+            .all(|pair| pair[0].schema_pos() < pair[1].schema_pos()));
+    }
+
+    #[test]
+    fn fuse_names_round_trip() {
+        for &fuse in Fuse::all() {
+            assert_eq!(fuse.to_string().parse::<Fuse>(), Ok(fuse));
+        }
+        assert_eq!(Fuse::RunAsNode.to_string(), "RunAsNode");
+
+        for name in [
+            "EnableCookieEncryption",
+            "enableCookieEncryption",
+            "cookie_encryption",
+            "EncryptedCookies",
+        ] {
+            assert_eq!(name.parse::<Fuse>(), Ok(Fuse::EncryptedCookies), "{}", name);
+        }
+
+        let error = "RunAsNod".parse::<Fuse>().unwrap_err();
+        assert_eq!(error.name(), "RunAsNod");
+        assert!(error.to_string().contains("RunAsNode"));
+        assert!("".parse::<Fuse>().is_err());
+        assert!("runasnode".parse::<Fuse>().is_err());
+        assert!("éRunAsNode".parse::<Fuse>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn fuse_statuses_round_trip_through_serde() {
+        let statuses = vec![
+            (Fuse::RunAsNode, FuseStatus::Present(true)),
+            (Fuse::EncryptedCookies, FuseStatus::Present(false)),
+            (Fuse::NodeOptions, FuseStatus::Modified),
+            (Fuse::NodeCliInspect, FuseStatus::Removed),
+        ];
+
+        let json = serde_json::to_string(&statuses).unwrap();
+        assert_eq!(
+            json,
+            r#"[["RunAsNode",{"Present":true}],["EnableCookieEncryption",{"Present":false}],["EnableNodeOptionsEnvironmentVariable","Modified"],["EnableNodeCliInspectArguments","Removed"]]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<(Fuse, FuseStatus)>>(&json).unwrap(),
+            statuses
+        );
+
+        assert!(serde_json::from_str::<Fuse>(r#""run_as_node""#).is_ok());
+        assert!(serde_json::from_str::<Fuse>(r#""RunAsNod""#).is_err());
+    }
+
+    #[test]
+    fn all_fuse_statuses_are_read() {
+        let bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        let statuses = app.get_all_fuse_statuses().unwrap();
+        assert_eq!(statuses.fuses.len(), Fuse::ALL.len());
+        for (fuse, status) in &statuses.fuses {
+            assert_eq!(app.get_fuse_status(*fuse).unwrap(), *status);
+        }
+        assert!(statuses.absent.is_empty());
+        assert!(statuses.unknown.is_empty());
+    }
+
+    #[test]
+    fn all_fuse_statuses_skip_missing_fuses() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        assert_eq!(
+            app.all_fuse_statuses(),
+            app.get_all_fuse_statuses().unwrap().fuses
+        );
+
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start - 1] = 2;
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        assert_eq!(
+            app.all_fuse_statuses(),
+            vec![
+                (Fuse::RunAsNode, FuseStatus::Present(true)),
+                (Fuse::EncryptedCookies, FuseStatus::Present(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn all_fuse_statuses_tolerate_other_wire_lengths() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start - 1] = 2;
+
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        let statuses = app.get_all_fuse_statuses().unwrap();
+        assert_eq!(
+            statuses.fuses,
+            vec![
+                (Fuse::RunAsNode, FuseStatus::Present(true)),
+                (Fuse::EncryptedCookies, FuseStatus::Present(false)),
+            ]
+        );
+        assert_eq!(statuses.absent, Fuse::ALL[2..].to_vec());
+
+        // A fuse from a newer version of Electron, appended over the padding after the wire.
+        let mut bytes = TEST_BYTES.to_vec();
+        bytes[wire.start - 1] += 1;
+        bytes[wire.end] = Fuse::REMOVED;
+
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        let statuses = app.get_all_fuse_statuses().unwrap();
+        assert_eq!(statuses.fuses.len(), Fuse::ALL.len());
+        assert_eq!(statuses.unknown, vec![(wire.len(), FuseStatus::Removed)]);
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn asar_only_fuse_is_flipped_independently() {
+        let mut wire = get_wire().to_vec();
+        let fuse = Fuse::OnlyLoadAppFromAsar;
+        let neighbors = [
+            Fuse::EmbeddedAsarIntegrityValidation,
+            Fuse::LoadBrowserProcessSpecificV8Snapshot,
+        ];
+        let original: Vec<_> = neighbors
+            .iter()
+            .map(|neighbor| neighbor.fuse_status(&wire).unwrap())
+            .collect();
+
+        assert_eq!(fuse.fuse_status(&wire).unwrap(), FuseStatus::Present(false));
+
+        fuse.enable(&mut wire).unwrap();
+        assert_eq!(fuse.fuse_status(&wire).unwrap(), FuseStatus::Present(true));
+        for (neighbor, status) in neighbors.iter().zip(&original) {
+            assert_eq!(neighbor.fuse_status(&wire).unwrap(), *status);
+        }
+
+        fuse.disable(&mut wire).unwrap();
+        assert_eq!(wire, get_wire());
+    }
+
+    #[test]
+    fn fuse_ids_are_stable() {
+        // These must never change. New fuses should be added to the end.
+        let pinned = [
+            (Fuse::RunAsNode, 0),
+            (Fuse::EncryptedCookies, 1),
+            (Fuse::NodeOptions, 2),
+            (Fuse::NodeCliInspect, 3),
+            (Fuse::EmbeddedAsarIntegrityValidation, 4),
+            (Fuse::OnlyLoadAppFromAsar, 5),
+            (Fuse::LoadBrowserProcessSpecificV8Snapshot, 6),
+        ];
+
+        assert_eq!(pinned.len(), Fuse::ALL.len());
+        for (fuse, id) in pinned.iter().copied() {
+            assert_eq!(fuse.id(), id);
+            assert_eq!(Fuse::from_id(id), Some(fuse));
+        }
+        assert_eq!(Fuse::from_id(pinned.len() as u16), None);
+    }
+
+    #[test]
+    fn cookie_encryption_is_detected() {
+        let application_bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+        assert_eq!(app.cookie_encryption_enabled(), Ok(false));
+
+        let mut application_bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&application_bytes, 0).unwrap().range();
+        application_bytes[wire.start + Fuse::EncryptedCookies.schema_pos()] = Fuse::ENABLED;
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+        assert_eq!(app.cookie_encryption_enabled(), Ok(true));
+
+        let mut application_bytes = TEST_BYTES.to_vec();
+        application_bytes[wire.start + Fuse::EncryptedCookies.schema_pos()] = Fuse::REMOVED;
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+        assert_eq!(
+            app.cookie_encryption_enabled(),
+            Err(PatcherError::RemovedFuse(Fuse::EncryptedCookies))
+        );
+
+        // A wire from before the fuse existed.
+        let mut application_bytes = TEST_BYTES.to_vec();
+        application_bytes[wire.start - 1] = 1;
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+        assert_eq!(app.cookie_encryption_enabled(), Ok(false));
+    }
+
+    #[test]
+    fn well_formed_wire_is_accepted() {
+        let application_bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_readonly(&application_bytes).unwrap();
+
+        assert_eq!(app.assert_wire_well_formed(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn malformed_wire_is_rejected() {
+        let mut application_bytes = TEST_BYTES.to_vec();
+        let wire = FuseWire::locate(&application_bytes, 0).unwrap().range();
+        application_bytes[wire.start - 1] += 1;
+        application_bytes[wire.end] = Fuse::DISABLED;
+
+        let mut app = ElectronApp::from_bytes(&mut application_bytes).unwrap();
+        // Corrupt a position past every known fuse after loading, which reading fuses would never catch.
+        app.contents[wire.end] = b'x';
+
+        let malformed = || {
+            PatcherError::Binary(BinaryError::MalformedFuseWire {
+                position: wire.len(),
+                value: b'x',
+                offset: wire.end,
+            })
+        };
+        assert_eq!(app.assert_wire_well_formed(), Err(malformed()));
+        assert_eq!(app.raw_fuse(wire.len()), Err(malformed()));
+    }
+
+    #[test]
+    fn empty_input_is_reported() {
+        assert!(matches!(
+            ElectronApp::from_bytes_readonly(&[]),
+            Err(PatcherError::Binary(BinaryError::EmptyInput))
+        ));
+
+        // A binary that isn't empty but can't contain a fuse wire isn't searched.
+        assert!(matches!(
+            ElectronApp::from_bytes_readonly(&[0]),
+            Err(PatcherError::Binary(BinaryError::InputTooSmall {
+                len: 1,
+                minimum: 35
+            }))
+        ));
+    }
+
+    #[cfg(feature = "patch")]
+    #[test]
+    fn app_is_read_from_and_written_to_paths() {
+        let dir =
+            std::env::temp_dir().join(format!("electron-hardener-paths-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("original");
+        let hardened = dir.join("hardened");
+        fs::write(&original, TEST_BYTES).unwrap();
+
+        let written = ElectronApp::from_path(&original)
+            .map_err(SourceError::from)
+            .and_then(|mut app| {
+                app.set_fuse_status(FUSE, false)?;
+                app.write_to_path(&hardened)
+            });
+        let contents = fs::read(&hardened);
+        let missing = ElectronApp::from_path(dir.join("missing")).err();
+        let _ = fs::remove_dir_all(&dir);
+
+        written.unwrap();
+        let mut expected = TEST_BYTES.to_vec();
+        ElectronApp::from_bytes(&mut expected)
+            .unwrap()
+            .set_fuse_status(FUSE, false)
+            .unwrap();
+        assert_eq!(contents.unwrap(), expected);
+
+        assert!(matches!(
+            missing,
+            Some(PatcherError::Io {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn read_only_apps_are_inspected() {
+        let bytes = crate::patcher::tests::test_app_bytes();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        assert_eq!(app.get_fuse_status(FUSE), Ok(FuseStatus::Present(true)));
+        assert_eq!(app.fuse_wire(), get_wire());
+        assert!(app.scan(crate::patcher::ElectronOption::JsFlags).is_some());
+
+        assert_eq!(
+            ElectronApp::from_bytes_readonly(&[]).err(),
+            Some(PatcherError::Binary(BinaryError::EmptyInput))
+        );
+    }
+
+    #[test]
+    fn read_only_apps_match_mutable_apps() {
+        let app_bytes = crate::patcher::tests::test_app_bytes();
+        let evil_patched = crate::patcher::tests::app_bytes(include_bytes!(
+            "../examples/fake_electron_flags_evil_patched.bin"
+        ));
+
+        let fixtures: &[&[u8]] = &[
+            &app_bytes,
+            &evil_patched,
+            include_bytes!("../examples/compat/electron_12.bin"),
+            include_bytes!("../examples/compat/electron_20.bin"),
+            include_bytes!("../examples/compat/electron_current.bin"),
+        ];
+
+        for &fixture in fixtures {
+            let view = ElectronApp::from_bytes_readonly(fixture).unwrap();
+            let copy = fixture.to_vec();
+            let app = ElectronApp::from_bytes_readonly(&copy).unwrap();
+
+            assert_eq!(view.wires(), app.wires());
+            assert_eq!(view.schema_version(), app.schema_version());
+            assert_eq!(view.fuse_wire_len(), app.fuse_wire_len());
+            assert_eq!(view.all_fuse_statuses(), app.all_fuse_statuses());
+            assert_eq!(view.patch_states(), app.patch_states());
+
+            for &target in crate::patcher::PatchTarget::ALL {
+                assert_eq!(view.is_present(target), app.is_present(target));
+            }
+        }
+
+        let view = ElectronApp::from_bytes_readonly(&app_bytes).unwrap();
+        assert!(view.is_present(crate::patcher::ElectronOption::JsFlags));
+        let view = ElectronApp::from_bytes_readonly(&evil_patched).unwrap();
+        assert!(!view.is_present(crate::patcher::ElectronOption::JsFlags));
+    }
+
+    #[cfg(feature = "patch")]
+    #[test]
+    fn apps_are_written_back() {
+        let path = std::env::temp_dir().join(format!(
+            "electron-hardener-write-back-{}",
+            std::process::id()
+        ));
+        fs::write(&path, TEST_BYTES).unwrap();
+
+        let written = ElectronApp::from_path(&path)
+            .map_err(SourceError::from)
+            .and_then(|mut app| {
+                assert_eq!(app.path(), Some(path.as_path()));
+                app.set_fuse_status(FUSE, false)?;
+                app.write_back()
+            });
+        let contents = fs::read(&path);
+        let _ = fs::remove_file(&path);
+
+        written.unwrap();
+        let app = ElectronApp::from_vec(contents.unwrap()).unwrap();
+        assert_eq!(app.get_fuse_status(FUSE), Ok(FuseStatus::Present(false)));
+
+        assert_eq!(app.path(), None);
+        assert!(matches!(
+            app.write_back(),
+            Err(SourceError::Patcher(PatcherError::NoPath))
+        ));
+    }
+
+    #[cfg(feature = "patch")]
+    #[test]
+    fn owned_apps_return_their_bytes() {
+        fn harden(bytes: Vec<u8>) -> Result<ElectronAppBuf, PatcherError> {
+            let mut app = ElectronApp::from_vec(bytes)?;
+            app.set_fuse_status(FUSE, false)?;
+            Ok(app)
+        }
+
+        let app = harden(TEST_BYTES.to_vec()).unwrap();
+        assert_eq!(app.get_fuse_status(FUSE), Ok(FuseStatus::Present(false)));
+
+        let mut expected = TEST_BYTES.to_vec();
+        let mut borrowed = ElectronApp::from_bytes(&mut expected).unwrap();
+        borrowed.set_fuse_status(FUSE, false).unwrap();
+        assert_eq!(borrowed.into_bytes(), app.into_bytes());
+
+        assert_eq!(
+            ElectronApp::from_vec(Vec::new()).err(),
+            Some(PatcherError::Binary(BinaryError::EmptyInput))
+        );
+    }
+
+    #[test]
+    fn truncated_binaries_are_rejected() {
+        let wire = FuseWire::locate(TEST_BYTES, 0).unwrap();
+        let truncated = TEST_BYTES[..(wire.range().start + 3)].to_vec();
+
+        assert_eq!(
+            ElectronApp::from_bytes_readonly(&truncated).err(),
+            Some(PatcherError::Binary(BinaryError::TruncatedFuseWire {
+                len: 7,
+                available: 3
+            }))
+        );
+
+        // An application whose wire was found before the binary was truncated is never read past its end.
+        let app = ElectronApp::with_contents(truncated[..].into(), vec![wire]);
+        let expected = Err(PatcherError::Binary(BinaryError::TruncatedFuseWire {
+            len: 7,
+            available: 3,
+        }));
+        assert_eq!(app.get_fuse_status(FUSE), expected);
+        assert_eq!(app.raw_fuse(0), expected);
+        assert!(app.get_all_fuse_statuses().is_err());
+        #[cfg(feature = "patch")]
+        {
+            let mut app = app;
+            assert_eq!(app.set_fuse_status(FUSE, false), expected);
+            assert_eq!(app.set_raw_fuse(0, false), expected);
+        }
+    }
+
+    #[test]
+    fn tiny_inputs_are_handled() {
+        let mut smallest = Fuse::SENTINEL.to_vec();
+        smallest.extend_from_slice(&[Fuse::EXPECTED_VERSION, 1, Fuse::ENABLED]);
+
+        for &len in &[0, 1, 31, 32, 33, 34] {
+            let expected = || {
+                if len == 0 {
+                    BinaryError::EmptyInput
+                } else {
+                    BinaryError::InputTooSmall { len, minimum: 35 }
+                }
+            };
+
+            for bytes in &[vec![0; len], smallest[..len].to_vec()] {
+                let bytes = bytes.clone();
+                assert_eq!(
+                    ElectronApp::from_bytes_readonly(&bytes).err(),
+                    Some(PatcherError::Binary(expected())),
+                    "{}",
+                    len
+                );
+                #[cfg(feature = "patch")]
+                {
+                    assert!(ElectronApp::from_bytes_with_options(
+                        &mut bytes.clone(),
+                        &ParseOptions::default()
+                    )
+                    .is_err());
+                    assert!(ElectronApp::from_bytes_with_deobfuscation(
+                        &mut bytes.clone(),
+                        &[SentinelTransform::Xor(0x5A)]
+                    )
+                    .is_err());
+                }
+
+                assert!(FuseWire::locate(&bytes, 0).is_err());
+                assert!(FuseWire::locate_all(&bytes, 0).is_err());
+                assert!(
+                    FuseWire::locate_obfuscated(&bytes, 0, &[SentinelTransform::Xor(0x5A)])
+                        .is_err()
+                );
+
+                for target in crate::patcher::PatchTarget::ALL {
+                    assert!(target.locate(&bytes).is_none());
+                    let (canonical, fallback) = target.count_matches(&bytes);
+                    assert_eq!(canonical + fallback.unwrap_or_default(), 0);
+                }
+                assert_eq!(crate::ElectronVersion::detect(&bytes), None);
+                assert!(crate::CoverageReport::scan(&bytes)
+                    .targets
+                    .iter()
+                    .all(|t| t.is_missing()));
+            }
+        }
+
+        // The smallest possible application is accepted.
+        let bytes = smallest.clone();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        assert_eq!(
+            app.get_fuse_status(Fuse::RunAsNode),
+            Ok(FuseStatus::Present(true))
+        );
+        assert_eq!(
+            app.get_fuse_status(Fuse::EncryptedCookies),
+            Err(PatcherError::Binary(BinaryError::FuseDoesNotExist(
+                Fuse::EncryptedCookies
+            )))
+        );
+    }
+
+    /// Creates bytes that start with the sentinel, but aren't followed by a valid fuse wire.
+    fn decoy(version: u8, wire: &[u8]) -> Vec<u8> {
+        let mut decoy = Fuse::SENTINEL.to_vec();
+        decoy.push(version);
+        decoy.push(wire.len() as u8);
+        decoy.extend_from_slice(wire);
+        decoy
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn obfuscated_sentinel_is_found() {
+        let mut bytes = TEST_BYTES.to_vec();
+        let sentinel = memchr::memmem::find(&bytes, Fuse::SENTINEL).unwrap();
+        for b in &mut bytes[sentinel..(sentinel + Fuse::SENTINEL.len())] {
+            *b ^= 0x5A;
+        }
+
+        assert!(matches!(
+            ElectronApp::from_bytes(&mut bytes),
+            Err(PatcherError::Binary(BinaryError::NoSentinel))
+        ));
+        assert!(matches!(
+            ElectronApp::from_bytes_with_deobfuscation(&mut bytes, &[SentinelTransform::Xor(0x13)]),
+            Err(PatcherError::Binary(BinaryError::NoSentinel))
+        ));
+
+        let transforms = [SentinelTransform::Xor(0x13), SentinelTransform::Xor(0x5A)];
+        let app = ElectronApp::from_bytes_with_deobfuscation(&mut bytes, &transforms).unwrap();
+        assert_eq!(
+            app.get_fuse_status(Fuse::RunAsNode),
+            Ok(FuseStatus::Present(true))
+        );
+
+        // Plain sentinels are still found without trying any transforms.
+        let mut bytes = TEST_BYTES.to_vec();
+        let app = ElectronApp::from_bytes_with_deobfuscation(&mut bytes, &transforms).unwrap();
+        assert_eq!(app.wires(), &[FuseWire::locate(TEST_BYTES, 0).unwrap()]);
+    }
+
+    #[test]
+    fn unsupported_wire_layouts_are_detected() {
+        let sentinel = memchr::memmem::find(TEST_BYTES, Fuse::SENTINEL).unwrap();
+        let wire = FuseWire::locate(TEST_BYTES, 0).unwrap().range();
+
+        let mut swapped = TEST_BYTES.to_vec();
+        swapped.swap(wire.start - 2, wire.start - 1);
+        assert!(matches!(
+            ElectronApp::from_bytes_readonly(&swapped),
+            Err(PatcherError::Binary(BinaryError::UnsupportedWireLayout {
+                layout: WireLayout::SwappedHeader,
+                offset,
+            })) if offset == sentinel
+        ));
+
+        let mut reversed = TEST_BYTES.to_vec();
+        reversed[sentinel..wire.end].reverse();
+        assert!(matches!(
+            ElectronApp::from_bytes_readonly(&reversed),
+            Err(PatcherError::Binary(BinaryError::UnsupportedWireLayout {
+                layout: WireLayout::Reversed,
+                offset,
+            })) if offset == wire.end - Fuse::SENTINEL.len()
+        ));
+
+        // A sentinel that isn't followed by a wire in any layout is still reported as before.
+        let truncated = TEST_BYTES[..(sentinel + Fuse::SENTINEL.len())].to_vec();
+        assert!(matches!(
+            ElectronApp::from_bytes_readonly(&truncated),
+            Err(PatcherError::Binary(BinaryError::NoFuseVersion { sentinel: found }))
+                if found == sentinel
+        ));
+
+        // A wire cut off after its version has no length.
+        let truncated = TEST_BYTES[..(sentinel + Fuse::SENTINEL.len() + 1)].to_vec();
+        assert_eq!(
+            ElectronApp::from_bytes_readonly(&truncated).err(),
+            Some(BinaryError::NoFuseLength { sentinel }.into())
+        );
+    }
+
+    fn two_wire_bytes() -> Vec<u8> {
+        let mut bytes = TEST_BYTES.to_vec();
+        bytes.extend_from_slice(TEST_BYTES);
+        bytes
+    }
+
+    #[test]
+    fn every_wire_is_found() {
+        let bytes = two_wire_bytes();
+        let first = FuseWire::locate(TEST_BYTES, 0).unwrap().range();
+
+        let wires = FuseWire::locate_all(&bytes, 0).unwrap();
+        assert_eq!(wires.len(), 2);
+        assert_eq!(wires[0].range(), first);
+        assert_eq!(
+            wires[1].range(),
+            (first.start + TEST_BYTES.len())..(first.end + TEST_BYTES.len())
+        );
+        assert_eq!(wires[1].candidates(), 2);
+
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        assert_eq!(app.wires(), &wires[..]);
+        assert_eq!(
+            app.get_fuse_status_in(1, FUSE),
+            Ok(FuseStatus::Present(true))
+        );
+        assert_eq!(
+            app.get_fuse_status_in(2, FUSE),
+            Err(PatcherError::Binary(BinaryError::FuseWireDoesNotExist(2)))
+        );
+    }
+
+    #[test]
+    fn ambiguous_sentinels_are_reported() {
+        let bytes = two_wire_bytes();
+
+        assert_eq!(
+            FuseWire::locate(&bytes, 0),
+            Err(PatcherError::Binary(BinaryError::AmbiguousSentinel {
+                count: 2
+            }))
+        );
+        assert_eq!(
+            FuseWire::locate_obfuscated(&bytes, 0, &[SentinelTransform::Xor(0x5A)]),
+            Err(PatcherError::Binary(BinaryError::AmbiguousSentinel {
+                count: 2
+            }))
+        );
+
+        // A sentinel that isn't followed by a wire isn't a candidate for patching.
+        let mut bytes = Fuse::SENTINEL.to_vec();
+        bytes.extend_from_slice(TEST_BYTES);
+        assert!(FuseWire::locate(&bytes, 0).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn fuses_are_set_in_every_wire() {
+        let mut bytes = two_wire_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        assert_eq!(app.set_fuse_status(FUSE, false), Ok(FuseStatus::Modified));
+        assert_eq!(
+            app.get_fuse_statuses(FUSE),
+            Ok(vec![FuseStatus::Present(false); 2])
+        );
+        assert_eq!(app.get_fuse_status(FUSE), Ok(FuseStatus::Present(false)));
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn fuses_are_set_per_wire() {
+        let mut bytes = two_wire_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        assert_eq!(
+            app.set_fuse_status_in(1, FUSE, false),
+            Ok(FuseStatus::Modified)
+        );
+        assert_eq!(
+            app.get_fuse_status_in(0, FUSE),
+            Ok(FuseStatus::Present(true))
+        );
+        assert_eq!(
+            app.get_fuse_status_in(1, FUSE),
+            Ok(FuseStatus::Present(false))
+        );
+
+        // Reading the fuse without picking a wire fails while they disagree.
+        assert_eq!(
+            app.get_fuse_statuses(FUSE),
+            Ok(vec![FuseStatus::Present(true), FuseStatus::Present(false)])
+        );
+        assert_eq!(
+            app.get_fuse_status(FUSE),
+            Err(PatcherError::Binary(BinaryError::FuseWiresDisagree {
+                fuse: FUSE,
+                statuses: vec![FuseStatus::Present(true), FuseStatus::Present(false)],
+            }))
+        );
+
+        // Only the wire that still has the fuse enabled is modified.
+        assert_eq!(app.set_fuse_status(FUSE, false), Ok(FuseStatus::Modified));
+        assert_eq!(
+            app.get_fuse_status_in(0, FUSE),
+            Ok(FuseStatus::Present(false))
+        );
+        assert_eq!(
+            app.set_fuse_status(FUSE, false),
+            Ok(FuseStatus::Present(false))
+        );
+
+        // A removed fuse in any wire stops every wire from being modified.
+        let wire = app.wires()[1].range();
+        drop(app);
+        bytes[wire.start + Fuse::NodeOptions.schema_pos()] = Fuse::REMOVED;
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(
+            app.set_fuse_status(Fuse::NodeOptions, false),
+            Err(PatcherError::RemovedFuse(Fuse::NodeOptions))
+        );
+        assert_eq!(
+            app.get_fuse_status_in(0, Fuse::NodeOptions),
+            Ok(FuseStatus::Present(true))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn fuse_changes_are_planned() {
+        let mut bytes = two_wire_bytes();
+        let original = bytes.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let wires: Vec<usize> = app.wires().iter().map(|w| w.range().start).collect();
+
+        let plan = app.plan_fuse_change(FUSE, false).unwrap();
+        assert_eq!(plan.status, FuseStatus::Modified);
+        assert_eq!(
+            plan.changes,
+            wires
+                .iter()
+                .map(|&wire| JournalEntry {
+                    offset: wire + FUSE.schema_pos(),
+                    original: vec![b'1'],
+                    modified: vec![b'0'],
+                })
+                .collect::<Vec<_>>()
+        );
+        assert!(!app.is_dirty());
+
+        app.start_undo_journal();
+        assert_eq!(app.set_fuse_status(FUSE, false), Ok(plan.status));
+        assert_eq!(
+            app.take_undo_journal().unwrap().entries(),
+            &plan.changes[..]
+        );
+
+        // Nothing is left to change, and errors are the same as when setting the fuse.
+        let plan = app.plan_fuse_change(FUSE, false).unwrap();
+        assert_eq!(plan.status, FuseStatus::Present(false));
+        assert!(plan.changes.is_empty());
+        drop(app);
+
+        let mut bytes = original;
+        bytes[wires[1] + Fuse::NodeOptions.schema_pos()] = Fuse::REMOVED;
+        let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(
+            app.plan_fuse_change(Fuse::NodeOptions, false),
+            Err(PatcherError::RemovedFuse(Fuse::NodeOptions))
+        );
+    }
+
+    #[test]
+    fn decoy_sentinels_are_skipped() {
+        let real_wire = FuseWire::locate(TEST_BYTES, 0).unwrap().range();
+
+        let decoys = [
+            decoy(b'/', b"101100"),
+            decoy(Fuse::EXPECTED_VERSION, b""),
+            decoy(Fuse::EXPECTED_VERSION, b"10\x89100"),
+        ];
+
+        for before in &decoys {
+            let mut application_bytes = before.clone();
+            application_bytes.extend_from_slice(TEST_BYTES);
+            application_bytes.extend_from_slice(&decoy(b'x', b"1"));
+
+            let wire = FuseWire::locate(&application_bytes, 0).unwrap();
+            assert_eq!(wire.candidates(), 2);
+            assert_eq!(wire.version(), Fuse::EXPECTED_VERSION);
+            assert_eq!(
+                wire.range(),
+                (real_wire.start + before.len())..(real_wire.end + before.len())
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_candidates_are_rejected() {
+        let mut application_bytes = decoy(Fuse::EXPECTED_VERSION, b"10x100");
+        assert_eq!(
+            FuseWire::locate(&application_bytes, 0),
+            Err(PatcherError::Binary(BinaryError::MalformedFuseWire {
+                position: 2,
+                value: b'x',
+                offset: Fuse::SENTINEL.len() + 4,
+            }))
+        );
+
+        // A length that runs past the end of the binary.
+        let len_pos = Fuse::SENTINEL.len() + 1;
+        application_bytes[len_pos] = 7;
+        assert_eq!(
+            FuseWire::locate(&application_bytes, 0),
+            Err(PatcherError::Binary(BinaryError::TruncatedFuseWire {
+                len: 7,
+                available: 6
+            }))
+        );
+
+        application_bytes[len_pos] = 0;
+        assert_eq!(
+            FuseWire::locate(&application_bytes, 0),
+            Err(PatcherError::Binary(BinaryError::ImplausibleFuseLength(0)))
+        );
+
+        application_bytes.extend_from_slice(&decoy(2, b"101100"));
+        assert_eq!(
+            FuseWire::locate(&application_bytes, 0),
+            Err(PatcherError::Binary(BinaryError::NoValidFuseWire {
+                candidates: 2
+            }))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
     fn fuse_modifies_correct_position() {
         let mut wire = get_wire().to_vec();
 
@@ -0,0 +1,79 @@
+//! Information about what this version of the library supports.
+//!
+//! Tools embedding the library can show this in their own diagnostics, or refuse to run against applications built
+//! with a newer version of Electron than the library knows about.
+
+use crate::{ElectronVersion, Fuse};
+
+/// The oldest major version of Electron supported, which is the first one with fuses.
+const MIN_ELECTRON_MAJOR: u32 = 12;
+
+/// The newest major version of Electron that the patch targets and fuses have been checked against.
+const MAX_ELECTRON_MAJOR: u32 = 32;
+
+/// The revision of the patch targets and their search patterns.
+///
+/// This has to be incremented whenever a target or pattern is added, removed, or changed.
+const PATTERN_REVISION: u32 = 1;
+
+/// What this version of the library supports, returned by [info].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct LibraryInfo {
+    /// The version of this crate.
+    pub crate_version: &'static str,
+    /// The oldest major version of Electron supported.
+    pub min_electron_major: u32,
+    /// The newest major version of Electron supported.
+    pub max_electron_major: u32,
+    /// The fuse schema version that fuse wires are expected to have.
+    pub fuse_schema_version: u8,
+    /// The revision of the patch targets and their search patterns, which only ever increases.
+    pub pattern_revision: u32,
+}
+
+impl LibraryInfo {
+    /// Returns `true` if the version of Electron is in the supported range.
+    pub fn supports(&self, version: ElectronVersion) -> bool {
+        (self.min_electron_major..=self.max_electron_major).contains(&version.major)
+    }
+}
+
+/// Returns what this version of the library supports.
+pub fn info() -> LibraryInfo {
+    LibraryInfo {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        min_electron_major: MIN_ELECTRON_MAJOR,
+        max_electron_major: MAX_ELECTRON_MAJOR,
+        fuse_schema_version: Fuse::EXPECTED_VERSION,
+        pattern_revision: PATTERN_REVISION,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patcher::PatchTarget;
+
+    #[test]
+    fn electron_range_matches_version_gates() {
+        let info = info();
+
+        let gates: Vec<u32> = PatchTarget::ALL
+            .iter()
+            .flat_map(|target| vec![target.available_since(), target.removed_in()])
+            .chain(Fuse::ALL.iter().map(|fuse| fuse.effective_since()))
+            .flatten()
+            .collect();
+
+        for &gate in &gates {
+            assert!(info.supports(ElectronVersion::new(gate, 0, 0)), "{}", gate);
+        }
+        assert_eq!(gates.iter().max(), Some(&info.max_electron_major));
+
+        assert!(info.supports(ElectronVersion::new(12, 0, 0)));
+        assert!(!info.supports(ElectronVersion::new(11, 5, 0)));
+        assert!(!info.supports(ElectronVersion::new(info.max_electron_major + 1, 0, 0)));
+    }
+}
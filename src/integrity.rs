@@ -0,0 +1,142 @@
+//! Detection of the ASAR integrity information embedded in an application binary.
+
+use crate::{fuses::FuseStatus, ElectronApp, Fuse};
+use memchr::memmem;
+use std::ops::Range;
+
+/// The state of an application's [ASAR integrity](https://www.electronjs.org/docs/latest/tutorial/asar-integrity)
+/// validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AsarIntegrity {
+    /// The status of the [EmbeddedAsarIntegrityValidation](Fuse::EmbeddedAsarIntegrityValidation) fuse, or `None` if
+    /// the application's fuse wire doesn't contain it.
+    pub fuse: Option<FuseStatus>,
+    /// The integrity information of the `app.asar` archive, if it's embedded in the binary.
+    pub block: Option<IntegrityBlock>,
+}
+
+/// The integrity information Electron embeds in an application binary to validate its `app.asar` archive.
+///
+/// On Windows, this is stored as a resource of the executable. macOS applications store it in their `Info.plist`
+/// instead, so it won't be found in their binaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IntegrityBlock {
+    /// The position of the whole block in the binary.
+    pub range: Range<usize>,
+    /// The position of the archive's hex-encoded SHA-256 header hash, which has to be updated when the archive is
+    /// repacked.
+    pub hash: Range<usize>,
+}
+
+impl IntegrityBlock {
+    /// How far the end of a block is searched for after its start.
+    const MAX_LEN: usize = 1024;
+
+    /// The length of a hex-encoded SHA-256 hash.
+    const HASH_LEN: usize = 64;
+
+    /// Searches for the JSON array that Electron's packaging tools embed, such as
+    /// `[{"file":"resources\\app.asar","alg":"SHA256","value":"..."}]`.
+    pub(crate) fn locate(binary: &[u8]) -> Option<Self> {
+        const START: &[u8] = b"[{\"file\":\"";
+
+        memmem::find_iter(binary, START).find_map(|pos| {
+            let end = (pos + Self::MAX_LEN).min(binary.len());
+            Self::parse(&binary[pos..end]).map(|(len, hash)| Self {
+                range: pos..(pos + len),
+                hash: (pos + hash.start)..(pos + hash.end),
+            })
+        })
+    }
+
+    /// Parses a block from the start of the bytes, returning its length and the position of its hash.
+    fn parse(bytes: &[u8]) -> Option<(usize, Range<usize>)> {
+        const ALG: &[u8] = b"\"alg\":\"";
+        const VALUE: &[u8] = b"\"value\":\"";
+
+        let len = memmem::find(bytes, b"}]")? + 2;
+        let block = &bytes[..len];
+
+        let alg = memmem::find(block, ALG)? + ALG.len();
+        if !block.get(alg..(alg + 7))?.eq_ignore_ascii_case(b"sha256\"") {
+            return None;
+        }
+
+        let hash = memmem::find(block, VALUE)? + VALUE.len();
+        let hash = hash..(hash + Self::HASH_LEN);
+        if !block.get(hash.clone())?.iter().all(u8::is_ascii_hexdigit)
+            || block.get(hash.end) != Some(&b'"')
+        {
+            return None;
+        }
+
+        Some((len, hash))
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Reports whether the application validates the integrity of its `app.asar` archive, and where the archive's
+    /// hash is stored in the binary.
+    ///
+    /// The hash location is a best-effort search, meant for tools that repack an application's resources and need to
+    /// update it. This crate doesn't compute the hash itself.
+    pub fn asar_integrity(&self) -> AsarIntegrity {
+        AsarIntegrity {
+            fuse: self
+                .get_fuse_status(Fuse::EmbeddedAsarIntegrityValidation)
+                .ok(),
+            block: IntegrityBlock::locate(&self.contents),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9d5aa7f8a1c7e4f0f1b07a3c1e7b2d6e4c0f5a9b8e3d2c1b0a9f8e7d6c5b4a39";
+
+    fn integrity_json(alg: &str) -> Vec<u8> {
+        format!(
+            "\0[{{\"file\":\"resources\\\\app.asar\",\"alg\":\"{}\",\"value\":\"{}\"}}]\0",
+            alg, HASH
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn integrity_block_is_located() {
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        let start = bytes.len() + 1;
+        bytes.extend_from_slice(&integrity_json("SHA256"));
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        let integrity = app.asar_integrity();
+        assert_eq!(integrity.fuse, Some(FuseStatus::Present(false)));
+
+        let block = integrity.block.unwrap();
+        assert_eq!(block.range, start..(bytes.len() - 1));
+        assert_eq!(&bytes[block.hash], HASH.as_bytes());
+    }
+
+    #[test]
+    fn missing_integrity_block_is_none() {
+        let bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+        assert_eq!(app.asar_integrity().block, None);
+
+        let mut unsupported = integrity_json("MD5");
+        unsupported.extend_from_slice(&integrity_json("sha256")[..40]);
+        assert_eq!(IntegrityBlock::locate(&unsupported), None);
+
+        assert_eq!(
+            IntegrityBlock::locate(&integrity_json("sha256"))
+                .unwrap()
+                .range
+                .start,
+            1
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Journals of the modifications made to an application, which can be used to undo them.
+
+use crate::{patcher::PatchTarget, ElectronApp, Fuse, PatcherError};
+use std::{fmt, ops::Range, str::FromStr};
+
+/// What a [modification](Modification) of an application changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ModifiedItem {
+    /// A fuse known by this library.
+    Fuse(Fuse),
+    /// The [raw fuse](ElectronApp::set_raw_fuse) at a position in the fuse wire.
+    RawFuse(usize),
+    /// A patch target that was patched out.
+    Target(PatchTarget),
+    /// Bytes that were restored by [reverting](ElectronApp::revert) an undo journal.
+    Reverted,
+}
+
+/// A modification made to an application since it was loaded, or since it was last
+/// [marked as clean](ElectronApp::mark_clean).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct Modification {
+    /// What was modified.
+    pub item: ModifiedItem,
+    /// The position of the modified bytes in the binary.
+    pub range: Range<usize>,
+}
+
+/// A single modification made to an application binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct JournalEntry {
+    /// The position of the modified bytes in the binary.
+    pub offset: usize,
+    /// The bytes before they were modified.
+    pub original: Vec<u8>,
+    /// The bytes after they were modified.
+    pub modified: Vec<u8>,
+}
+
+/// A record of every modification made to an application binary, which can be used to restore its original contents.
+///
+/// Journals only contain the bytes that were modified, so they are much smaller than a full backup of the binary.
+/// They can be saved with their [Display](fmt::Display) implementation and read back with [FromStr].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UndoJournal {
+    entries: Vec<JournalEntry>,
+}
+
+impl UndoJournal {
+    /// The first line of a saved journal, which identifies its format.
+    const HEADER: &'static str = "electron-hardener undo journal v1";
+
+    /// Creates a new, empty journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every modification in the journal, in the order they were made.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Returns `true` if no modifications have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds the modifications of a journal that was recorded after this one, so that reverting the combined journal
+    /// undoes both of them.
+    pub fn append(&mut self, later: UndoJournal) {
+        self.entries.extend(later.entries);
+    }
+
+    /// Forgets every modification recorded after the first `len`.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    pub(crate) fn record(&mut self, offset: usize, original: &[u8], modified: &[u8]) {
+        self.entries.push(JournalEntry {
+            offset,
+            original: original.to_vec(),
+            modified: modified.to_vec(),
+        });
+    }
+
+    /// Restores the original bytes of every modification in the journal.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [UndoMismatch](PatcherError::UndoMismatch) if the binary doesn't contain the modified
+    /// bytes recorded in the journal, which means it isn't the binary the journal was recorded for. The binary is
+    /// left unmodified in that case.
+    pub fn revert(&self, binary: &mut [u8]) -> Result<(), PatcherError> {
+        // Modifications are undone in reverse, so bytes that were modified more than once end up with their
+        // original contents.
+        for (reverted, entry) in self.entries.iter().rev().enumerate() {
+            let range = entry.offset..(entry.offset + entry.modified.len());
+
+            match binary.get_mut(range) {
+                Some(current) if *current == *entry.modified => {
+                    current.copy_from_slice(&entry.original)
+                }
+                _ => {
+                    // Put back everything that was already reverted.
+                    for entry in self.entries.iter().rev().take(reverted).rev() {
+                        let range = entry.offset..(entry.offset + entry.modified.len());
+                        binary[range].copy_from_slice(&entry.modified);
+                    }
+
+                    return Err(PatcherError::UndoMismatch {
+                        offset: entry.offset,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => Some((hex_digit(*high)? << 4) | hex_digit(*low)?),
+            _ => None,
+        })
+        .collect()
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|d| d as u8)
+}
+
+impl fmt::Display for UndoJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", Self::HEADER)?;
+
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{} {} {}",
+                entry.offset,
+                encode_hex(&entry.original),
+                encode_hex(&entry.modified)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for UndoJournal {
+    type Err = PatcherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        if lines.next() != Some(Self::HEADER) {
+            return Err(PatcherError::InvalidUndoJournal { line: 1 });
+        }
+
+        let mut journal = Self::new();
+
+        for (i, line) in lines.enumerate() {
+            let invalid = || PatcherError::InvalidUndoJournal { line: i + 2 };
+
+            let mut parts = line.split(' ');
+            let offset = parts
+                .next()
+                .and_then(|offset| offset.parse().ok())
+                .ok_or_else(invalid)?;
+            let original = parts.next().and_then(decode_hex).ok_or_else(invalid)?;
+            let modified = parts.next().and_then(decode_hex).ok_or_else(invalid)?;
+
+            if parts.next().is_some() || original.len() != modified.len() {
+                return Err(invalid());
+            }
+
+            journal.record(offset, &original, &modified);
+        }
+
+        Ok(journal)
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Starts recording every modification made to the application into an [undo journal](UndoJournal).
+    ///
+    /// Any journal that was already being recorded is discarded.
+    pub fn start_undo_journal(&mut self) {
+        self.journal = Some(UndoJournal::new());
+    }
+
+    /// Stops recording modifications and returns the journal, if one was [started](ElectronApp::start_undo_journal).
+    pub fn take_undo_journal(&mut self) -> Option<UndoJournal> {
+        self.journal.take()
+    }
+
+    /// Returns `true` if the application has been modified since it was loaded, or since it was last
+    /// [marked as clean](ElectronApp::mark_clean).
+    ///
+    /// This is the cheapest way to decide whether a binary has to be written back, signed again, or notarized again.
+    pub fn is_dirty(&self) -> bool {
+        !self.modifications.is_empty()
+    }
+
+    /// Returns every modification made to the application since it was loaded, or since it was last
+    /// [marked as clean](ElectronApp::mark_clean), in the order they were made.
+    ///
+    /// Unlike an [undo journal](UndoJournal), this is always recorded, and only describes what was modified.
+    pub fn modifications(&self) -> &[Modification] {
+        &self.modifications
+    }
+
+    /// Forgets every recorded [modification](ElectronApp::modifications), such as after the application has been
+    /// written back to disk.
+    pub fn mark_clean(&mut self) {
+        self.modifications.clear();
+    }
+
+    /// Restores the original bytes of every modification in an undo journal that was recorded for this application.
+    ///
+    /// This is how a saved journal is used to undo hardening. The restored bytes are
+    /// [recorded](ElectronApp::modifications) as [Reverted](ModifiedItem::Reverted) modifications, and in the undo
+    /// journal if one is being kept, so reverting can itself be undone.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [UndoMismatch](PatcherError::UndoMismatch) if the application doesn't contain the
+    /// modified bytes recorded in the journal, which means it was recorded for a different binary or the binary was
+    /// modified again since. Nothing is written in that case.
+    pub fn revert(&mut self, journal: &UndoJournal) -> Result<(), PatcherError> {
+        if journal.is_empty() {
+            return Ok(());
+        }
+
+        self.check_signature()?;
+        for entry in &journal.entries {
+            self.check_write(ModifiedItem::Reverted, entry.offset, &entry.original)?;
+        }
+        journal.revert(&mut self.contents)?;
+
+        for entry in journal.entries.iter().rev() {
+            if let Some(own) = &mut self.journal {
+                own.record(entry.offset, &entry.modified, &entry.original);
+            }
+
+            self.modifications.push(Modification {
+                item: ModifiedItem::Reverted,
+                range: entry.offset..(entry.offset + entry.original.len()),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes bytes into the application, recording the modification, and in the undo journal if one is being kept.
+    ///
+    /// This is the only way the application's bytes are modified, so every write is [checked](Self::check_write)
+    /// against the changes that can be made to its fuse wires first. Nothing is written if the check fails.
+    pub(crate) fn write_bytes(
+        &mut self,
+        item: ModifiedItem,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<(), PatcherError> {
+        self.check_write(item, offset, bytes)?;
+
+        let range = offset..(offset + bytes.len());
+        let target = &mut self.contents[range.clone()];
+
+        if let Some(journal) = &mut self.journal {
+            journal.record(offset, target, bytes);
+        }
+
+        target.copy_from_slice(bytes);
+        self.modifications.push(Modification { item, range });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        patcher::{
+            tests::{app_bytes, test_app_bytes},
+            ElectronOption,
+        },
+        HardeningPolicy, Preset,
+    };
+
+    fn hardened_with_journal() -> (Vec<u8>, UndoJournal) {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        app.start_undo_journal();
+        app.apply_policy(&HardeningPolicy::from_preset(Preset::Paranoid))
+            .unwrap();
+        app.patch_option(crate::patcher::PatchTarget::NodeInspect)
+            .unwrap();
+        let journal = app.take_undo_journal().unwrap();
+
+        (bytes, journal)
+    }
+
+    #[test]
+    fn modifications_are_tracked() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let wire = app.wires()[0].range().start;
+        assert!(!app.is_dirty());
+
+        app.set_fuse_status(Fuse::RunAsNode, false).unwrap();
+        // Already disabled, so nothing is written.
+        app.set_fuse_status(Fuse::RunAsNode, false).unwrap();
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+        app.set_raw_fuse(1, true).unwrap();
+
+        let js_flags = app.modifications()[1].range.clone();
+        assert_eq!(
+            app.modifications(),
+            &[
+                Modification {
+                    item: ModifiedItem::Fuse(Fuse::RunAsNode),
+                    range: wire..(wire + 1),
+                },
+                Modification {
+                    item: ModifiedItem::Target(ElectronOption::JsFlags.into()),
+                    range: js_flags,
+                },
+                Modification {
+                    item: ModifiedItem::RawFuse(1),
+                    range: (wire + 1)..(wire + 2),
+                },
+            ]
+        );
+        assert!(app.is_dirty());
+
+        app.mark_clean();
+        assert!(!app.is_dirty());
+        assert!(app.modifications().is_empty());
+    }
+
+    #[test]
+    fn journal_reverts_hardening() {
+        let (mut bytes, journal) = hardened_with_journal();
+        assert_ne!(bytes, test_app_bytes());
+
+        journal.revert(&mut bytes).unwrap();
+        assert_eq!(bytes, test_app_bytes());
+    }
+
+    #[test]
+    fn applications_can_be_reverted() {
+        let fixtures: [&[u8]; 2] = [
+            include_bytes!("../examples/fake_electron_flags.bin"),
+            include_bytes!("../examples/fake_electron_flags_windows.bin"),
+        ];
+
+        for flags in fixtures {
+            let original = app_bytes(flags);
+
+            let mut bytes = original.clone();
+            let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+            app.start_undo_journal();
+            app.apply_policy(&HardeningPolicy::from_preset(Preset::Paranoid))
+                .unwrap();
+            let saved = app.take_undo_journal().unwrap().to_string();
+            app.mark_clean();
+
+            // The journal is read back the same way it would be from a file.
+            let journal: UndoJournal = saved.parse().unwrap();
+            app.start_undo_journal();
+            app.revert(&journal).unwrap();
+            assert!(app
+                .modifications()
+                .iter()
+                .all(|m| m.item == ModifiedItem::Reverted));
+            assert!(
+                app.as_bytes() == &original[..],
+                "the binary wasn't restored"
+            );
+
+            // Reverting the revert hardens the binary again.
+            let undo = app.take_undo_journal().unwrap();
+            app.revert(&undo).unwrap();
+            assert!(app.as_bytes() != &original[..]);
+        }
+    }
+
+    #[test]
+    fn mismatched_application_is_not_reverted() {
+        let (bytes, journal) = hardened_with_journal();
+
+        // The last entry is a patched out target, so the fuse wire can still be read.
+        let mut mismatched = bytes;
+        let last = journal.entries().last().unwrap();
+        mismatched[last.offset] = b'?';
+        let expected = mismatched.clone();
+
+        let mut app = ElectronApp::from_bytes(&mut mismatched).unwrap();
+        assert_eq!(
+            app.revert(&journal),
+            Err(PatcherError::UndoMismatch {
+                offset: last.offset
+            })
+        );
+        assert!(!app.is_dirty());
+        assert!(mismatched == expected, "the binary was modified");
+    }
+
+    #[test]
+    fn journal_is_only_recorded_when_started() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+        assert_eq!(app.take_undo_journal(), None);
+
+        app.start_undo_journal();
+        app.set_fuse_status(crate::Fuse::RunAsNode, false).unwrap();
+        // Already disabled, so nothing is written.
+        app.set_fuse_status(crate::Fuse::RunAsNode, false).unwrap();
+        assert_eq!(app.take_undo_journal().unwrap().entries().len(), 1);
+    }
+
+    #[test]
+    fn journal_round_trips_through_text() {
+        let (_, journal) = hardened_with_journal();
+
+        let saved = journal.to_string();
+        assert!(saved.starts_with(UndoJournal::HEADER));
+        assert_eq!(saved.parse::<UndoJournal>(), Ok(journal));
+    }
+
+    #[test]
+    fn invalid_journal_is_rejected() {
+        assert_eq!(
+            "not a journal".parse::<UndoJournal>(),
+            Err(PatcherError::InvalidUndoJournal { line: 1 })
+        );
+
+        let journal = format!("{}\n10 00 01\n20 0g 01\n", UndoJournal::HEADER);
+        assert_eq!(
+            journal.parse::<UndoJournal>(),
+            Err(PatcherError::InvalidUndoJournal { line: 3 })
+        );
+
+        let journal = format!("{}\n10 0000 01\n", UndoJournal::HEADER);
+        assert_eq!(
+            journal.parse::<UndoJournal>(),
+            Err(PatcherError::InvalidUndoJournal { line: 2 })
+        );
+    }
+
+    #[test]
+    fn mismatched_binary_is_left_alone() {
+        let (bytes, journal) = hardened_with_journal();
+
+        // Modify the bytes of the last entry, which is reverted first.
+        let mut mismatched = bytes.clone();
+        let last = journal.entries().last().unwrap();
+        mismatched[last.offset] = b'?';
+        let expected = mismatched.clone();
+
+        assert_eq!(
+            journal.revert(&mut mismatched),
+            Err(PatcherError::UndoMismatch {
+                offset: last.offset
+            })
+        );
+        assert_eq!(mismatched, expected);
+
+        // Modify the bytes of the first entry, which is reverted last.
+        let mut mismatched = bytes;
+        let first = &journal.entries()[0];
+        mismatched[first.offset] = b'?';
+        let expected = mismatched.clone();
+
+        assert_eq!(
+            journal.revert(&mut mismatched),
+            Err(PatcherError::UndoMismatch {
+                offset: first.offset
+            })
+        );
+        assert_eq!(mismatched, expected);
+    }
+}
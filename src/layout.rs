@@ -0,0 +1,154 @@
+//! Checks of the files packaged next to an application binary.
+
+use crate::ElectronApp;
+use std::path::Path;
+
+/// How an application's code is packaged in its resources directory.
+///
+/// Electron loads the code from an `app` directory before an `app.asar` archive, unless the
+/// [OnlyLoadAppFromAsar](crate::Fuse::OnlyLoadAppFromAsar) fuse is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum AppLayout {
+    /// The code is only packaged in an `app.asar` archive.
+    Asar,
+    /// The code is only packaged in an unpacked `app` directory.
+    Directory,
+    /// Both an `app.asar` archive and an `app` directory are present. The directory is currently the one being
+    /// loaded.
+    Both,
+    /// Neither an `app.asar` archive nor an `app` directory could be found.
+    Unknown,
+}
+
+impl AppLayout {
+    /// Inspects an application's resources directory, such as `resources` on Windows and Linux or
+    /// `Contents/Resources` on macOS.
+    pub fn detect<P: AsRef<Path>>(resources_dir: P) -> Self {
+        let resources_dir = resources_dir.as_ref();
+
+        match (
+            resources_dir.join("app.asar").is_file(),
+            resources_dir.join("app").is_dir(),
+        ) {
+            (true, false) => AppLayout::Asar,
+            (false, true) => AppLayout::Directory,
+            (true, true) => AppLayout::Both,
+            (false, false) => AppLayout::Unknown,
+        }
+    }
+
+    /// Finds the resources directory packaged with an application binary and inspects it.
+    ///
+    /// The directory is `resources` next to the binary on Windows and Linux, and `Contents/Resources` in a macOS
+    /// bundle. Returns `None` if neither exists, such as when only the binary is available.
+    pub fn for_binary<P: AsRef<Path>>(binary: P) -> Option<Self> {
+        let dir = binary.as_ref().parent()?;
+
+        [dir.join("resources"), dir.join("../Resources")]
+            .iter()
+            .find(|candidate| candidate.is_dir())
+            .map(AppLayout::detect)
+    }
+
+    /// Returns `true` if enabling the [OnlyLoadAppFromAsar](crate::Fuse::OnlyLoadAppFromAsar) fuse would change
+    /// which code is loaded, or stop the application from starting.
+    pub fn conflicts_with_asar_only(&self) -> bool {
+        matches!(self, AppLayout::Directory | AppLayout::Both)
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Provides how the application's code is packaged, so that enabling the
+    /// [OnlyLoadAppFromAsar](crate::Fuse::OnlyLoadAppFromAsar) fuse can be checked against it.
+    ///
+    /// When a policy enables that fuse on an application whose layout [conflicts](AppLayout::conflicts_with_asar_only)
+    /// with it, an [UnpackedApp](crate::HardeningWarning::UnpackedApp) warning is reported. The check is skipped if
+    /// no layout was provided, such as when only the binary is available.
+    pub fn set_app_layout(&mut self, layout: AppLayout) {
+        self.app_layout = Some(layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HardeningPolicy, HardeningWarning, Preset};
+    use std::{fs, path::PathBuf};
+
+    /// A resources directory in the temporary directory that's removed when dropped.
+    struct TempResources(PathBuf);
+
+    impl TempResources {
+        fn new(name: &str, asar: bool, dir: bool) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "electron-hardener-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+
+            if asar {
+                fs::write(path.join("app.asar"), b"").unwrap();
+            }
+            if dir {
+                fs::create_dir(path.join("app")).unwrap();
+            }
+
+            Self(path)
+        }
+    }
+
+    impl Drop for TempResources {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn layout_warnings(layout: Option<AppLayout>) -> Vec<HardeningWarning> {
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        if let Some(layout) = layout {
+            app.set_app_layout(layout);
+        }
+
+        app.apply_policy(&HardeningPolicy::from_preset(Preset::Recommended))
+            .unwrap()
+            .warnings
+            .into_iter()
+            .filter(|w| matches!(w, HardeningWarning::UnpackedApp(_)))
+            .collect()
+    }
+
+    #[test]
+    fn layouts_are_detected() {
+        let asar = TempResources::new("layout-asar", true, false);
+        let dir = TempResources::new("layout-dir", false, true);
+        let both = TempResources::new("layout-both", true, true);
+        let neither = TempResources::new("layout-neither", false, false);
+
+        assert_eq!(AppLayout::detect(&asar.0), AppLayout::Asar);
+        assert_eq!(AppLayout::detect(&dir.0), AppLayout::Directory);
+        assert_eq!(AppLayout::detect(&both.0), AppLayout::Both);
+        assert_eq!(AppLayout::detect(&neither.0), AppLayout::Unknown);
+    }
+
+    #[test]
+    fn conflicting_layout_is_a_warning() {
+        assert_eq!(layout_warnings(Some(AppLayout::Asar)), vec![]);
+        assert_eq!(
+            layout_warnings(Some(AppLayout::Directory)),
+            vec![HardeningWarning::UnpackedApp(AppLayout::Directory)]
+        );
+        assert_eq!(
+            layout_warnings(Some(AppLayout::Both)),
+            vec![HardeningWarning::UnpackedApp(AppLayout::Both)]
+        );
+
+        // Without a layout, only the binary is checked.
+        assert_eq!(layout_warnings(None), vec![]);
+    }
+}
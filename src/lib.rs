@@ -7,7 +7,7 @@
 //! This library provides two sets of functionality:
 //! - An interface to view and modify the status of fuses in an application, similar to the [official fuses package].
 //! - A fast and configurable alternative implementation of the [electron-evil-feature-patcher] tool created by [Dimitri Witkowski].
-//!     All patches it can perform are also exposed in this crate. See its README for more details on how it works.
+//!   All patches it can perform are also exposed in this crate. See its README for more details on how it works.
 //!
 //! Functionality is tested on a minimum version of Electron 15. Older versions may partially work but this is not guaranteed.
 //!
@@ -18,23 +18,314 @@
 //!
 //! For a stronger assurance, consider disabling the [dev tools messages](patcher::DevToolsMessage).
 //!
+//! ### Determinism
+//!
+//! Hardening is deterministic. Applying the same policy to the same binary always produces identical bytes,
+//! regardless of the platform, the run, or the order of the modifications in the policy. Replacements only depend on
+//! the bytes they replace, and no randomness or unordered collections are involved in applying them.
+//!
+//! ### Cargo Features
+//!
+//! - `inspect`: Reading fuses, the Electron version, and whether patch targets are present in an application.
+//! - `patch`: Everything that modifies an application, including policies, reports, and undo journals. Implies `inspect`.
+//! - `audit`: An [append-only log](audit::AuditLog) of modifications made across many runs. Implies `patch`.
+//! - `object-parser`: Refusing to patch targets found inside of a binary's executable sections, and
+//!   [restricting searches](ElectronApp::restrict_search_to_sections) to its data sections. Implies `patch`.
+//! - `mmap`: [Patching applications in place](ElectronApp::open_mmap) through a memory map. Implies `patch`.
+//! - `codesign`: [Signing patched macOS applications again](ElectronApp::resign_with_codesign) with `codesign`.
+//!   Implies `patch`.
+//! - `cli`: The command line tool. Implies `audit`.
+//! - `integration-tests`: Tests that harden a real Electron release, which is downloaded the first time they run.
+//!   Only useful for developing this crate.
+//!
+//! All features except `mmap`, `codesign` and `integration-tests` are enabled by default. Tools that only need to inspect applications can depend on this crate with
+//! `default-features = false, features = ["inspect"]`, which leaves out all of the patching code. Without `patch`,
+//! applications are opened with [from_bytes_readonly](ElectronApp::from_bytes_readonly), [from_vec](ElectronApp::from_vec)
+//! or [from_path](ElectronApp::from_path), since the constructors that borrow a mutable buffer are only available with it.
+//!
 //! [fuses]: https://www.electronjs.org/docs/tutorial/fuses
 //! [official fuses package]: https://github.com/electron/fuses
 //! [electron-evil-feature-patcher]: https://github.com/antelle/electron-evil-feature-patcher
 //! [Dimitri Witkowski]: https://github.com/antelle
 #![warn(missing_docs)]
 
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "audit")]
+pub use audit::AuditLog;
+
+#[cfg(feature = "inspect")]
+pub mod bench;
+
+#[cfg(feature = "patch")]
+pub mod batch;
+#[cfg(feature = "patch")]
+pub use batch::{ItemOutcome, PatchItem, SimulatedItem};
+
+#[cfg(feature = "codesign")]
+mod codesign;
+#[cfg(feature = "patch")]
+pub mod compat;
+#[cfg(feature = "patch")]
+pub use compat::compat_tests;
+
+#[cfg(feature = "inspect")]
+pub mod cancel;
+#[cfg(feature = "inspect")]
+pub use cancel::CancelToken;
+
+#[cfg(feature = "inspect")]
+pub mod coverage;
+#[cfg(feature = "inspect")]
+pub use coverage::CoverageReport;
+
+#[cfg(feature = "inspect")]
 mod error;
-pub use error::{BinaryError, PatcherError};
+#[cfg(feature = "audit")]
+pub use error::AuditError;
+#[cfg(feature = "inspect")]
+pub use error::{BinaryError, PatcherError, UnknownNameError};
+#[cfg(feature = "patch")]
+pub use error::{PolicyError, ReadOnlyReason, SourceError};
+
+#[cfg(feature = "inspect")]
+mod fat;
 
+#[cfg(feature = "inspect")]
 pub mod fuses;
-pub use fuses::Fuse;
+#[cfg(feature = "inspect")]
+pub use fuses::{Fuse, FuseConfig, FuseSetting, ParseOptions};
+
+#[cfg(feature = "inspect")]
+pub mod info;
+#[cfg(feature = "inspect")]
+pub use info::{info, LibraryInfo};
 
+#[cfg(feature = "inspect")]
+pub mod integrity;
+#[cfg(feature = "inspect")]
+pub use integrity::AsarIntegrity;
+
+#[cfg(feature = "patch")]
+pub mod journal;
+#[cfg(feature = "patch")]
+pub use journal::{Modification, ModifiedItem, UndoJournal};
+
+#[cfg(feature = "inspect")]
 pub mod patcher;
 
+#[cfg(feature = "patch")]
+pub mod layout;
+#[cfg(feature = "patch")]
+pub use layout::AppLayout;
+
+#[cfg(feature = "patch")]
+pub mod policy;
+#[cfg(feature = "patch")]
+pub use policy::{
+    harden, harden_file, harden_files, harden_to_vec, replace_binary, CompiledPolicy,
+    HardeningPolicy, PolicyWarning, Preset, Severity,
+};
+
+#[cfg(feature = "patch")]
+pub mod pool;
+#[cfg(feature = "patch")]
+pub use pool::BufferPool;
+
+#[cfg(feature = "patch")]
+pub mod posture;
+#[cfg(feature = "patch")]
+pub use posture::{Exposure, PostureReport};
+
+#[cfg(feature = "patch")]
+pub mod report;
+
+#[cfg(feature = "mmap")]
+mod mmap;
+
+#[cfg(feature = "object-parser")]
+mod sections;
+
+#[cfg(feature = "inspect")]
+mod signature;
+#[cfg(feature = "patch")]
+pub use report::{HardeningReport, HardeningWarning, MixedTarget, PatchContext, Regression};
+
+#[cfg(feature = "patch")]
+pub mod source;
+#[cfg(feature = "patch")]
+pub use source::{harden_source, BinarySource};
+
+#[cfg(feature = "inspect")]
+pub mod version;
+#[cfg(feature = "inspect")]
+pub use version::ElectronVersion;
+
 /// An Electron application binary.
+///
+/// Applications usually borrow bytes that the caller already has in memory. Ones
+/// [read from a file](ElectronApp::from_path) or [created from a `Vec`](ElectronApp::from_vec) own their bytes
+/// instead, and are an [ElectronAppBuf].
+#[cfg(feature = "inspect")]
 pub struct ElectronApp<'a> {
-    contents: &'a mut [u8],
-    wire_start: usize,
-    wire_end: usize,
+    contents: Contents<'a>,
+    path: Option<std::path::PathBuf>,
+    wires: Vec<fuses::FuseWire>,
+    /// The architecture slices of a universal binary, or nothing if it only has one architecture.
+    slices: Vec<std::ops::Range<usize>>,
+    search_hint: Option<std::ops::Range<usize>>,
+    #[cfg(feature = "patch")]
+    cancel: Option<CancelToken>,
+    #[cfg(feature = "patch")]
+    app_layout: Option<AppLayout>,
+    #[cfg(feature = "patch")]
+    journal: Option<UndoJournal>,
+    #[cfg(feature = "patch")]
+    modifications: Vec<journal::Modification>,
+    #[cfg(feature = "patch")]
+    patch_fill: u8,
+    #[cfg(feature = "patch")]
+    context_bytes: usize,
+    #[cfg(feature = "patch")]
+    deny_signature_invalidation: bool,
+    #[cfg(feature = "object-parser")]
+    allow_code_patches: bool,
+    /// The read-only data sections of the binary, where patch targets are searched for.
+    #[cfg(feature = "object-parser")]
+    search_sections: Option<Vec<std::ops::Range<usize>>>,
+    /// The name of each of the search sections.
+    #[cfg(feature = "object-parser")]
+    section_names: Vec<String>,
+    #[cfg(feature = "object-parser")]
+    restrict_search: bool,
+}
+
+/// An [Electron application](ElectronApp) that owns its bytes.
+///
+/// It has every method a borrowed application does, and can be returned from functions or stored without keeping
+/// the original buffer alive. Use [into_bytes](ElectronApp::into_bytes) to get the modified bytes back.
+#[cfg(feature = "inspect")]
+pub type ElectronAppBuf = ElectronApp<'static>;
+
+/// A read-only [Electron application](ElectronApp), created with
+/// [from_bytes_readonly](ElectronApp::from_bytes_readonly).
+///
+/// It dereferences to an application that can't be borrowed mutably, so every method that reads the application is
+/// available but none of the ones that modify it are. This includes reading fuses with
+/// [get_fuse_status](ElectronApp::get_fuse_status), the fuse wire's metadata, and checking whether patch targets are
+/// [still present](ElectronApp::is_present). Views are parsed the same way as any other application.
+///
+/// This is meant for auditing binaries that are only available through a shared reference, such as a read-only
+/// memory map, without copying them.
+#[cfg(feature = "inspect")]
+pub struct ElectronAppView<'a> {
+    app: ElectronApp<'a>,
+}
+
+#[cfg(feature = "inspect")]
+impl<'a> ElectronAppView<'a> {
+    pub(crate) fn new(app: ElectronApp<'a>) -> Self {
+        Self { app }
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl<'a> std::ops::Deref for ElectronAppView<'a> {
+    type Target = ElectronApp<'a>;
+
+    fn deref(&self) -> &ElectronApp<'a> {
+        &self.app
+    }
+}
+
+/// The bytes of an [application](ElectronApp), either borrowed from the caller or owned by the application.
+#[cfg(feature = "inspect")]
+enum Contents<'a> {
+    Borrowed(&'a mut [u8]),
+    Owned(Vec<u8>),
+    /// Only used by [views](ElectronAppView), which never give out a mutable reference to their application.
+    Shared(&'a [u8]),
+    #[cfg(feature = "mmap")]
+    Mapped(mmap::MappedFile),
+}
+
+#[cfg(feature = "inspect")]
+impl std::ops::Deref for Contents<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Contents::Borrowed(bytes) => bytes,
+            Contents::Owned(bytes) => bytes,
+            Contents::Shared(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Contents::Mapped(file) => file,
+        }
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl std::ops::DerefMut for Contents<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Contents::Borrowed(bytes) => bytes,
+            Contents::Owned(bytes) => bytes,
+            Contents::Shared(_) => unreachable!("read-only applications are never modified"),
+            #[cfg(feature = "mmap")]
+            Contents::Mapped(file) => file,
+        }
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl<'a> From<&'a mut [u8]> for Contents<'a> {
+    fn from(bytes: &'a mut [u8]) -> Self {
+        Contents::Borrowed(bytes)
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl<'a> From<&'a [u8]> for Contents<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        Contents::Shared(bytes)
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl From<Vec<u8>> for Contents<'_> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Contents::Owned(bytes)
+    }
+}
+
+#[cfg(all(test, feature = "inspect", not(feature = "patch")))]
+mod inspect_tests {
+    use super::*;
+    use crate::{
+        fuses::FuseStatus,
+        patcher::{tests::test_app_bytes, PatchTarget},
+    };
+
+    #[test]
+    fn fuses_can_be_read() {
+        let bytes = test_app_bytes();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        assert_eq!(app.schema_version(), 1);
+        assert_eq!(
+            app.get_fuse_status(Fuse::RunAsNode),
+            Ok(FuseStatus::Present(true))
+        );
+        assert!(app.assert_wire_well_formed().is_ok());
+    }
+
+    #[test]
+    fn targets_can_be_scanned() {
+        let bytes = test_app_bytes();
+        let app = ElectronApp::from_bytes_readonly(&bytes).unwrap();
+
+        assert!(app.scan(PatchTarget::NodeInspect).is_some());
+        assert!(app.scan(PatchTarget::DebuggerListeningMessage).is_some());
+        assert_eq!(app.electron_version(), None);
+    }
 }
@@ -0,0 +1,177 @@
+//! Patching applications in place through a memory map.
+//!
+//! Electron binaries are often hundreds of megabytes. Mapping them lets fuses and options be changed without reading
+//! the whole file into memory and writing it back, since only the pages that are searched or modified are loaded.
+
+use crate::{fuses::FuseWire, ElectronApp, ElectronAppBuf, PatcherError};
+use memmap2::MmapMut;
+use std::{
+    fs::OpenOptions,
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+/// A file mapped into memory read-write, which is flushed to disk when it's dropped.
+pub(crate) struct MappedFile {
+    map: MmapMut,
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+impl DerefMut for MappedFile {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.map
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        // Errors can't be reported from here. Callers that need to know should flush explicitly first.
+        let _ = self.map.flush();
+    }
+}
+
+impl ElectronApp<'static> {
+    /// Opens an application binary through a read-write memory map, so that it's modified in place.
+    ///
+    /// Every modification is made directly to the file. They're written to disk when the application is
+    /// [flushed](Self::flush) or dropped, although the operating system may write them earlier. Nothing is kept to
+    /// undo them if hardening fails partway, so use an [undo journal](Self::start_undo_journal) when that matters.
+    ///
+    /// # Caveats
+    ///
+    /// The file must not be truncated or modified by anything else while it's open, which would make the mapping
+    /// invalid. Don't patch a binary that's currently running: most platforms refuse to open it for writing, and on
+    /// those that don't, the running process sees the changes to its own pages and can crash. Modifying a binary also
+    /// invalidates its code signature, the same as with any other way of patching it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [Io](PatcherError::Io) if the file couldn't be opened or mapped. Otherwise, it returns
+    /// the same errors as [from_bytes](Self::from_bytes).
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> Result<ElectronAppBuf, PatcherError> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| PatcherError::io(path, &e))?;
+
+        // SAFETY: The mapping is only sound while nothing else modifies the file, which callers are told to ensure.
+        let map = unsafe { MmapMut::map_mut(&file) }.map_err(|e| PatcherError::io(path, &e))?;
+
+        FuseWire::check_input_len(map.len())?;
+        let wires = FuseWire::locate_all(&map, 0)?;
+
+        let mut app =
+            ElectronApp::with_contents(crate::Contents::Mapped(MappedFile { map }), wires);
+        app.path = Some(path.to_path_buf());
+        Ok(app)
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Writes the modifications made to an application [opened through a memory map](Self::open_mmap) to disk.
+    ///
+    /// This does nothing for applications that aren't mapped, since they aren't backed by a file.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [Io](PatcherError::Io) if the modifications couldn't be written.
+    pub fn flush(&self) -> Result<(), PatcherError> {
+        match (&self.contents, &self.path) {
+            (crate::Contents::Mapped(file), Some(path)) => {
+                file.map.flush().map_err(|e| PatcherError::io(path, &e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fuses::FuseStatus,
+        patcher::{tests::test_app_bytes, ElectronOption},
+        Fuse,
+    };
+    use std::fs;
+
+    fn temp_copy(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "electron-hardener-mmap-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, test_app_bytes()).unwrap();
+        path
+    }
+
+    fn harden(app: &mut ElectronApp<'_>) {
+        app.set_fuse_status(Fuse::RunAsNode, false).unwrap();
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+    }
+
+    fn expected_bytes() -> Vec<u8> {
+        let mut bytes = test_app_bytes();
+        harden(&mut ElectronApp::from_bytes(&mut bytes).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn mapped_apps_are_patched_in_place() {
+        let path = temp_copy("flush");
+
+        let on_disk = ElectronApp::open_mmap(&path).and_then(|mut app| {
+            assert_eq!(app.path(), Some(path.as_path()));
+            harden(&mut app);
+            assert_eq!(
+                app.get_fuse_status(Fuse::RunAsNode),
+                Ok(FuseStatus::Present(false))
+            );
+
+            app.flush()?;
+            // The changes are on disk before the mapping is dropped.
+            Ok(fs::read(&path).unwrap())
+        });
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(on_disk.unwrap(), expected_bytes());
+    }
+
+    #[test]
+    fn mapped_apps_are_flushed_when_dropped() {
+        let path = temp_copy("drop");
+
+        let mut app = ElectronApp::open_mmap(&path).unwrap();
+        harden(&mut app);
+        drop(app);
+
+        let on_disk = fs::read(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(on_disk.unwrap(), expected_bytes());
+    }
+
+    #[test]
+    fn unmappable_files_are_reported() {
+        let path = std::env::temp_dir().join(format!(
+            "electron-hardener-mmap-missing-{}",
+            std::process::id()
+        ));
+
+        assert!(matches!(
+            ElectronApp::open_mmap(&path).err(),
+            Some(PatcherError::Io {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            })
+        ));
+    }
+}
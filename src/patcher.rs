@@ -2,309 +2,2692 @@
 //!
 //! [electron-evil-feature-patcher]: https://github.com/antelle/electron-evil-feature-patcher
 
-use crate::{BinaryError, ElectronApp, PatcherError};
-use regex::bytes::Regex;
+use crate::{CancelToken, ElectronApp, ElectronVersion, UnknownNameError};
+use memchr::memmem;
+use std::{borrow::Cow, collections::HashMap, fmt, ops::Range, str::FromStr, sync::OnceLock};
+
+#[cfg(feature = "patch")]
+use crate::{
+    journal::{JournalEntry, ModifiedItem},
+    BinaryError, PatcherError,
+};
+#[cfg(feature = "patch")]
+use aho_corasick::AhoCorasick;
 
 #[cfg(test)]
 use enum_iterator::IntoEnumIterator;
 
+/// The byte that the rest of a patched Electron option's name is overwritten with, unless another one is
+/// [chosen](ElectronApp::set_patch_fill).
+///
+/// This isn't a null byte, so that the replacement ends at its own terminator and its length can be measured even
+/// when more null bytes follow it.
+pub(crate) const DEFAULT_PATCH_FILL: u8 = b'x';
+
 /// A flag inside an Electron application binary that can be patched to disable it.
+#[cfg(feature = "patch")]
 pub trait Patchable: private::Sealed {
     #[doc(hidden)]
     /// Disables the option.
     ///
     /// You are probably looking for [patch_option](ElectronApp::patch_option).
-    fn disable(&self, binary: &mut [u8]) -> Result<(), PatcherError>;
+    ///
+    /// Returns the range of the binary that was rewritten.
+    fn disable(&self, binary: &mut [u8]) -> Result<Range<usize>, PatcherError> {
+        let patch = self
+            .target()
+            .plan(binary, SearchScope::default(), None, DEFAULT_PATCH_FILL)
+            .map_err(|e| self.legacy_error(e))?;
+        patch.apply(binary);
+        Ok(patch.range)
+    }
 }
 
-#[allow(deprecated)]
+#[cfg(feature = "patch")]
 mod private {
-    use super::{DevToolsMessage, ElectronOption, NodeJsCommandLineFlag};
+    use super::PatchTarget;
+    use crate::PatcherError;
 
-    pub trait Sealed {}
+    pub trait Sealed {
+        /// Returns the patch target this option is disabled with.
+        fn target(&self) -> PatchTarget;
 
-    impl Sealed for NodeJsCommandLineFlag {}
-    impl Sealed for ElectronOption {}
-    impl Sealed for DevToolsMessage {}
+        /// Converts an error from patching the target into the one this type has always returned.
+        fn legacy_error(&self, e: PatcherError) -> PatcherError {
+            e
+        }
+    }
 }
 
-/// List of known command line debugging flags that can be disabled
+/// Every patch that can be applied to an Electron application binary.
 ///
-/// See the [Node.JS documentation] for details on what each flag does.
+/// This covers Electron command line options, Node.JS debugging flags, and Node.JS debugging messages. Environment variables
+/// such as `ELECTRON_RUN_AS_NODE` and `NODE_OPTIONS` are handled by [fuses](crate::Fuse) instead.
 ///
-/// [Node.JS documentation]: https://nodejs.org/en/docs/guides/debugging-getting-started/#command-line-options
-#[deprecated(
-    since = "0.2.2",
-    note = "This has been superseded by the NodeCliInspect fuse."
-)]
-#[allow(missing_docs)]
+/// The Node.JS flags and messages are no longer needed on Electron versions with the [NodeCliInspect](crate::Fuse::NodeCliInspect)
+/// fuse, but remain available here for applications that need to support older versions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
-pub enum NodeJsCommandLineFlag {
-    Inspect,
-    InspectBrk,
-    InspectPort,
-    Debug,
-    DebugBrk,
-    DebugPort,
-    InspectBrkNode,
-    InspectPublishUid,
+pub enum PatchTarget {
+    /// An Electron command line option.
+    ElectronOption(ElectronOption),
+    /// The Node.JS `--inspect` flag.
+    NodeInspect,
+    /// The Node.JS `--inspect-brk` flag.
+    NodeInspectBrk,
+    /// The Node.JS `--inspect-port` flag.
+    NodeInspectPort,
+    /// The Node.JS `--debug` flag.
+    NodeDebug,
+    /// The Node.JS `--debug-brk` flag.
+    NodeDebugBrk,
+    /// The Node.JS `--debug-port` flag.
+    NodeDebugPort,
+    /// The Node.JS `--inspect-brk-node` flag.
+    NodeInspectBrkNode,
+    /// The Node.JS `--inspect-publish-uid` flag.
+    NodeInspectPublishUid,
+    /// The Node.JS `--inspect-wait` flag.
+    NodeInspectWait,
+    /// The message printed to standard out when Node.JS listens on TCP port.
+    ///
+    /// Ex: `Debugger listening on 127.0.0.1:9229/uuid`
+    DebuggerListeningMessage,
+    /// The message printed to standard out when Node.JS listens on a websocket.
+    ///
+    /// Ex: `Debugger listening on ws://127.0.0.1:9229/uuid`
+    DevToolsListeningMessage,
 }
 
-#[allow(deprecated)]
-impl NodeJsCommandLineFlag {
-    const fn search_string(&self) -> &'static str {
+impl PatchTarget {
+    /// Every patch target known by this library, in [identifier](PatchTarget::id) order.
+    pub(crate) const ALL: &'static [PatchTarget] = &[
+        Self::ElectronOption(ElectronOption::JsFlags),
+        Self::ElectronOption(ElectronOption::RemoteDebuggingPipe),
+        Self::ElectronOption(ElectronOption::RemoteDebuggingPort),
+        Self::ElectronOption(ElectronOption::WaitForDebuggerChildren),
+        Self::NodeInspect,
+        Self::NodeInspectBrk,
+        Self::NodeInspectPort,
+        Self::NodeDebug,
+        Self::NodeDebugBrk,
+        Self::NodeDebugPort,
+        Self::NodeInspectBrkNode,
+        Self::NodeInspectPublishUid,
+        Self::DebuggerListeningMessage,
+        Self::DevToolsListeningMessage,
+        Self::ElectronOption(ElectronOption::RemoteAllowOrigins),
+        Self::NodeInspectWait,
+        Self::ElectronOption(ElectronOption::UnsafelyTreatInsecureOriginAsSecure),
+        Self::ElectronOption(ElectronOption::CrashServerUrl),
+        Self::ElectronOption(ElectronOption::CrashDumpsDir),
+        Self::ElectronOption(ElectronOption::FullMemoryCrashReport),
+        Self::ElectronOption(ElectronOption::DisableHangMonitor),
+        Self::ElectronOption(ElectronOption::DisableBreakpad),
+        Self::ElectronOption(ElectronOption::DisableHttpCache),
+        Self::ElectronOption(ElectronOption::DisableGpu),
+    ];
+
+    /// Returns a stable identifier for this target, for referring to it in configuration files and other languages.
+    ///
+    /// Identifiers are never changed or reused, and new targets are assigned the next unused one.
+    pub const fn id(&self) -> u16 {
         match self {
-            Self::Inspect => "\0--inspect\0",
-            Self::InspectBrk => "\0--inspect-brk\0",
-            Self::InspectPort => "\0--inspect-port\0",
-            Self::Debug => "\0--debug\0",
-            Self::DebugBrk => "\0--debug-brk\0",
-            Self::DebugPort => "\0--debug-port\0",
-            Self::InspectBrkNode => "\0--inspect-brk-node\0",
-            Self::InspectPublishUid => "\0--inspect-publish-uid\0",
+            Self::ElectronOption(opt) => match opt {
+                ElectronOption::JsFlags => 0,
+                ElectronOption::RemoteDebuggingPipe => 1,
+                ElectronOption::RemoteDebuggingPort => 2,
+                ElectronOption::WaitForDebuggerChildren => 3,
+                ElectronOption::RemoteAllowOrigins => 14,
+                ElectronOption::UnsafelyTreatInsecureOriginAsSecure => 16,
+                ElectronOption::CrashServerUrl => 17,
+                ElectronOption::CrashDumpsDir => 18,
+                ElectronOption::FullMemoryCrashReport => 19,
+                ElectronOption::DisableHangMonitor => 20,
+                ElectronOption::DisableBreakpad => 21,
+                ElectronOption::DisableHttpCache => 22,
+                ElectronOption::DisableGpu => 23,
+            },
+            Self::NodeInspect => 4,
+            Self::NodeInspectBrk => 5,
+            Self::NodeInspectPort => 6,
+            Self::NodeDebug => 7,
+            Self::NodeDebugBrk => 8,
+            Self::NodeDebugPort => 9,
+            Self::NodeInspectBrkNode => 10,
+            Self::NodeInspectPublishUid => 11,
+            Self::DebuggerListeningMessage => 12,
+            Self::DevToolsListeningMessage => 13,
+            Self::NodeInspectWait => 15,
         }
     }
 
-    const fn fallback_search_string(&self) -> Option<&'static str> {
-        // Electron 13 Windows binaries have flags laid out differently.
-        if matches!(self, Self::Inspect) {
-            Some(r"(?-u)\xAA--inspect\x00")
-        } else {
-            None
+    /// Returns the target with the provided [identifier](PatchTarget::id), if it is known by this library.
+    pub fn from_id(id: u16) -> Option<PatchTarget> {
+        Self::ALL.get(usize::from(id)).copied()
+    }
+
+    const fn search_pattern(&self) -> SearchPattern {
+        match self {
+            Self::ElectronOption(opt) => opt.search_pattern(),
+            Self::NodeInspect => SearchPattern::c_str(b"\0--inspect"),
+            Self::NodeInspectBrk => SearchPattern::c_str(b"\0--inspect-brk"),
+            Self::NodeInspectPort => SearchPattern::c_str(b"\0--inspect-port"),
+            Self::NodeDebug => SearchPattern::c_str(b"\0--debug"),
+            Self::NodeDebugBrk => SearchPattern::c_str(b"\0--debug-brk"),
+            Self::NodeDebugPort => SearchPattern::c_str(b"\0--debug-port"),
+            Self::NodeInspectBrkNode => SearchPattern::c_str(b"\0--inspect-brk-node"),
+            Self::NodeInspectPublishUid => SearchPattern::c_str(b"\0--inspect-publish-uid"),
+            Self::NodeInspectWait => SearchPattern::c_str(b"\0--inspect-wait"),
+            Self::DebuggerListeningMessage => SearchPattern::c_str(b"\0Debugger listening on %s\n"),
+            Self::DevToolsListeningMessage => {
+                SearchPattern::c_str(b"\0\nDevTools listening on ws://%s%s\n")
+            }
         }
     }
-}
 
-#[allow(deprecated)]
-impl Patchable for NodeJsCommandLineFlag {
-    fn disable(&self, binary: &mut [u8]) -> Result<(), PatcherError> {
-        let search = Regex::new(self.search_string()).expect("all regex patterns should be valid");
-        let found = search
-            .find(binary)
-            .or_else(|| {
-                self.fallback_search_string().and_then(|s| {
-                    let search = Regex::new(s).expect("all regex patterns should be valid");
-                    search.find(binary)
-                })
-            })
-            .ok_or(BinaryError::NodeJsFlagNotPresent(*self))?
-            .range();
+    /// Returns the pattern that's searched for when the canonical one isn't found anywhere in the binary.
+    ///
+    /// Windows builds of Electron 13 pad Node.JS's flags in `.rdata` with `0xAA` instead of placing them directly
+    /// after the previous string's null terminator.
+    const fn fallback_pattern(&self) -> Option<SearchPattern> {
+        let literal: &'static [u8] = match self {
+            Self::NodeInspect => b"\xAA--inspect",
+            Self::NodeInspectBrk => b"\xAA--inspect-brk",
+            Self::NodeInspectPort => b"\xAA--inspect-port",
+            Self::NodeDebug => b"\xAA--debug",
+            Self::NodeDebugBrk => b"\xAA--debug-brk",
+            Self::NodeDebugPort => b"\xAA--debug-port",
+            Self::NodeInspectBrkNode => b"\xAA--inspect-brk-node",
+            Self::NodeInspectPublishUid => b"\xAA--inspect-publish-uid",
+            Self::NodeInspectWait => b"\xAA--inspect-wait",
+            _ => return None,
+        };
+        Some(SearchPattern::c_str(literal))
+    }
 
-        for b in &mut binary[found] {
-            if *b == b'-' {
-                *b = b' '
-            }
+    /// Returns the first major version of Electron that contains this target, if it isn't available in every
+    /// version supported by this library.
+    pub const fn available_since(&self) -> Option<u32> {
+        match self {
+            Self::ElectronOption(opt) => opt.available_since(),
+            // Added in Node.JS 20.15, which first shipped in Electron 32.
+            Self::NodeInspectWait => Some(32),
+            _ => None,
         }
+    }
 
-        Ok(())
+    /// Returns the major version of Electron that this target was removed in, if it has been.
+    pub const fn removed_in(&self) -> Option<u32> {
+        // None of the supported targets have been removed from Electron yet.
+        None
     }
-}
 
-/// List of known Electron command line flags that can be disabled.
-///
-/// See the [Electron documentation] for details on what each flag does.
-///
-/// [Electron documentation]: https://www.electronjs.org/docs/api/command-line-switches
-#[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(test, derive(IntoEnumIterator))]
-#[non_exhaustive]
-pub enum ElectronOption {
-    JsFlags,
-    RemoteDebuggingPipe,
-    RemoteDebuggingPort,
-    WaitForDebuggerChildren,
+    /// Returns `true` if this target is expected to be present in the provided version of Electron.
+    pub fn is_available_in(&self, version: ElectronVersion) -> bool {
+        let not_added = matches!(self.available_since(), Some(v) if version.major < v);
+        let removed = matches!(self.removed_in(), Some(v) if version.major >= v);
+        !not_added && !removed
+    }
+
+    /// Returns `true` if this target is a Node.JS debugging flag or message, which the
+    /// [NodeCliInspect](crate::Fuse::NodeCliInspect) fuse supersedes.
+    pub const fn is_node_debugging(&self) -> bool {
+        !matches!(self, Self::ElectronOption(_))
+    }
+
+    #[cfg(feature = "patch")]
+    fn not_present(&self) -> BinaryError {
+        match self {
+            Self::ElectronOption(opt) => BinaryError::ElectronOptionNotPresent(*opt),
+            _ => BinaryError::TargetNotPresent(*self),
+        }
+    }
 }
 
-impl ElectronOption {
-    const fn search_string(&self) -> &'static str {
+/// Formats the target with a stable name. Electron options use the name of their command line switch, such as
+/// `js-flags`, and other targets use the name of their variant, such as `NodeInspect`.
+impl fmt::Display for PatchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::JsFlags => "\0js-flags\0",
-            Self::RemoteDebuggingPipe => "\0remote-debugging-pipe\0",
-            Self::RemoteDebuggingPort => "\0remote-debugging-port\0",
-            Self::WaitForDebuggerChildren => "\0wait-for-debugger-children\0",
+            Self::ElectronOption(opt) => write!(f, "{}", opt),
+            _ => write!(f, "{:?}", self),
         }
     }
 }
 
-impl Patchable for ElectronOption {
-    fn disable(&self, binary: &mut [u8]) -> Result<(), PatcherError> {
-        let search = Regex::new(self.search_string()).expect("all regex patterns should be valid");
-        let found = search
-            .find(binary)
-            .ok_or(BinaryError::ElectronOptionNotPresent(*self))?
-            .range();
+/// Where in a binary patch targets are searched for.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SearchScope<'a> {
+    /// A range that's searched before the rest of the binary.
+    pub(crate) hint: Option<&'a Range<usize>>,
+    /// The only ranges that are searched, if the binary's layout is known.
+    pub(crate) sections: Option<&'a [Range<usize>]>,
+}
 
-        let replacement = b"\0xx\r\n"
-            .iter()
-            .copied()
-            .chain(std::iter::repeat(0))
-            .take(found.len());
+impl PatchTarget {
+    /// Searches the binary for this target, returning where it was found and which pattern matched.
+    pub fn locate(&self, binary: &[u8]) -> Option<PatternMatch> {
+        self.locate_within(binary, SearchScope::default(), None)
+    }
+
+    /// Searches the scope's hint for this target before falling back to the rest of the scope.
+    ///
+    /// If `cancel` is cancelled, the search stops early and `None` is returned.
+    pub(crate) fn locate_within(
+        &self,
+        binary: &[u8],
+        scope: SearchScope<'_>,
+        cancel: Option<&CancelToken>,
+    ) -> Option<PatternMatch> {
+        let whole = 0..binary.len();
+        let ranges = scope.sections.unwrap_or(std::slice::from_ref(&whole));
+
+        if let Some(hint) = scope.hint {
+            // The hint only narrows down the ranges that are searched first.
+            let hinted: Vec<Range<usize>> = ranges
+                .iter()
+                .map(|range| range.start.max(hint.start)..range.end.min(hint.end))
+                .collect();
 
-        for (old, new) in binary[found].iter_mut().zip(replacement) {
-            *old = new;
+            if let Some(found) = self.search(binary, &hinted, cancel) {
+                return Some(found);
+            }
         }
 
-        Ok(())
+        self.search(binary, ranges, cancel)
     }
-}
 
-/// List of known developer tool command line messages that can be
-/// written to stdout by Node.JS during debugging.
-///
-/// ### Warning
-///
-/// Disabling these is a worst-case fallback protection against internal changes to the way
-/// that Chromium/Electron/Node.JS handle parsing command line arguments. If something is changed
-/// and a debugging flag slips through, modifying one of these will cause the application to trigger a segemntation fault
-/// and be terminated by the OS, exiting immediately.
-#[deprecated(
-    since = "0.2.2",
-    note = "This is no longer necessary due to the NodeCliInspect fuse's functionality."
-)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[non_exhaustive]
-pub enum DevToolsMessage {
-    /// The message printed to standard out when Node.JS listens on TCP port.
+    /// Searches each range for this target's canonical pattern, then each range for its fallback pattern.
+    fn search(
+        &self,
+        binary: &[u8],
+        ranges: &[Range<usize>],
+        cancel: Option<&CancelToken>,
+    ) -> Option<PatternMatch> {
+        let find = |pattern: SearchPattern| {
+            ranges.iter().find_map(|range| {
+                let start = range.start.min(binary.len());
+                let end = range.end.min(binary.len());
+                if start >= end {
+                    return None;
+                }
+
+                let found = pattern.find(&binary[start..end], cancel)?;
+                Some((found.start + start)..(found.end + start))
+            })
+        };
+
+        find(self.search_pattern())
+            .map(|range| PatternMatch {
+                matched_pattern: PatternId::Canonical,
+                range,
+                section: None,
+            })
+            .or_else(|| {
+                let range = find(self.fallback_pattern()?)?;
+                Some(PatternMatch {
+                    matched_pattern: PatternId::Fallback,
+                    range,
+                    section: None,
+                })
+            })
+    }
+
+    /// Creates the bytes that will replace the matched bytes of this target.
     ///
-    /// Ex: `Debugger listening on 127.0.0.1:9229/uuid`
-    Listening,
-    /// The message printed to standard out when Node.JS listens on a websocket.
+    /// Electron options have the rest of their name overwritten with `fill`, and always end with a null byte.
+    fn replacement(&self, original: &[u8], fill: u8) -> Vec<u8> {
+        let mut replacement = match self {
+            Self::ElectronOption(_) => {
+                let mut replacement: Vec<u8> = b"\0xx\r\n"
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(fill))
+                    .take(original.len().saturating_sub(1))
+                    .collect();
+                replacement.push(0);
+                replacement
+            }
+            Self::DebuggerListeningMessage | Self::DevToolsListeningMessage => {
+                let mut replacement = Vec::with_capacity(original.len());
+                replacement.push(b'\0');
+                let str_len = original.len() - 3;
+                for _ in (0..str_len).step_by(2) {
+                    replacement.push(b'%');
+                    replacement.push(b's');
+                }
+                replacement.extend_from_slice(b"\n\0");
+                replacement
+            }
+            _ => original
+                .iter()
+                .map(|&b| if b == b'-' { b' ' } else { b })
+                .collect(),
+        };
+
+        replacement.truncate(original.len());
+        replacement
+    }
+
+    /// Creates the bytes that [electron-evil-feature-patcher] writes over the matched bytes of this target.
     ///
-    /// Ex: `Debugger listening on ws://127.0.0.1:9229/uuid`
-    ListeningWs,
-}
+    /// Its replacements have the same structure as the ones made by this library, but are filled differently:
+    /// - Electron options are padded with spaces instead of null bytes.
+    /// - Node.JS flags have their dashes replaced with `x` instead of spaces.
+    /// - Messages contain a single `%s`, padded with spaces.
+    ///
+    /// [electron-evil-feature-patcher]: https://github.com/antelle/electron-evil-feature-patcher
+    fn evil_feature_patcher_replacement(&self, original: &[u8]) -> Vec<u8> {
+        let fill = |prefix: &[u8], padding: u8, suffix: &[u8]| {
+            let mut replacement = prefix.to_vec();
+            replacement.resize(original.len() - suffix.len(), padding);
+            replacement.extend_from_slice(suffix);
+            replacement
+        };
 
-#[allow(deprecated)]
-impl DevToolsMessage {
-    const fn search_string(&self) -> &'static str {
         match self {
-            #[allow(deprecated)]
-            Self::Listening => "\0Debugger listening on %s\n\0",
-            Self::ListeningWs => "\0\nDevTools listening on ws://%s%s\n\0",
+            Self::ElectronOption(_) => fill(b"\0xx\r\n", b' ', b"\0"),
+            Self::DebuggerListeningMessage | Self::DevToolsListeningMessage => {
+                fill(b"\0%s", b' ', b"\n\0")
+            }
+            _ => original
+                .iter()
+                .map(|&b| if b == b'-' { b'x' } else { b })
+                .collect(),
         }
     }
-}
 
-#[allow(deprecated)]
-impl Patchable for DevToolsMessage {
-    fn disable(&self, binary: &mut [u8]) -> Result<(), PatcherError> {
-        let search = Regex::new(self.search_string()).expect("all regex patterns should be valid");
-        let found = search
-            .find(binary)
-            .ok_or(BinaryError::MessageNotPresent(*self))?
-            .range();
+    /// Determines whether this target is still present in the binary, and which tool patched it out if it isn't.
+    ///
+    /// Patched Electron options all look alike apart from the length of their padding, so an option is only reported
+    /// as patched if a replacement of exactly its length is found.
+    pub fn patch_state(&self, binary: &[u8]) -> PatchState {
+        if self.locate(binary).is_some() {
+            return PatchState::Unpatched;
+        }
+
+        // Patched strings are always searched for with their null terminator, since that's how they are usually
+        // embedded.
+        let originals: Vec<Vec<u8>> = std::iter::once(self.search_pattern())
+            .chain(self.fallback_pattern())
+            .map(|pattern| [pattern.literal, &b"\0"[..]].concat())
+            .collect();
 
-        let mut replacement = Vec::with_capacity(found.len());
-        replacement.push(b'\0');
-        let str_len = found.len() - 3;
-        for _ in (0..str_len).step_by(2) {
-            replacement.push(b'%');
-            replacement.push(b's');
+        if let Self::ElectronOption(_) = self {
+            let replacements = filled_replacements(binary);
+            let patched = |is_fill: &dyn Fn(u8) -> bool| {
+                replacements.iter().any(|&(len, fill)| {
+                    is_fill(fill) && originals.iter().any(|original| original.len() == len)
+                })
+            };
+
+            // Replacements filled with spaces can't be told apart from electron-evil-feature-patcher's, so they're
+            // attributed to it.
+            return if patched(&|fill| fill == DEFAULT_PATCH_FILL) {
+                PatchState::PatchedByThisTool
+            } else if patched(&|fill| fill == b' ') {
+                PatchState::PatchedByEvilFeaturePatcher
+            } else if patched(&|_| true) {
+                PatchState::PatchedByThisTool
+            } else {
+                PatchState::Absent
+            };
         }
-        replacement.extend_from_slice(b"\n\0");
 
-        for (old, new) in binary[found].iter_mut().zip(replacement) {
-            *old = new;
+        let contains = |replacement: Vec<u8>| memmem::find(binary, &replacement).is_some();
+
+        if originals
+            .iter()
+            .any(|original| contains(self.replacement(original, DEFAULT_PATCH_FILL)))
+        {
+            PatchState::PatchedByThisTool
+        } else if originals
+            .iter()
+            .any(|original| contains(self.evil_feature_patcher_replacement(original)))
+        {
+            PatchState::PatchedByEvilFeaturePatcher
+        } else {
+            PatchState::Absent
         }
+    }
 
-        Ok(())
+    /// Counts how many times each of this target's search patterns matches in the binary.
+    ///
+    /// The fallback pattern's count is `None` if the target doesn't have one.
+    pub(crate) fn count_matches(&self, binary: &[u8]) -> (usize, Option<usize>) {
+        (
+            self.search_pattern().count(binary),
+            self.fallback_pattern().map(|pattern| pattern.count(binary)),
+        )
     }
 }
 
-impl ElectronApp<'_> {
-    /// Disables the ability to use this command line flag in the application.
-    ///
-    /// After being disabled, the flag will no longer be processed by the application. The removal
-    /// is a best-effort attempt. See the [crate documentation on effectiveness](crate).
-    pub fn patch_option<P: Patchable>(&mut self, to_disable: P) -> Result<(), PatcherError> {
-        to_disable.disable(self.contents)
+/// Finds every patched Electron option in the binary, returning the length and fill byte of each replacement.
+///
+/// Replacements are the replacement prefix, a run of a single fill byte, and exactly one null terminator, which is
+/// shared with the next string. Only complete runs are measured, so a replacement is never mistaken for a shorter
+/// one that would fit inside of it, like the null padding of a longer option.
+fn filled_replacements(binary: &[u8]) -> Vec<(usize, u8)> {
+    const PREFIX: &[u8] = b"\0xx\r\n";
+
+    let mut replacements = Vec::new();
+    let mut previous_end = 0;
+
+    for pos in memmem::find_iter(binary, PREFIX) {
+        // The prefix can't be part of an earlier replacement's fill run. Adjacent replacements share the null byte
+        // between them.
+        if pos + 1 < previous_end {
+            continue;
+        }
+
+        let start = pos + PREFIX.len();
+        let fill = match binary.get(start) {
+            Some(&fill) => fill,
+            None => continue,
+        };
+        let filled = binary[start..].iter().take_while(|&&b| b == fill).count();
+
+        // Any other fill is followed by the terminator, which gives the replacement's exact length even if the
+        // string was padded with more null bytes. Null padding includes its own terminator, and can't be told apart
+        // from null bytes after it, so it's only bounded if the next string starts right after it.
+        let end = if fill == 0 {
+            start + filled
+        } else if binary.get(start + filled) == Some(&0) {
+            start + filled + 1
+        } else {
+            continue;
+        };
+
+        previous_end = end;
+        replacements.push((end - pos, fill));
     }
+
+    replacements
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(feature = "patch")]
+impl PatchTarget {
+    /// Returns the bytes this target is usually embedded in a binary as, including its terminator.
+    pub(crate) fn embedded(&self) -> Vec<u8> {
+        let pattern = self.search_pattern();
+        [pattern.literal, &pattern.terminators[..1]].concat()
+    }
 
-    const TEST_DATA: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+    /// Locates this target in the binary and computes how it will be modified, without writing anything.
+    pub(crate) fn plan(
+        &self,
+        binary: &[u8],
+        scope: SearchScope<'_>,
+        cancel: Option<&CancelToken>,
+        fill: u8,
+    ) -> Result<PlannedPatch, PatcherError> {
+        let found = match self.locate_within(binary, scope, cancel) {
+            Some(found) => found,
+            None if matches!(cancel, Some(token) if token.is_cancelled()) => {
+                return Err(PatcherError::Cancelled)
+            }
+            None => return Err(self.not_present().into()),
+        };
+        Ok(self.plan_at(binary, found, fill))
+    }
 
-    #[test]
-    #[allow(deprecated)]
-    fn disabling_nodejs_flags_works() {
-        use NodeJsCommandLineFlag::*;
-        let mut data = TEST_DATA.to_vec();
+    /// Computes how this target will be modified where it was found in the binary.
+    fn plan_at(&self, binary: &[u8], found: PatternMatch, fill: u8) -> PlannedPatch {
+        let original = &binary[found.range.clone()];
+        let replacement = self.replacement(original, fill);
 
-        const ALL_FLAGS: &[NodeJsCommandLineFlag] = &[
-            Inspect,
-            InspectBrk,
-            InspectPort,
-            Debug,
-            DebugBrk,
-            DebugPort,
-            InspectBrkNode,
-            InspectPublishUid,
-        ];
+        // Only the bytes that actually change are part of the patch. This keeps the shared
+        // null terminators of neighboring strings from being considered overlapping.
+        let changed = |i: &usize| original[*i] != replacement[*i];
+        let first = (0..original.len()).find(changed).unwrap_or(0);
+        let last = (0..original.len())
+            .rev()
+            .find(changed)
+            .map_or(original.len(), |i| i + 1);
+
+        PlannedPatch {
+            target: *self,
+            range: (found.range.start + first)..(found.range.start + last),
+            replacement: replacement[first..last].to_vec(),
+            found,
+        }
+    }
+
+    /// Searches the scope for several targets at once, walking the binary a single time instead of once for each of
+    /// them.
+    ///
+    /// Each target is found where [locate_within](Self::locate_within) would find it. If `cancel` is cancelled, the
+    /// search stops early and the targets that weren't found yet are `None`.
+    pub(crate) fn locate_many(
+        targets: &[PatchTarget],
+        binary: &[u8],
+        scope: SearchScope<'_>,
+        cancel: Option<&CancelToken>,
+    ) -> Vec<Option<PatternMatch>> {
+        let whole = 0..binary.len();
+        let ranges = scope.sections.unwrap_or(std::slice::from_ref(&whole));
+        let mut found = vec![None; targets.len()];
+
+        if let Some(hint) = scope.hint {
+            let hinted: Vec<Range<usize>> = ranges
+                .iter()
+                .map(|range| range.start.max(hint.start)..range.end.min(hint.end))
+                .collect();
+            Self::search_many(targets, binary, &hinted, cancel, &mut found);
+        }
+
+        if found.iter().any(Option::is_none) {
+            Self::search_many(targets, binary, ranges, cancel, &mut found);
+        }
+
+        found
+    }
+
+    /// Searches each range for the targets that haven't been found yet, filling in where they were found.
+    fn search_many(
+        targets: &[PatchTarget],
+        binary: &[u8],
+        ranges: &[Range<usize>],
+        cancel: Option<&CancelToken>,
+        found: &mut [Option<PatternMatch>],
+    ) {
+        let compiled = CompiledPatterns::get();
+
+        // Which of the targets that haven't been found yet each compiled pattern belongs to.
+        let mut wanted: Vec<Vec<usize>> = vec![Vec::new(); compiled.patterns.len()];
+        for (index, (target, _, _)) in compiled.patterns.iter().enumerate() {
+            wanted[index] = (0..targets.len())
+                .filter(|&i| targets[i] == *target && found[i].is_none())
+                .collect();
+        }
+        if wanted.iter().all(Vec::is_empty) {
+            return;
+        }
+
+        let longest = compiled
+            .patterns
+            .iter()
+            .map(|(_, _, pattern)| pattern.literal.len())
+            .max()
+            .unwrap_or(0);
+
+        // The first match of each pattern, in the first range containing one.
+        let mut matches: Vec<Option<Range<usize>>> = vec![None; compiled.patterns.len()];
+        let mut canonical_left = compiled
+            .patterns
+            .iter()
+            .zip(&wanted)
+            .filter(|((_, id, _), wanted)| *id == PatternId::Canonical && !wanted.is_empty())
+            .count();
+
+        'ranges: for range in ranges {
+            let start = range.start.min(binary.len());
+            let end = range.end.min(binary.len());
+            let haystack = &binary[start..end];
+
+            let mut chunk_start = 0;
+            while chunk_start < haystack.len() {
+                if matches!(cancel, Some(token) if token.is_cancelled()) {
+                    return;
+                }
+
+                // Chunks overlap by the longest literal, like in SearchPattern::find.
+                let chunk_end = chunk_start
+                    .saturating_add(SearchPattern::CHUNK_LEN + longest)
+                    .min(haystack.len());
 
-        // Remove all the flags supported.
-        for flag in ALL_FLAGS {
-            flag.disable(&mut data).unwrap();
+                for m in compiled
+                    .automaton
+                    .find_overlapping_iter(&haystack[chunk_start..chunk_end])
+                {
+                    let index = m.pattern().as_usize();
+                    let (_, id, pattern) = compiled.patterns[index];
+                    let found_at = chunk_start + m.start();
+                    let literal_end = chunk_start + m.end();
 
-            if flag.fallback_search_string().is_some() {
-                let _ = flag.disable(&mut data);
+                    if wanted[index].is_empty() || matches[index].is_some() {
+                        continue;
+                    }
+                    match haystack.get(literal_end) {
+                        Some(b) if pattern.terminators.contains(b) => {}
+                        _ => continue,
+                    }
+
+                    matches[index] = Some((found_at + start)..(literal_end + 1 + start));
+                    if id == PatternId::Canonical {
+                        canonical_left -= 1;
+                        if canonical_left == 0 {
+                            break 'ranges;
+                        }
+                    }
+                }
+
+                chunk_start += SearchPattern::CHUNK_LEN;
             }
         }
 
-        // Ensure they no longer exist
-        for flag in ALL_FLAGS {
-            assert_eq!(
-                flag.disable(&mut data),
-                Err(PatcherError::Binary(BinaryError::NodeJsFlagNotPresent(
-                    *flag
-                )))
-            );
+        // Each target's canonical pattern comes before its fallback, so the fallback is only used if the canonical
+        // pattern wasn't found anywhere.
+        for (((_, matched_pattern, _), range), wanted) in
+            compiled.patterns.iter().zip(matches).zip(wanted)
+        {
+            for i in wanted {
+                if let (Some(range), None) = (&range, &found[i]) {
+                    found[i] = Some(PatternMatch {
+                        matched_pattern: *matched_pattern,
+                        range: range.clone(),
+                        section: None,
+                    });
+                }
+            }
         }
     }
+}
+
+#[cfg(feature = "patch")]
+impl Patchable for PatchTarget {}
+
+#[cfg(feature = "patch")]
+impl private::Sealed for PatchTarget {
+    fn target(&self) -> PatchTarget {
+        *self
+    }
+}
 
-    #[test]
-    fn disabling_electron_options_works() {
-        let mut data = TEST_DATA.to_vec();
+/// A literal string to search a binary for, followed by one of a set of terminating bytes.
+///
+/// The terminator is usually the null byte ending a C string, but options that can be embedded with a value can
+/// also be followed by `=`.
+#[derive(Debug, Clone, Copy)]
+struct SearchPattern {
+    literal: &'static [u8],
+    terminators: &'static [u8],
+}
 
-        // Remove all the options supported.
-        for opt in ElectronOption::into_enum_iter() {
-            opt.disable(&mut data).unwrap();
+impl SearchPattern {
+    /// A pattern for a null terminated string.
+    const fn c_str(literal: &'static [u8]) -> Self {
+        Self {
+            literal,
+            terminators: b"\0",
         }
+    }
 
-        // Ensure they no longer exist
-        for opt in ElectronOption::into_enum_iter() {
-            assert_eq!(
-                opt.disable(&mut data),
-                Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(
-                    opt
-                )))
-            );
+    /// How many bytes are searched between checks for cancellation.
+    const CHUNK_LEN: usize = 1 << 20;
+
+    /// Returns the searcher for this pattern's literal, which is only compiled once for the patch targets' patterns.
+    fn finder(&self) -> Cow<'static, memmem::Finder<'static>> {
+        match CompiledPatterns::get().finders.get(self.literal) {
+            Some(finder) => Cow::Borrowed(finder),
+            None => Cow::Owned(memmem::Finder::new(self.literal)),
         }
     }
 
-    #[allow(deprecated)]
-    #[test]
-    fn disabling_debugging_messages_works() {
-        let mut data = TEST_DATA.to_vec();
+    /// Returns the position of the first match in the haystack, including its terminator.
+    ///
+    /// The haystack is searched in chunks so that `cancel` can be checked regularly. If it's cancelled, `None` is
+    /// returned.
+    fn find(&self, haystack: &[u8], cancel: Option<&CancelToken>) -> Option<Range<usize>> {
+        let finder = self.finder();
+        let mut start = 0;
 
-        const ALL_MESSAGES: &[DevToolsMessage] =
-            &[DevToolsMessage::ListeningWs, DevToolsMessage::Listening];
+        while start < haystack.len() {
+            if matches!(cancel, Some(token) if token.is_cancelled()) {
+                return None;
+            }
 
-        // Remove all the options supported.
-        for msg in ALL_MESSAGES.iter().copied() {
-            msg.disable(&mut data).unwrap();
+            // Chunks overlap by the length of the literal, so matches crossing a chunk boundary are still found.
+            let chunk_end = start
+                .saturating_add(Self::CHUNK_LEN + self.literal.len())
+                .min(haystack.len());
+
+            let pos = match finder.find(&haystack[start..chunk_end]) {
+                Some(pos) => pos,
+                None => {
+                    start += Self::CHUNK_LEN;
+                    continue;
+                }
+            };
+
+            let found = start + pos;
+            let end = found + self.literal.len();
+
+            match haystack.get(end) {
+                Some(b) if self.terminators.contains(b) => return Some(found..(end + 1)),
+                Some(_) => start = found + 1,
+                None => return None,
+            }
         }
 
-        // Ensure they no longer exist
-        for msg in ALL_MESSAGES.iter().copied() {
-            assert_eq!(
-                msg.disable(&mut data),
-                Err(PatcherError::Binary(BinaryError::MessageNotPresent(msg)))
-            );
+        None
+    }
+
+    /// Returns how many times the pattern matches in the haystack, including overlapping matches.
+    fn count(&self, haystack: &[u8]) -> usize {
+        self.finder()
+            .find_iter(haystack)
+            .filter(|pos| {
+                matches!(haystack.get(pos + self.literal.len()), Some(b) if self.terminators.contains(b))
+            })
+            .count()
+    }
+}
+
+/// The search patterns of every patch target, compiled the first time they're needed and shared by every search
+/// after that.
+struct CompiledPatterns {
+    /// Every pattern along with the target it belongs to. A target's canonical pattern comes before its fallback.
+    #[cfg(feature = "patch")]
+    patterns: Vec<(PatchTarget, PatternId, SearchPattern)>,
+    /// A searcher for each pattern's literal on its own.
+    finders: HashMap<&'static [u8], memmem::Finder<'static>>,
+    /// Searches for all of the patterns at once, identifying them by their index in `patterns`.
+    #[cfg(feature = "patch")]
+    automaton: AhoCorasick,
+}
+
+impl CompiledPatterns {
+    fn get() -> &'static Self {
+        static COMPILED: OnceLock<CompiledPatterns> = OnceLock::new();
+
+        COMPILED.get_or_init(|| {
+            let patterns: Vec<(PatchTarget, PatternId, SearchPattern)> = PatchTarget::ALL
+                .iter()
+                .flat_map(|&target| {
+                    std::iter::once((target, PatternId::Canonical, target.search_pattern())).chain(
+                        target
+                            .fallback_pattern()
+                            .map(|pattern| (target, PatternId::Fallback, pattern)),
+                    )
+                })
+                .collect();
+
+            let finders = patterns
+                .iter()
+                .map(|(_, _, pattern)| (pattern.literal, memmem::Finder::new(pattern.literal)))
+                .collect();
+
+            #[cfg(feature = "patch")]
+            let automaton =
+                AhoCorasick::new(patterns.iter().map(|(_, _, pattern)| pattern.literal))
+                    .expect("search patterns are always valid");
+
+            CompiledPatterns {
+                #[cfg(feature = "patch")]
+                patterns,
+                finders,
+                #[cfg(feature = "patch")]
+                automaton,
+            }
+        })
+    }
+}
+
+/// Whether a [patch target](PatchTarget) is still present in a binary, and which tool patched it out if it isn't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum PatchState {
+    /// The target was patched out by this library.
+    PatchedByThisTool,
+    /// The target was patched out by [electron-evil-feature-patcher].
+    ///
+    /// [electron-evil-feature-patcher]: https://github.com/antelle/electron-evil-feature-patcher
+    PatchedByEvilFeaturePatcher,
+    /// The target is present and hasn't been patched.
+    Unpatched,
+    /// Neither the target nor a patched version of it could be found.
+    Absent,
+}
+
+/// Identifies which of a patch target's search patterns matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum PatternId {
+    /// The target's usual spelling.
+    Canonical,
+    /// A pattern for a binary layout used by specific Electron versions or platforms.
+    Fallback,
+}
+
+/// Where a patch target was found in a binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PatternMatch {
+    /// The pattern that matched.
+    pub matched_pattern: PatternId,
+    /// The position of the matched bytes in the binary.
+    pub range: Range<usize>,
+    /// The name of the object file section the match is in, if the application's sections are known.
+    ///
+    /// This is only set for matches found in an [application](ElectronApp), and requires the `object-parser`
+    /// feature.
+    pub section: Option<String>,
+}
+
+/// A patch that has been located in a binary, but hasn't been written to it yet.
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlannedPatch {
+    pub(crate) target: PatchTarget,
+    /// Where the target was found.
+    pub(crate) found: PatternMatch,
+    /// The bytes in the binary that will be modified.
+    pub(crate) range: Range<usize>,
+    /// The new contents of `range`.
+    pub(crate) replacement: Vec<u8>,
+}
+
+#[cfg(feature = "patch")]
+impl PlannedPatch {
+    pub(crate) fn apply(&self, binary: &mut [u8]) {
+        binary[self.range.clone()].copy_from_slice(&self.replacement);
+    }
+
+    /// Checks that no two patches modify the same bytes.
+    pub(crate) fn check_overlaps(patches: &[PlannedPatch]) -> Result<(), PatcherError> {
+        let mut sorted: Vec<&PlannedPatch> = patches.iter().collect();
+        sorted.sort_by_key(|patch| patch.range.start);
+
+        for pair in sorted.windows(2) {
+            let (first, second) = (pair[0], pair[1]);
+            if second.range.start < first.range.end {
+                return Err(PatcherError::OverlappingPatches {
+                    first: (first.target, first.range.clone()),
+                    second: (second.target, second.range.clone()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// List of known command line debugging flags that can be disabled
+///
+/// See the [Node.JS documentation] for details on what each flag does.
+///
+/// Use the equivalent [PatchTarget] variants if these still need to be patched.
+///
+/// [Node.JS documentation]: https://nodejs.org/en/docs/guides/debugging-getting-started/#command-line-options
+#[cfg(feature = "patch")]
+#[deprecated(
+    since = "0.2.2",
+    note = "This has been superseded by the NodeCliInspect fuse."
+)]
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NodeJsCommandLineFlag {
+    Inspect,
+    InspectBrk,
+    InspectPort,
+    Debug,
+    DebugBrk,
+    DebugPort,
+    InspectBrkNode,
+    InspectPublishUid,
+}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl From<NodeJsCommandLineFlag> for PatchTarget {
+    fn from(flag: NodeJsCommandLineFlag) -> Self {
+        match flag {
+            NodeJsCommandLineFlag::Inspect => Self::NodeInspect,
+            NodeJsCommandLineFlag::InspectBrk => Self::NodeInspectBrk,
+            NodeJsCommandLineFlag::InspectPort => Self::NodeInspectPort,
+            NodeJsCommandLineFlag::Debug => Self::NodeDebug,
+            NodeJsCommandLineFlag::DebugBrk => Self::NodeDebugBrk,
+            NodeJsCommandLineFlag::DebugPort => Self::NodeDebugPort,
+            NodeJsCommandLineFlag::InspectBrkNode => Self::NodeInspectBrkNode,
+            NodeJsCommandLineFlag::InspectPublishUid => Self::NodeInspectPublishUid,
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl Patchable for NodeJsCommandLineFlag {}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl private::Sealed for NodeJsCommandLineFlag {
+    fn target(&self) -> PatchTarget {
+        PatchTarget::from(*self)
+    }
+
+    fn legacy_error(&self, e: PatcherError) -> PatcherError {
+        match e {
+            PatcherError::Binary(BinaryError::TargetNotPresent(_)) => {
+                BinaryError::NodeJsFlagNotPresent(*self).into()
+            }
+            e => e,
+        }
+    }
+}
+
+/// List of known Electron command line flags that can be disabled.
+///
+/// See the [Electron documentation] for details on what each flag does.
+///
+/// [Electron documentation]: https://www.electronjs.org/docs/api/command-line-switches
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(test, derive(IntoEnumIterator))]
+#[non_exhaustive]
+pub enum ElectronOption {
+    JsFlags,
+    RemoteDebuggingPipe,
+    RemoteDebuggingPort,
+    WaitForDebuggerChildren,
+    RemoteAllowOrigins,
+    UnsafelyTreatInsecureOriginAsSecure,
+    CrashServerUrl,
+    /// Redirects where crash dumps, which can contain secrets from process memory, are written.
+    ///
+    /// Patching this out doesn't affect a dump directory the application configures at runtime, such as with
+    /// `app.setPath('crashDumps', ...)`, since that doesn't rely on the switch.
+    CrashDumpsDir,
+    /// Makes crash dumps include all of the crashed process's memory.
+    FullMemoryCrashReport,
+    /// Stops unresponsive renderers from being detected, which can keep a compromised renderer alive.
+    DisableHangMonitor,
+    /// Stops crash dumps from being written, which can hide evidence of a compromise.
+    DisableBreakpad,
+    /// Makes every request bypass the HTTP cache, which forces the application's traffic onto the network where it's
+    /// easier to observe.
+    DisableHttpCache,
+    /// Forces rendering onto the software path, which is easier to instrument than GPU rendering.
+    DisableGpu,
+}
+
+impl ElectronOption {
+    /// Every Electron option known by this library.
+    const ALL: &'static [ElectronOption] = &[
+        Self::JsFlags,
+        Self::RemoteDebuggingPipe,
+        Self::RemoteDebuggingPort,
+        Self::WaitForDebuggerChildren,
+        Self::RemoteAllowOrigins,
+        Self::UnsafelyTreatInsecureOriginAsSecure,
+        Self::CrashServerUrl,
+        Self::CrashDumpsDir,
+        Self::FullMemoryCrashReport,
+        Self::DisableHangMonitor,
+        Self::DisableBreakpad,
+        Self::DisableHttpCache,
+        Self::DisableGpu,
+    ];
+
+    /// Returns every Electron option known by this library.
+    pub const fn all() -> &'static [ElectronOption] {
+        Self::ALL
+    }
+
+    /// Returns the name of the command line switch this option controls, such as `js-flags`.
+    pub fn switch(&self) -> &'static str {
+        let literal = self.search_pattern().literal;
+        std::str::from_utf8(&literal[1..]).expect("switch names are ASCII")
+    }
+
+    const fn available_since(&self) -> Option<u32> {
+        match self {
+            // Added in Chromium 111, which first shipped in Electron 24.
+            Self::RemoteAllowOrigins => Some(24),
+            _ => None,
+        }
+    }
+
+    const fn search_pattern(&self) -> SearchPattern {
+        match self {
+            Self::JsFlags => SearchPattern::c_str(b"\0js-flags"),
+            Self::RemoteDebuggingPipe => SearchPattern::c_str(b"\0remote-debugging-pipe"),
+            Self::RemoteDebuggingPort => SearchPattern::c_str(b"\0remote-debugging-port"),
+            Self::WaitForDebuggerChildren => SearchPattern::c_str(b"\0wait-for-debugger-children"),
+            Self::RemoteAllowOrigins => SearchPattern::c_str(b"\0remote-allow-origins"),
+            // This can also be embedded with a list of origins as its value.
+            Self::UnsafelyTreatInsecureOriginAsSecure => SearchPattern {
+                literal: b"\0unsafely-treat-insecure-origin-as-secure",
+                terminators: b"\0=",
+            },
+            // This can also be embedded with the URL as its value.
+            Self::CrashServerUrl => SearchPattern {
+                literal: b"\0crash-server-url",
+                terminators: b"\0=",
+            },
+            // This can also be embedded with the directory as its value.
+            Self::CrashDumpsDir => SearchPattern {
+                literal: b"\0crash-dumps-dir",
+                terminators: b"\0=",
+            },
+            Self::FullMemoryCrashReport => SearchPattern::c_str(b"\0full-memory-crash-report"),
+            Self::DisableHangMonitor => SearchPattern::c_str(b"\0disable-hang-monitor"),
+            Self::DisableBreakpad => SearchPattern::c_str(b"\0disable-breakpad"),
+            Self::DisableHttpCache => SearchPattern::c_str(b"\0disable-http-cache"),
+            Self::DisableGpu => SearchPattern::c_str(b"\0disable-gpu"),
+        }
+    }
+}
+
+/// Formats the option with the name of its command line switch, such as `js-flags`.
+impl fmt::Display for ElectronOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.switch())
+    }
+}
+
+/// Parses an option from the name of its command line switch, such as `js-flags`.
+impl FromStr for ElectronOption {
+    type Err = UnknownNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|opt| opt.switch() == s)
+            .ok_or_else(|| {
+                UnknownNameError::new(
+                    "Electron option",
+                    s,
+                    Self::ALL.iter().map(ElectronOption::switch).collect(),
+                )
+            })
+    }
+}
+
+impl From<ElectronOption> for PatchTarget {
+    fn from(opt: ElectronOption) -> Self {
+        Self::ElectronOption(opt)
+    }
+}
+
+#[cfg(feature = "patch")]
+impl Patchable for ElectronOption {}
+
+#[cfg(feature = "patch")]
+impl private::Sealed for ElectronOption {
+    fn target(&self) -> PatchTarget {
+        PatchTarget::from(*self)
+    }
+}
+
+/// List of known developer tool command line messages that can be
+/// written to stdout by Node.JS during debugging.
+///
+/// Use the equivalent [PatchTarget] variants if these still need to be patched.
+///
+/// ### Warning
+///
+/// Disabling these is a worst-case fallback protection against internal changes to the way
+/// that Chromium/Electron/Node.JS handle parsing command line arguments. If something is changed
+/// and a debugging flag slips through, modifying one of these will cause the application to trigger a segemntation fault
+/// and be terminated by the OS, exiting immediately.
+#[cfg(feature = "patch")]
+#[deprecated(
+    since = "0.2.2",
+    note = "This is no longer necessary due to the NodeCliInspect fuse's functionality."
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DevToolsMessage {
+    /// The message printed to standard out when Node.JS listens on TCP port.
+    ///
+    /// Ex: `Debugger listening on 127.0.0.1:9229/uuid`
+    Listening,
+    /// The message printed to standard out when Node.JS listens on a websocket.
+    ///
+    /// Ex: `Debugger listening on ws://127.0.0.1:9229/uuid`
+    ListeningWs,
+}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl From<DevToolsMessage> for PatchTarget {
+    fn from(msg: DevToolsMessage) -> Self {
+        match msg {
+            DevToolsMessage::Listening => Self::DebuggerListeningMessage,
+            DevToolsMessage::ListeningWs => Self::DevToolsListeningMessage,
+        }
+    }
+}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl Patchable for DevToolsMessage {}
+
+#[cfg(feature = "patch")]
+#[allow(deprecated)]
+impl private::Sealed for DevToolsMessage {
+    fn target(&self) -> PatchTarget {
+        PatchTarget::from(*self)
+    }
+
+    fn legacy_error(&self, e: PatcherError) -> PatcherError {
+        match e {
+            PatcherError::Binary(BinaryError::TargetNotPresent(_)) => {
+                BinaryError::MessageNotPresent(*self).into()
+            }
+            e => e,
+        }
+    }
+}
+
+/// The modifications [patch_option](ElectronApp::patch_option) would make, computed by
+/// [plan_option_patch](ElectronApp::plan_option_patch) without writing anything.
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PatchPlan {
+    /// The target that would be patched out.
+    pub target: PatchTarget,
+    /// Every modification that would be made, in order. This is empty if the target was already patched out.
+    pub changes: Vec<JournalEntry>,
+}
+
+#[cfg(feature = "patch")]
+impl PatchPlan {
+    /// Returns the ranges of the binary that would be rewritten, like [patch_option](ElectronApp::patch_option)
+    /// returns them.
+    pub fn ranges(&self) -> Vec<Range<usize>> {
+        self.changes
+            .iter()
+            .map(|change| change.offset..(change.offset + change.modified.len()))
+            .collect()
+    }
+}
+
+/// The result of patching an option in an application.
+#[cfg(feature = "patch")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum PatchOutcome {
+    /// The option was found in the binary and disabled.
+    Patched,
+    /// The option had already been patched out of the binary, by this library or electron-evil-feature-patcher.
+    AlreadyPatched,
+    /// The option couldn't be found in the binary.
+    NotPresent,
+    /// The option couldn't be found in the binary, but it isn't expected to exist in the detected version of Electron.
+    Unavailable,
+}
+
+/// An iterator that patches each of a sequence of targets in an application as it's advanced.
+///
+/// This is created by [patch_options_iter](ElectronApp::patch_options_iter).
+#[cfg(feature = "patch")]
+pub struct PatchOutcomes<'app, 'a, I> {
+    app: &'app mut ElectronApp<'a>,
+    targets: I,
+    version: Option<ElectronVersion>,
+}
+
+#[cfg(feature = "patch")]
+impl<I> Iterator for PatchOutcomes<'_, '_, I>
+where
+    I: Iterator,
+    I::Item: Into<PatchTarget>,
+{
+    type Item = Result<(PatchTarget, PatchOutcome), PatcherError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let target = self.targets.next()?.into();
+
+        let app = &mut *self.app;
+        let plan = app
+            .plan_targets(&[target])
+            .pop()
+            .expect("a result per target");
+        let outcome = match app.patch_planned(target, plan, &mut Vec::new(), self.version) {
+            Ok((outcome, _)) => outcome,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(Ok((target, outcome)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.targets.size_hint()
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Searches the application for a patch target without modifying it.
+    ///
+    /// Returns where the target was found and which of its patterns matched, or `None` if it isn't present.
+    pub fn scan<T: Into<PatchTarget>>(&self, target: T) -> Option<PatternMatch> {
+        #[allow(unused_mut)]
+        let mut found = target
+            .into()
+            .locate_within(&self.contents, self.search_scope(), None)?;
+        #[cfg(feature = "object-parser")]
+        {
+            found.section = self.section_name(found.range.start).map(String::from);
+        }
+        Some(found)
+    }
+
+    /// Determines whether a patch target is still present in the application, and which tool patched it out if it
+    /// isn't.
+    ///
+    /// See [PatchTarget::patch_state] for the limitations of this.
+    pub fn patch_state<T: Into<PatchTarget>>(&self, target: T) -> PatchState {
+        target.into().patch_state(&self.contents)
+    }
+
+    /// Returns `true` if a patch target is still present in the application, meaning it hasn't been patched out.
+    ///
+    /// This only searches for the target, so it's faster than checking its [state](ElectronApp::patch_state).
+    pub fn is_present<T: Into<PatchTarget>>(&self, target: T) -> bool {
+        self.scan(target).is_some()
+    }
+
+    /// Determines the [state](ElectronApp::patch_state) of every patch target known by this library, in
+    /// [identifier](PatchTarget::id) order.
+    ///
+    /// This searches the whole application several times for each target, so it's much slower than checking only
+    /// the targets of interest.
+    pub fn patch_states(&self) -> Vec<(PatchTarget, PatchState)> {
+        PatchTarget::ALL
+            .iter()
+            .map(|&target| (target, self.patch_state(target)))
+            .collect()
+    }
+
+    /// Restricts searches for patch targets to a range of the binary, such as the section containing Electron's
+    /// command line switches.
+    ///
+    /// Targets that can't be found inside the range are still searched for in the rest of the binary, so a wrong
+    /// hint only costs the time spent searching it. If a target appears more than once, the occurrence inside
+    /// the range is used. Ranges extending past the end of the binary are truncated.
+    pub fn set_search_hint(&mut self, range: Range<usize>) {
+        self.search_hint = Some(range);
+    }
+
+    /// Returns where patch targets are searched for in the application.
+    pub(crate) fn search_scope(&self) -> SearchScope<'_> {
+        SearchScope {
+            hint: self.search_hint.as_ref(),
+            #[cfg(feature = "object-parser")]
+            sections: self
+                .search_sections
+                .as_deref()
+                .filter(|_| self.restrict_search),
+            #[cfg(not(feature = "object-parser"))]
+            sections: None,
+        }
+    }
+
+    /// Returns the scopes that each copy of a patch target is searched for in, which is one for each
+    /// [architecture](Self::architecture_count).
+    #[cfg(feature = "patch")]
+    fn slice_scopes(&self) -> Vec<SearchScope<'_>> {
+        let scope = self.search_scope();
+        if self.slices.len() < 2 {
+            return vec![scope];
+        }
+
+        self.slices
+            .iter()
+            .map(|slice| {
+                let sections = match scope.sections {
+                    // The sections of each slice are listed together, after the ones of the slices before it.
+                    Some(all) => {
+                        let in_slice = |section: &Range<usize>| slice.contains(&section.start);
+                        let start = all.iter().position(in_slice).unwrap_or(all.len());
+                        let len = all[start..].iter().take_while(|s| in_slice(s)).count();
+                        &all[start..(start + len)]
+                    }
+                    None => std::slice::from_ref(slice),
+                };
+
+                SearchScope {
+                    sections: Some(sections),
+                    ..scope
+                }
+            })
+            .collect()
+    }
+
+    /// Locates a patch target in each architecture of the application, planning a patch for every copy found.
+    ///
+    /// An error is only returned for a missing target if it wasn't found in any of them.
+    #[cfg(feature = "patch")]
+    pub(crate) fn plan_target(
+        &self,
+        target: PatchTarget,
+    ) -> Result<Vec<PlannedPatch>, PatcherError> {
+        let mut patches = Vec::new();
+        let mut not_present = None;
+
+        for scope in self.slice_scopes() {
+            match target.plan(&self.contents, scope, self.cancel.as_ref(), self.patch_fill) {
+                Ok(mut patch) => {
+                    self.record_section(&mut patch);
+                    patches.push(patch)
+                }
+                Err(e @ PatcherError::Binary(BinaryError::ElectronOptionNotPresent(_)))
+                | Err(e @ PatcherError::Binary(BinaryError::TargetNotPresent(_))) => {
+                    not_present.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        match not_present {
+            Some(e) if patches.is_empty() => Err(e),
+            _ => Ok(patches),
+        }
+    }
+
+    /// Plans patches for several targets like [plan_target](Self::plan_target), but searches each architecture for
+    /// all of them in a single pass.
+    ///
+    /// If the search is cancelled, every target fails with [Cancelled](PatcherError::Cancelled).
+    #[cfg(feature = "patch")]
+    pub(crate) fn plan_targets(
+        &self,
+        targets: &[PatchTarget],
+    ) -> Vec<Result<Vec<PlannedPatch>, PatcherError>> {
+        let mut planned: Vec<Vec<PlannedPatch>> = vec![Vec::new(); targets.len()];
+
+        for scope in self.slice_scopes() {
+            let found =
+                PatchTarget::locate_many(targets, &self.contents, scope, self.cancel.as_ref());
+            for ((target, found), planned) in targets.iter().zip(found).zip(&mut planned) {
+                if let Some(found) = found {
+                    let mut patch = target.plan_at(&self.contents, found, self.patch_fill);
+                    self.record_section(&mut patch);
+                    planned.push(patch);
+                }
+            }
+        }
+
+        let cancelled = matches!(&self.cancel, Some(token) if token.is_cancelled());
+        targets
+            .iter()
+            .zip(planned)
+            .map(|(target, patches)| match patches {
+                _ if cancelled => Err(PatcherError::Cancelled),
+                patches if patches.is_empty() => Err(target.not_present().into()),
+                patches => Ok(patches),
+            })
+            .collect()
+    }
+
+    /// Records which section a planned patch's target was found in, for diagnostics.
+    #[cfg(feature = "patch")]
+    #[cfg_attr(not(feature = "object-parser"), allow(unused_variables))]
+    fn record_section(&self, patch: &mut PlannedPatch) {
+        #[cfg(feature = "object-parser")]
+        {
+            patch.found.section = self.section_name(patch.found.range.start).map(String::from);
+        }
+    }
+
+    /// Sets the byte that the rest of an Electron option's name is overwritten with when it's patched.
+    ///
+    /// Patched options always start with the same replacement prefix and end with a null byte, and everything in
+    /// between is set to `fill`. The default is `x`. Patches made with a space can't be told apart from
+    /// electron-evil-feature-patcher's by [patch_state](Self::patch_state). Patches made with a null byte are only
+    /// recognized if the next string starts right after the option, since null padding after it would make the
+    /// replacement look longer.
+    #[cfg(feature = "patch")]
+    pub fn set_patch_fill(&mut self, fill: u8) {
+        self.patch_fill = fill;
+    }
+
+    /// Sets how many bytes on each side of every patched range are recorded in a
+    /// [hardening report](crate::HardeningReport::contexts). The default is 16, and 0 disables capturing them.
+    #[cfg(feature = "patch")]
+    pub fn set_report_context(&mut self, bytes: usize) {
+        self.context_bytes = bytes;
+    }
+
+    /// Disables the ability to use this command line flag in the application.
+    ///
+    /// After being disabled, the flag will no longer be processed by the application. The removal
+    /// is a best-effort attempt. See the [crate documentation on effectiveness](crate).
+    ///
+    /// Returns the ranges of the binary that were rewritten, in order. Only the bytes that changed are included, and
+    /// there's one range for every copy of the option that was patched, such as one per architecture of a universal
+    /// binary. If the option was already patched out, by this library or electron-evil-feature-patcher, nothing is
+    /// rewritten and no ranges are returned. Patched options are only recognized by a replacement of exactly their
+    /// length, as described by [patch_state](PatchTarget::patch_state).
+    ///
+    /// # Errors
+    ///
+    /// This function returns a "not present" [BinaryError] if neither the option nor a patched version of it could be
+    /// found.
+    #[cfg(feature = "patch")]
+    pub fn patch_option<P: Patchable>(
+        &mut self,
+        to_disable: P,
+    ) -> Result<Vec<Range<usize>>, PatcherError> {
+        let plan = self.plan_option_patch(to_disable)?;
+        self.apply_patch_plan(&plan)
+    }
+
+    /// Computes what [patch_option](Self::patch_option) would do, without modifying the application.
+    ///
+    /// The plan holds every modification `patch_option` would make, with the offset, current bytes and replacement
+    /// bytes of each, so the changes can be reviewed before they're made. Calling `patch_option` afterwards makes
+    /// exactly these changes, as long as the application wasn't modified in between.
+    ///
+    /// ```
+    /// # use electron_hardener::{ElectronApp, patcher::ElectronOption};
+    /// # let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+    /// # bytes.extend_from_slice(include_bytes!("../examples/fake_electron_flags.bin"));
+    /// let mut app = ElectronApp::from_bytes(&mut bytes)?;
+    ///
+    /// let plan = app.plan_option_patch(ElectronOption::JsFlags)?;
+    /// assert!(!app.is_dirty());
+    /// assert_eq!(app.patch_option(ElectronOption::JsFlags)?, plan.ranges());
+    /// # Ok::<(), electron_hardener::PatcherError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [patch_option](Self::patch_option), except for
+    /// [SignatureInvalidated](PatcherError::SignatureInvalidated), which is only checked when the changes are made.
+    #[cfg(feature = "patch")]
+    pub fn plan_option_patch<P: Patchable>(
+        &self,
+        to_disable: P,
+    ) -> Result<PatchPlan, PatcherError> {
+        let target = to_disable.target();
+        let patches = match self
+            .plan_targets(&[target])
+            .pop()
+            .expect("a result per target")
+        {
+            Ok(patches) => patches,
+            Err(e) if is_not_present(&e) && self.is_patched_out(target) => Vec::new(),
+            Err(e) => return Err(to_disable.legacy_error(e)),
+        };
+        #[cfg(feature = "object-parser")]
+        self.check_code_patches(&patches)?;
+
+        Ok(self.patch_plan(target, patches))
+    }
+
+    /// Collects the changes planned patches for a target would make.
+    #[cfg(feature = "patch")]
+    pub(crate) fn patch_plan(&self, target: PatchTarget, patches: Vec<PlannedPatch>) -> PatchPlan {
+        let changes = patches
+            .into_iter()
+            .map(|patch| JournalEntry {
+                offset: patch.range.start,
+                original: self.contents[patch.range].to_vec(),
+                modified: patch.replacement,
+            })
+            .collect();
+
+        PatchPlan { target, changes }
+    }
+
+    /// Makes the changes of a patch plan, returning the ranges that were rewritten.
+    #[cfg(feature = "patch")]
+    fn apply_patch_plan(&mut self, plan: &PatchPlan) -> Result<Vec<Range<usize>>, PatcherError> {
+        if !plan.changes.is_empty() {
+            self.check_signature()?;
+        }
+
+        for change in &plan.changes {
+            self.check_write(
+                ModifiedItem::Target(plan.target),
+                change.offset,
+                &change.modified,
+            )?;
+        }
+        for change in &plan.changes {
+            self.write_bytes(
+                ModifiedItem::Target(plan.target),
+                change.offset,
+                &change.modified,
+            )?;
+        }
+
+        Ok(plan.ranges())
+    }
+
+    /// Disables every option in a list that can be found in the application, searching for all of them in a single
+    /// pass.
+    ///
+    /// This is a shorthand for [patch_all](Self::patch_all) that returns the options that were found, in the same
+    /// order, whether they were [patched](PatchOutcome::Patched) now or were [already patched](PatchOutcome::AlreadyPatched)
+    /// out before. Options that aren't in the application at all are skipped instead of being an error, which makes
+    /// this the simplest way to disable the same list of options in applications built with different Electron
+    /// versions.
+    ///
+    /// ```
+    /// # use electron_hardener::{ElectronApp, patcher::ElectronOption};
+    /// # let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+    /// # bytes.extend_from_slice(include_bytes!("../examples/fake_electron_flags.bin"));
+    /// let mut app = ElectronApp::from_bytes(&mut bytes)?;
+    ///
+    /// let found = app.patch_options(&[ElectronOption::JsFlags, ElectronOption::RemoteDebuggingPort])?;
+    /// assert_eq!(found, [ElectronOption::JsFlags, ElectronOption::RemoteDebuggingPort]);
+    /// # Ok::<(), electron_hardener::PatcherError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns the first error that isn't a missing option, such as
+    /// [Cancelled](PatcherError::Cancelled). Like with `patch_all`, every option is still attempted, so the options
+    /// that were patched before or after it stay patched.
+    #[cfg(feature = "patch")]
+    pub fn patch_options(
+        &mut self,
+        options: &[ElectronOption],
+    ) -> Result<Vec<ElectronOption>, PatcherError> {
+        let to_disable: Vec<&dyn Patchable> = options
+            .iter()
+            .map(|option| option as &dyn Patchable)
+            .collect();
+        let mut found = Vec::with_capacity(options.len());
+        let mut first_error = None;
+
+        for (&option, result) in options.iter().zip(self.patch_all(&to_disable)) {
+            match result {
+                Ok(_) => found.push(option),
+                Err(e) if is_not_present(&e) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(found),
+        }
+    }
+
+    /// Disables several options at once, searching the application for all of them in a single pass.
+    ///
+    /// Options are patched the same way as with [patch_option](Self::patch_option), but large binaries are only
+    /// searched once instead of once for each option. Every option is attempted even if some of them fail, and a
+    /// result is returned for each of them in the same order, holding the ranges or error `patch_option` would have
+    /// returned.
+    ///
+    /// The other ways of patching several targets make their changes the same way, and only differ in how they
+    /// report a target that can't be found: this returns the same "not present" error as `patch_option` unless the
+    /// target was already patched out, [patch_options](Self::patch_options) skips it,
+    /// [patch_options_iter](Self::patch_options_iter) reports it as a [PatchOutcome], and
+    /// [apply_items](Self::apply_items) reports it as an [ItemOutcome](crate::ItemOutcome).
+    ///
+    /// ```
+    /// # use electron_hardener::{ElectronApp, patcher::{ElectronOption, PatchTarget}};
+    /// # let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+    /// # bytes.extend_from_slice(include_bytes!("../examples/fake_electron_flags.bin"));
+    /// let mut app = ElectronApp::from_bytes(&mut bytes)?;
+    ///
+    /// let results = app.patch_all(&[&ElectronOption::JsFlags, &PatchTarget::NodeInspect]);
+    /// assert!(results.iter().all(Result::is_ok));
+    /// # Ok::<(), electron_hardener::PatcherError>(())
+    /// ```
+    #[cfg(feature = "patch")]
+    pub fn patch_all(
+        &mut self,
+        to_disable: &[&dyn Patchable],
+    ) -> Vec<Result<Vec<Range<usize>>, PatcherError>> {
+        let targets: Vec<PatchTarget> = to_disable.iter().map(|option| option.target()).collect();
+        let plans = self.plan_targets(&targets);
+        let mut written = Vec::new();
+
+        to_disable
+            .iter()
+            .zip(plans)
+            .map(|(option, plan)| {
+                let target = option.target();
+                match self.patch_planned(target, plan, &mut written, None) {
+                    Ok((PatchOutcome::Patched, ranges)) => Ok(ranges),
+                    Ok((PatchOutcome::AlreadyPatched, _)) => Ok(Vec::new()),
+                    Ok((PatchOutcome::NotPresent, _)) | Ok((PatchOutcome::Unavailable, _)) => {
+                        Err(option.legacy_error(target.not_present().into()))
+                    }
+                    Err(e) => Err(option.legacy_error(e)),
+                }
+            })
+            .collect()
+    }
+
+    /// Patches a target that was planned along with others by [plan_targets](Self::plan_targets), returning its
+    /// outcome and the ranges that were rewritten. Every way of patching several targets is built on this.
+    ///
+    /// `written` holds the ranges rewritten by the targets patched before it. A target that was found where one of
+    /// them was rewritten is searched for again, like it would be when patching them one at a time. A target that
+    /// can't be found is only [Unavailable](PatchOutcome::Unavailable) if the Electron `version` is known.
+    #[cfg(feature = "patch")]
+    pub(crate) fn patch_planned(
+        &mut self,
+        target: PatchTarget,
+        plan: Result<Vec<PlannedPatch>, PatcherError>,
+        written: &mut Vec<Range<usize>>,
+        version: Option<ElectronVersion>,
+    ) -> Result<(PatchOutcome, Vec<Range<usize>>), PatcherError> {
+        let overlaps = |patches: &[PlannedPatch]| {
+            patches.iter().any(|patch| {
+                written.iter().any(|range| {
+                    patch.found.range.start < range.end && range.start < patch.found.range.end
+                })
+            })
+        };
+        let plan = match plan {
+            Ok(patches) if overlaps(&patches) => self.plan_target(target),
+            plan => plan,
+        };
+
+        let patches = match plan {
+            Ok(patches) => patches,
+            Err(e) if is_not_present(&e) => {
+                let outcome = match version {
+                    _ if self.is_patched_out(target) => PatchOutcome::AlreadyPatched,
+                    Some(version) if !target.is_available_in(version) => PatchOutcome::Unavailable,
+                    _ => PatchOutcome::NotPresent,
+                };
+                return Ok((outcome, Vec::new()));
+            }
+            Err(e) => return Err(e),
+        };
+
+        #[cfg(feature = "object-parser")]
+        self.check_code_patches(&patches)?;
+        self.check_signature()?;
+        for patch in &patches {
+            self.check_write(
+                ModifiedItem::Target(patch.target),
+                patch.range.start,
+                &patch.replacement,
+            )?;
+        }
+        let first = written.len();
+        for patch in patches {
+            self.write_bytes(
+                ModifiedItem::Target(patch.target),
+                patch.range.start,
+                &patch.replacement,
+            )?;
+            written.push(patch.range);
+        }
+        Ok((PatchOutcome::Patched, written[first..].to_vec()))
+    }
+}
+
+#[cfg(feature = "patch")]
+impl<'a> ElectronApp<'a> {
+    /// Patches each of the targets in turn, returning an iterator that yields their [outcomes](PatchOutcome) as
+    /// they're processed.
+    ///
+    /// Targets are patched the same way as with [patch_all](Self::patch_all), but each of them is searched for
+    /// separately, when it's reached.
+    ///
+    /// Nothing is searched or patched until the iterator is advanced, so callers can report progress on large
+    /// binaries or stop early, such as on the first target that isn't present. Targets that were already yielded stay
+    /// patched if the iterator is dropped. Unlike [patch_option](Self::patch_option), a missing target isn't an
+    /// error: it's reported as [NotPresent](PatchOutcome::NotPresent), as [AlreadyPatched](PatchOutcome::AlreadyPatched)
+    /// if it was patched out earlier, or as [Unavailable](PatchOutcome::Unavailable) if the detected
+    /// [Electron version](Self::electron_version) isn't expected to contain it.
+    ///
+    /// # Errors
+    ///
+    /// The iterator yields an error if searching for a target failed, such as when the application's
+    /// [cancel token](crate::CancelToken) was cancelled. It can still be advanced afterwards.
+    pub fn patch_options_iter<I>(&mut self, targets: I) -> PatchOutcomes<'_, 'a, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: Into<PatchTarget>,
+    {
+        let version = self.electron_version();
+        PatchOutcomes {
+            app: self,
+            targets: targets.into_iter(),
+            version,
+        }
+    }
+
+    /// Returns `true` if a target that couldn't be found was patched out, by this library or
+    /// electron-evil-feature-patcher.
+    pub(crate) fn is_patched_out(&self, target: PatchTarget) -> bool {
+        matches!(
+            self.patch_state(target),
+            PatchState::PatchedByThisTool | PatchState::PatchedByEvilFeaturePatcher
+        )
+    }
+}
+
+/// Returns `true` if the error is that a patch target couldn't be found.
+#[cfg(feature = "patch")]
+pub(crate) fn is_not_present(e: &PatcherError) -> bool {
+    matches!(
+        e,
+        PatcherError::Binary(BinaryError::ElectronOptionNotPresent(_))
+            | PatcherError::Binary(BinaryError::TargetNotPresent(_))
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+    /// The same flags laid out like in Windows builds of Electron 13.
+    const WINDOWS_TEST_DATA: &[u8] = include_bytes!("../examples/fake_electron_flags_windows.bin");
+
+    /// An application made of the fuse fixture followed by `flags`.
+    pub(crate) fn app_bytes(flags: &[u8]) -> Vec<u8> {
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        bytes.extend_from_slice(flags);
+        bytes
+    }
+
+    /// An application made of the fuse and flag fixtures, shared by the tests of every module.
+    pub(crate) fn test_app_bytes() -> Vec<u8> {
+        app_bytes(TEST_DATA)
+    }
+
+    const NODE_FLAGS: &[PatchTarget] = &[
+        PatchTarget::NodeInspect,
+        PatchTarget::NodeInspectBrk,
+        PatchTarget::NodeInspectPort,
+        PatchTarget::NodeDebug,
+        PatchTarget::NodeDebugBrk,
+        PatchTarget::NodeDebugPort,
+        PatchTarget::NodeInspectBrkNode,
+        PatchTarget::NodeInspectPublishUid,
+        PatchTarget::NodeInspectWait,
+    ];
+
+    const MESSAGES: &[PatchTarget] = &[
+        PatchTarget::DevToolsListeningMessage,
+        PatchTarget::DebuggerListeningMessage,
+    ];
+
+    #[test]
+    fn option_names_round_trip() {
+        assert_eq!(
+            ElectronOption::all().to_vec(),
+            ElectronOption::into_enum_iter().collect::<Vec<_>>()
+        );
+
+        for &opt in ElectronOption::all() {
+            assert_eq!(opt.to_string().parse::<ElectronOption>(), Ok(opt));
+        }
+        assert_eq!(
+            "remote-debugging-port".parse::<ElectronOption>(),
+            Ok(ElectronOption::RemoteDebuggingPort)
+        );
+        assert!("--js-flags".parse::<ElectronOption>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn disabling_nodejs_flags_works() {
+        for (layout, expected_pattern) in &[
+            (TEST_DATA, PatternId::Canonical),
+            (WINDOWS_TEST_DATA, PatternId::Fallback),
+        ] {
+            let mut data = layout.to_vec();
+
+            // Remove all the flags supported.
+            for flag in NODE_FLAGS {
+                let found = flag.locate(&data).unwrap();
+                assert_eq!(found.matched_pattern, *expected_pattern, "{:?}", flag);
+                flag.disable(&mut data).unwrap();
+            }
+
+            // Ensure they no longer exist
+            for flag in NODE_FLAGS.iter().copied() {
+                assert_eq!(
+                    flag.disable(&mut data),
+                    Err(PatcherError::Binary(BinaryError::TargetNotPresent(flag)))
+                );
+                assert_eq!(flag.patch_state(&data), PatchState::PatchedByThisTool);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn disabling_electron_options_works() {
+        let mut data = TEST_DATA.to_vec();
+
+        // Remove all the options supported.
+        for opt in ElectronOption::into_enum_iter() {
+            PatchTarget::from(opt).disable(&mut data).unwrap();
+        }
+
+        // Switches that only share a prefix with an option are left alone.
+        for similar in [
+            &b"\0disable-hang-monitor-for-testing\0"[..],
+            &b"\0enable-breakpad\0"[..],
+            &b"\0disable-gpu-sandbox\0"[..],
+            &b"\0disable-http2\0"[..],
+        ] {
+            assert!(memmem::find(&data, similar).is_some());
+        }
+
+        // Ensure they no longer exist
+        for opt in ElectronOption::into_enum_iter() {
+            assert_eq!(
+                PatchTarget::from(opt).disable(&mut data),
+                Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(
+                    opt
+                )))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn disabling_debugging_messages_works() {
+        let mut data = TEST_DATA.to_vec();
+
+        // Remove all the options supported.
+        for msg in MESSAGES {
+            msg.disable(&mut data).unwrap();
+        }
+
+        // Ensure they no longer exist
+        for msg in MESSAGES.iter().copied() {
+            assert_eq!(
+                msg.disable(&mut data),
+                Err(PatcherError::Binary(BinaryError::TargetNotPresent(msg)))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn electron_option_with_value_is_disabled() {
+        let mut data =
+            b"\0\0unsafely-treat-insecure-origin-as-secure=http://a.test,http://b.test\0\0"
+                .to_vec();
+        let target = PatchTarget::from(ElectronOption::UnsafelyTreatInsecureOriginAsSecure);
+
+        target.disable(&mut data).unwrap();
+
+        assert_eq!(
+            target.disable(&mut data),
+            Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(
+                ElectronOption::UnsafelyTreatInsecureOriginAsSecure
+            )))
+        );
+        assert!(!data
+            .windows(b"unsafely-treat-insecure-origin-as-secure".len())
+            .any(|w| w == b"unsafely-treat-insecure-origin-as-secure"));
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn planned_patch_only_covers_changed_bytes() {
+        let data = b"\0--inspect\0--inspect-brk\0";
+
+        let inspect = PatchTarget::NodeInspect
+            .plan(data, SearchScope::default(), None, DEFAULT_PATCH_FILL)
+            .unwrap();
+        let inspect_brk = PatchTarget::NodeInspectBrk
+            .plan(data, SearchScope::default(), None, DEFAULT_PATCH_FILL)
+            .unwrap();
+
+        assert_eq!(inspect.range, 1..3);
+        assert_eq!(inspect_brk.range, 11..21);
+        assert!(PlannedPatch::check_overlaps(&[inspect, inspect_brk]).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn overlapping_patches_are_rejected() {
+        let first = PatchTarget::from(ElectronOption::JsFlags)
+            .plan(TEST_DATA, SearchScope::default(), None, DEFAULT_PATCH_FILL)
+            .unwrap();
+        let second = PlannedPatch {
+            target: PatchTarget::NodeDebug,
+            found: first.found.clone(),
+            range: (first.range.end - 1)..(first.range.end + 2),
+            replacement: vec![0; 3],
+        };
+
+        assert_eq!(
+            PlannedPatch::check_overlaps(&[second.clone(), first.clone()]),
+            Err(PatcherError::OverlappingPatches {
+                first: (first.target, first.range),
+                second: (second.target, second.range),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn crash_server_url_with_value_is_disabled() {
+        let mut data = b"\0\0crash-server-url=https://crash.example.com/submit\0\0".to_vec();
+        let target = PatchTarget::from(ElectronOption::CrashServerUrl);
+
+        target.disable(&mut data).unwrap();
+
+        assert_eq!(
+            target.disable(&mut data),
+            Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(
+                ElectronOption::CrashServerUrl
+            )))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn crash_dumps_dir_with_value_is_disabled() {
+        let mut data = b"\0\0crash-dumps-dir=/tmp/dumps\0full-memory-crash-report\0\0".to_vec();
+
+        for opt in [
+            ElectronOption::CrashDumpsDir,
+            ElectronOption::FullMemoryCrashReport,
+        ] {
+            let target = PatchTarget::from(opt);
+            target.disable(&mut data).unwrap();
+            assert_eq!(
+                target.disable(&mut data),
+                Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(
+                    opt
+                )))
+            );
+        }
+
+        // The directory is left behind, but is no longer part of a switch.
+        assert_eq!(&data[..29], b"\0\0xx\r\nxxxxxxxxxxx\0/tmp/dumps\0".as_ref());
+    }
+
+    #[test]
+    fn all_targets_are_found() {
+        for target in PatchTarget::ALL {
+            let found = target.locate(TEST_DATA).unwrap();
+            assert_eq!(found.matched_pattern, PatternId::Canonical);
+
+            // Only the Node.JS flags are laid out differently on Windows.
+            let expected = if target.fallback_pattern().is_some() {
+                PatternId::Fallback
+            } else {
+                PatternId::Canonical
+            };
+            let found = target.locate(WINDOWS_TEST_DATA).unwrap();
+            assert_eq!(found.matched_pattern, expected, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn patch_state_identifies_the_patching_tool() {
+        let evil_patched = include_bytes!("../examples/fake_electron_flags_evil_patched.bin");
+
+        for target in PatchTarget::ALL {
+            assert_eq!(target.patch_state(TEST_DATA), PatchState::Unpatched);
+            assert_eq!(
+                target.patch_state(evil_patched),
+                PatchState::PatchedByEvilFeaturePatcher
+            );
+            assert_eq!(target.patch_state(b"\0unrelated\0"), PatchState::Absent);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patch_state_recognizes_own_patches() {
+        let mut data = TEST_DATA.to_vec();
+
+        for target in PatchTarget::ALL {
+            target.disable(&mut data).unwrap();
+        }
+
+        for target in PatchTarget::ALL {
+            assert_eq!(target.patch_state(&data), PatchState::PatchedByThisTool);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn shorter_options_are_not_found_in_longer_patches() {
+        let shorter = [
+            ElectronOption::JsFlags,
+            ElectronOption::RemoteAllowOrigins,
+            ElectronOption::DisableGpu,
+        ];
+
+        for &fill in &[DEFAULT_PATCH_FILL, b'#'] {
+            for flags in [
+                &b"\0remote-debugging-port\0type\0"[..],
+                b"\0remote-debugging-port\0\0\0\0",
+            ] {
+                let mut bytes = app_bytes(flags);
+                let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+                app.set_patch_fill(fill);
+                app.patch_option(ElectronOption::RemoteDebuggingPort)
+                    .unwrap();
+
+                let states = app.patch_states();
+                for option in shorter {
+                    assert!(
+                        states.contains(&(option.into(), PatchState::Absent)),
+                        "{:?} with {:?}",
+                        option,
+                        fill
+                    );
+                }
+            }
+        }
+
+        // A replacement of exactly the option's length is still recognized.
+        let mut bytes = app_bytes(b"\0remote-debugging-port\0type\0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.patch_option(ElectronOption::RemoteDebuggingPort)
+            .unwrap();
+        assert_eq!(
+            app.patch_state(ElectronOption::RemoteDebuggingPort),
+            PatchState::PatchedByThisTool
+        );
+    }
+
+    #[test]
+    fn terminators_are_required() {
+        let data = b"\0--inspect-brk-node\0\0crash-server-url=http://x\0";
+
+        // `--inspect-brk` is only a prefix of the flag that's present.
+        assert_eq!(PatchTarget::NodeInspectBrk.locate(data), None);
+        assert_eq!(
+            PatchTarget::NodeInspectBrkNode.locate(data).unwrap().range,
+            0..20
+        );
+        assert_eq!(
+            PatchTarget::from(ElectronOption::CrashServerUrl)
+                .locate(data)
+                .unwrap()
+                .range,
+            20..38
+        );
+    }
+
+    #[test]
+    fn matches_across_search_chunks_are_found() {
+        let mut data = vec![0; SearchPattern::CHUNK_LEN - 4];
+        data.extend_from_slice(b"--inspect\0");
+
+        assert_eq!(
+            PatchTarget::NodeInspect.locate(&data).unwrap().range,
+            (SearchPattern::CHUNK_LEN - 5)..(data.len())
+        );
+    }
+
+    #[test]
+    fn matched_pattern_is_reported() {
+        let data = b"\0\0--inspect-port\0\xAA--inspect\0\0";
+
+        assert_eq!(
+            PatchTarget::NodeInspectPort.locate(data),
+            Some(PatternMatch {
+                matched_pattern: PatternId::Canonical,
+                range: 1..17,
+                section: None,
+            })
+        );
+        assert_eq!(
+            PatchTarget::NodeInspect.locate(data),
+            Some(PatternMatch {
+                matched_pattern: PatternId::Fallback,
+                range: 17..28,
+                section: None,
+            })
+        );
+        assert_eq!(PatchTarget::NodeDebug.locate(data), None);
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn targets_are_located_together() {
+        let mut data = vec![0; SearchPattern::CHUNK_LEN - 4];
+        data.extend_from_slice(b"--inspect\0\0--inspect-brk-node\0\0--inspect-port\0");
+        data.extend_from_slice(TEST_DATA);
+
+        let found = PatchTarget::locate_many(PatchTarget::ALL, &data, SearchScope::default(), None);
+        for (target, found) in PatchTarget::ALL.iter().zip(found) {
+            assert_eq!(found, target.locate(&data), "{:?}", target);
+        }
+
+        let data = b"\0\0--inspect-port\0\xAA--inspect\0\0";
+        let targets = &[PatchTarget::NodeInspect, PatchTarget::NodeInspectBrk];
+        assert_eq!(
+            PatchTarget::locate_many(targets, data, SearchScope::default(), None),
+            vec![PatchTarget::NodeInspect.locate(data), None]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patching_all_at_once_matches_patching_one_at_a_time() {
+        let options: Vec<&dyn Patchable> = PatchTarget::ALL
+            .iter()
+            .map(|target| target as &dyn Patchable)
+            .collect();
+
+        let mut expected = app_bytes(TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut expected).unwrap();
+        for target in PatchTarget::ALL {
+            app.patch_option(*target).unwrap();
+        }
+
+        let mut bytes = app_bytes(TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert!(app.patch_all(&options).iter().all(Result::is_ok));
+        assert!(bytes == expected);
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn missing_options_are_reported_by_patch_all() {
+        let mut bytes = app_bytes(TEST_DATA);
+        let erased = PatchTarget::NodeInspectBrk.locate(&bytes).unwrap().range;
+        for byte in &mut bytes[erased] {
+            *byte = 0;
+        }
+        // Only the dashes of the flag are rewritten.
+        let inspect = PatchTarget::NodeInspect.locate(&bytes).unwrap().range.start + 1;
+        let inspect = inspect..inspect + 2;
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+
+        // Options that were already patched out are reported as succeeding without rewriting anything.
+        assert_eq!(
+            app.patch_all(&[
+                &ElectronOption::JsFlags,
+                &PatchTarget::NodeInspect,
+                &PatchTarget::NodeInspectBrk,
+            ]),
+            vec![
+                Ok(Vec::new()),
+                Ok(vec![inspect]),
+                Err(BinaryError::TargetNotPresent(PatchTarget::NodeInspectBrk).into()),
+            ]
+        );
+
+        let token = CancelToken::new();
+        app.set_cancel_token(token.clone());
+        token.cancel();
+        assert_eq!(
+            app.patch_all(&[&ElectronOption::RemoteDebuggingPort]),
+            vec![Err(PatcherError::Cancelled)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn batch_entry_points_agree() {
+        use crate::{ItemOutcome, PatchItem};
+
+        let mut original = app_bytes(TEST_DATA);
+        let erased = PatchTarget::ElectronOption(ElectronOption::CrashServerUrl)
+            .locate(&original)
+            .unwrap()
+            .range;
+        for byte in &mut original[erased] {
+            *byte = 0;
+        }
+        ElectronApp::from_bytes(&mut original)
+            .unwrap()
+            .patch_option(ElectronOption::JsFlags)
+            .unwrap();
+
+        // Already patched, patched now, and not present.
+        let options = [
+            ElectronOption::JsFlags,
+            ElectronOption::RemoteDebuggingPort,
+            ElectronOption::CrashServerUrl,
+        ];
+        let expected = [
+            PatchOutcome::AlreadyPatched,
+            PatchOutcome::Patched,
+            PatchOutcome::NotPresent,
+        ];
+
+        let mut all = original.clone();
+        let to_disable: Vec<&dyn Patchable> = options.iter().map(|o| o as &dyn Patchable).collect();
+        let results = ElectronApp::from_bytes(&mut all)
+            .unwrap()
+            .patch_all(&to_disable);
+        assert!(matches!(&results[0], Ok(ranges) if ranges.is_empty()));
+        assert!(matches!(&results[1], Ok(ranges) if !ranges.is_empty()));
+        assert_eq!(
+            results[2],
+            Err(BinaryError::ElectronOptionNotPresent(ElectronOption::CrashServerUrl).into())
+        );
+
+        let mut found = original.clone();
+        assert_eq!(
+            ElectronApp::from_bytes(&mut found)
+                .unwrap()
+                .patch_options(&options),
+            Ok(options[..2].to_vec())
+        );
+
+        let mut iterated = original.clone();
+        let outcomes: Vec<PatchOutcome> = ElectronApp::from_bytes(&mut iterated)
+            .unwrap()
+            .patch_options_iter(options.iter().copied())
+            .map(|result| result.unwrap().1)
+            .collect();
+        assert_eq!(outcomes, expected);
+
+        let mut items = original.clone();
+        let item_list: Vec<PatchItem> = options.iter().map(|&o| o.into()).collect();
+        let outcomes: Vec<ItemOutcome> = ElectronApp::from_bytes(&mut items)
+            .unwrap()
+            .apply_items(&item_list)
+            .into_iter()
+            .map(|(_, outcome)| outcome.unwrap())
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ItemOutcome::AlreadyApplied,
+                ItemOutcome::Applied,
+                ItemOutcome::NotPresent
+            ]
+        );
+
+        assert!(all == found && found == iterated && iterated == items);
+        assert!(all != original);
+    }
+
+    /// The length of the fuse fixture that [app_bytes] prepends to the flags.
+    #[cfg(feature = "patch")]
+    const FUSE_LEN: usize = include_bytes!("../examples/fake_electron_fuses.bin").len();
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn search_hint_is_preferred() {
+        let mut bytes = app_bytes(b"\0js-flags\0\0\0js-flags\0");
+        let second = (bytes.len() - 10)..bytes.len();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        app.set_search_hint(second.clone());
+        let found = app.scan(ElectronOption::JsFlags).unwrap();
+        assert_eq!(found.range, second);
+
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+        assert_eq!(&bytes[second.start + 1..second.start + 3], b"xx");
+        assert_eq!(&bytes[second.start - 11..second.start - 1], b"\0js-flags\0");
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patched_options_are_unrecoverable() {
+        for &fill in &[DEFAULT_PATCH_FILL, b'#'] {
+            for opt in ElectronOption::into_enum_iter() {
+                let mut bytes = app_bytes(TEST_DATA);
+                let found = PatchTarget::from(opt).locate(&bytes).unwrap().range;
+                let name = &opt.search_pattern().literal[1..];
+
+                let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+                app.set_patch_fill(fill);
+                app.patch_option(opt).unwrap();
+
+                let patched = &bytes[found.clone()];
+                let mut expected = b"\0xx\r\n".to_vec();
+                expected.resize(found.len() - 1, fill);
+                expected.push(0);
+                assert_eq!(patched, expected.as_slice(), "{:?}", opt);
+
+                // Not even the end of the name can be recovered from the patched bytes.
+                for len in 2..=name.len() {
+                    let remnant = &name[(name.len() - len)..];
+                    assert!(
+                        memmem::find(patched, remnant).is_none(),
+                        "{:?} left {:?} behind",
+                        opt,
+                        String::from_utf8_lossy(remnant)
+                    );
+                }
+
+                assert_eq!(
+                    PatchTarget::from(opt).patch_state(&bytes),
+                    PatchState::PatchedByThisTool
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patching_twice_succeeds() {
+        for layout in &[TEST_DATA, WINDOWS_TEST_DATA] {
+            let mut bytes = app_bytes(layout);
+            let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+            for &target in PatchTarget::ALL {
+                assert!(
+                    !app.patch_option(target).unwrap().is_empty(),
+                    "{:?}",
+                    target
+                );
+            }
+            for &target in PatchTarget::ALL {
+                assert_eq!(app.patch_option(target), Ok(Vec::new()), "{:?}", target);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn absent_options_stay_absent_next_to_patched_ones() {
+        for flags in [
+            &b"\0remote-debugging-port\0type\0"[..],
+            b"\0remote-debugging-port\0\0\0\0",
+        ] {
+            let mut bytes = app_bytes(flags);
+            let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+            app.patch_option(ElectronOption::RemoteDebuggingPort)
+                .unwrap();
+
+            for option in [ElectronOption::JsFlags, ElectronOption::RemoteAllowOrigins] {
+                assert_eq!(
+                    app.patch_option(option),
+                    Err(BinaryError::ElectronOptionNotPresent(option).into())
+                );
+            }
+
+            let outcomes: Vec<_> = app
+                .patch_options_iter([ElectronOption::JsFlags, ElectronOption::DisableGpu])
+                .collect();
+            assert_eq!(
+                outcomes,
+                vec![
+                    Ok((ElectronOption::JsFlags.into(), PatchOutcome::NotPresent)),
+                    Ok((ElectronOption::DisableGpu.into(), PatchOutcome::NotPresent)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patch_outcomes_are_streamed() {
+        let mut bytes = app_bytes(TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let targets = [
+            ElectronOption::JsFlags,
+            ElectronOption::JsFlags,
+            ElectronOption::RemoteDebuggingPort,
+        ];
+        let mut outcomes = app.patch_options_iter(targets.iter().copied());
+        assert_eq!(outcomes.size_hint(), (3, Some(3)));
+
+        assert_eq!(
+            outcomes.next(),
+            Some(Ok((ElectronOption::JsFlags.into(), PatchOutcome::Patched)))
+        );
+        assert_eq!(
+            outcomes.next(),
+            Some(Ok((
+                ElectronOption::JsFlags.into(),
+                PatchOutcome::AlreadyPatched
+            )))
+        );
+
+        // Stopping early leaves the remaining targets untouched.
+        assert_eq!(app.scan(ElectronOption::JsFlags), None);
+        assert!(app.scan(ElectronOption::RemoteDebuggingPort).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn wrong_search_hint_falls_back_to_full_scan() {
+        let mut bytes = app_bytes(TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let expected = app.scan(ElectronOption::RemoteDebuggingPort).unwrap();
+
+        // The hint only covers the fuse wire.
+        app.set_search_hint(0..64);
+        assert_eq!(
+            app.scan(ElectronOption::RemoteDebuggingPort),
+            Some(expected)
+        );
+        app.patch_option(ElectronOption::RemoteDebuggingPort)
+            .unwrap();
+        assert_eq!(app.scan(ElectronOption::RemoteDebuggingPort), None);
+
+        // Hints outside of the binary are ignored.
+        app.set_search_hint(usize::MAX - 1..usize::MAX);
+        app.patch_option(PatchTarget::NodeInspectPort).unwrap();
+    }
+
+    #[test]
+    fn target_ids_are_stable() {
+        // These must never change. New targets should be added to the end.
+        let pinned = [
+            (ElectronOption::JsFlags.into(), 0),
+            (ElectronOption::RemoteDebuggingPipe.into(), 1),
+            (ElectronOption::RemoteDebuggingPort.into(), 2),
+            (ElectronOption::WaitForDebuggerChildren.into(), 3),
+            (PatchTarget::NodeInspect, 4),
+            (PatchTarget::NodeInspectBrk, 5),
+            (PatchTarget::NodeInspectPort, 6),
+            (PatchTarget::NodeDebug, 7),
+            (PatchTarget::NodeDebugBrk, 8),
+            (PatchTarget::NodeDebugPort, 9),
+            (PatchTarget::NodeInspectBrkNode, 10),
+            (PatchTarget::NodeInspectPublishUid, 11),
+            (PatchTarget::DebuggerListeningMessage, 12),
+            (PatchTarget::DevToolsListeningMessage, 13),
+            (ElectronOption::RemoteAllowOrigins.into(), 14),
+            (PatchTarget::NodeInspectWait, 15),
+            (
+                ElectronOption::UnsafelyTreatInsecureOriginAsSecure.into(),
+                16,
+            ),
+            (ElectronOption::CrashServerUrl.into(), 17),
+            (ElectronOption::CrashDumpsDir.into(), 18),
+            (ElectronOption::FullMemoryCrashReport.into(), 19),
+            (ElectronOption::DisableHangMonitor.into(), 20),
+            (ElectronOption::DisableBreakpad.into(), 21),
+            (ElectronOption::DisableHttpCache.into(), 22),
+            (ElectronOption::DisableGpu.into(), 23),
+        ];
+
+        assert_eq!(pinned.len(), PatchTarget::ALL.len());
+        for (target, id) in pinned.iter().copied() {
+            assert_eq!(target.id(), id);
+            assert_eq!(PatchTarget::from_id(id), Some(target));
+        }
+        assert_eq!(PatchTarget::from_id(pinned.len() as u16), None);
+
+        // Every target must be listed.
+        for opt in ElectronOption::into_enum_iter() {
+            assert!(PatchTarget::ALL.contains(&opt.into()));
+        }
+        for target in NODE_FLAGS.iter().chain(MESSAGES) {
+            assert!(PatchTarget::ALL.contains(target));
+        }
+    }
+
+    #[test]
+    fn availability_is_version_gated() {
+        let old = ElectronVersion::new(20, 3, 0);
+        let new = ElectronVersion::new(33, 0, 0);
+
+        let target = PatchTarget::NodeInspectWait;
+        assert!(!target.is_available_in(old));
+        assert!(target.is_available_in(new));
+
+        let target = PatchTarget::from(ElectronOption::RemoteAllowOrigins);
+        assert!(!target.is_available_in(old));
+        assert!(target.is_available_in(new));
+
+        let target = PatchTarget::from(ElectronOption::RemoteDebuggingPort);
+        assert!(target.is_available_in(old));
+        assert!(target.is_available_in(new));
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    #[allow(deprecated)]
+    fn deprecated_types_keep_their_errors() {
+        let mut data = TEST_DATA.to_vec();
+
+        let flag = NodeJsCommandLineFlag::DebugPort;
+        flag.disable(&mut data).unwrap();
+        assert_eq!(
+            flag.disable(&mut data),
+            Err(PatcherError::Binary(BinaryError::NodeJsFlagNotPresent(
+                flag
+            )))
+        );
+
+        let msg = DevToolsMessage::Listening;
+        msg.disable(&mut data).unwrap();
+        assert_eq!(
+            msg.disable(&mut data),
+            Err(PatcherError::Binary(BinaryError::MessageNotPresent(msg)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn patched_ranges_are_returned() {
+        // Only the dashes of Node.js flags are rewritten, wherever the flag was found.
+        let mut data = TEST_DATA.to_vec();
+        assert_eq!(
+            PatchTarget::NodeInspect.disable(&mut data),
+            Ok(0x13a..0x13c)
+        );
+        let mut data = WINDOWS_TEST_DATA.to_vec();
+        assert_eq!(
+            PatchTarget::NodeInspect.disable(&mut data),
+            Ok(0x13b..0x13d)
+        );
+
+        let mut bytes = app_bytes(TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let js_flags = FUSE_LEN + 0x2d0..FUSE_LEN + 0x2d8;
+        assert_eq!(
+            app.patch_option(ElectronOption::JsFlags),
+            Ok(vec![js_flags])
+        );
+
+        let mut bytes =
+            crate::fat::tests::fat_binary(&[&app_bytes(TEST_DATA), &app_bytes(TEST_DATA)]);
+        let slices = crate::fat::slices(&bytes).unwrap();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let ranges = app.patch_option(ElectronOption::JsFlags).unwrap();
+        assert_eq!(ranges.len(), slices.len());
+        for (range, slice) in ranges.iter().zip(&slices) {
+            let offset = slice.start + FUSE_LEN + 0x2d0;
+            assert_eq!(*range, offset..offset + 8);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn returned_ranges_cover_every_changed_byte() {
+        let targets: [PatchTarget; 3] = [
+            PatchTarget::DevToolsListeningMessage,
+            ElectronOption::RemoteDebuggingPort.into(),
+            PatchTarget::NodeInspect,
+        ];
+
+        for target in targets {
+            let original = app_bytes(WINDOWS_TEST_DATA);
+            let mut bytes = original.clone();
+            let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+            let found = app.scan(target).unwrap().range;
+
+            let ranges = app.patch_option(target).unwrap();
+            assert_eq!(ranges.len(), 1);
+            let range = ranges[0].clone();
+            assert!(found.start <= range.start && range.end <= found.end);
+
+            let changed: Vec<usize> = (0..bytes.len())
+                .filter(|&i| bytes[i] != original[i])
+                .collect();
+            assert_eq!(changed.first(), Some(&range.start), "{}", target);
+            assert_eq!(changed.last(), Some(&(range.end - 1)), "{}", target);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn option_patches_are_planned() {
+        let mut bytes = app_bytes(WINDOWS_TEST_DATA);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let plan = app.plan_option_patch(PatchTarget::NodeInspect).unwrap();
+        let inspect = FUSE_LEN + 0x13b;
+        assert_eq!(plan.ranges(), vec![inspect..inspect + 2]);
+        assert_eq!(plan.changes[0].original, b"--");
+        assert!(!app.is_dirty());
+
+        app.start_undo_journal();
+        assert_eq!(
+            app.patch_option(PatchTarget::NodeInspect),
+            Ok(plan.ranges())
+        );
+        assert_eq!(
+            app.take_undo_journal().unwrap().entries(),
+            &plan.changes[..]
+        );
+
+        // Already patched targets plan nothing, and missing ones fail like they would when patched.
+        let plan = app.plan_option_patch(PatchTarget::NodeInspect).unwrap();
+        assert!(plan.changes.is_empty());
+        drop(app);
+        let app = ElectronApp::from_bytes(&mut bytes[..FUSE_LEN]).unwrap();
+        assert_eq!(
+            app.plan_option_patch(ElectronOption::JsFlags),
+            Err(BinaryError::ElectronOptionNotPresent(ElectronOption::JsFlags).into())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn options_are_patched_in_one_pass() {
+        let mut bytes = app_bytes(TEST_DATA);
+        let mut one_at_a_time = bytes.clone();
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let found = app.patch_options(ElectronOption::ALL).unwrap();
+        assert!(!found.is_empty());
+        assert_eq!(app.patch_options(ElectronOption::ALL), Ok(found.clone()));
+
+        let mut app = ElectronApp::from_bytes(&mut one_at_a_time).unwrap();
+        let expected: Vec<ElectronOption> = ElectronOption::ALL
+            .iter()
+            .copied()
+            .filter(|&option| app.patch_option(option).is_ok())
+            .collect();
+        assert_eq!(found, expected);
+        assert!(bytes == one_at_a_time, "patching in one pass differed");
+
+        // Missing options aren't errors.
+        let mut bytes = include_bytes!("../examples/fake_electron_fuses.bin").to_vec();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(app.patch_options(ElectronOption::ALL), Ok(Vec::new()));
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn options_are_patched_in_every_architecture() {
+        let slice = test_app_bytes();
+        let mut bytes = crate::fat::tests::fat_binary(&[&slice, &slice]);
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+
+        for range in crate::fat::slices(&bytes).unwrap() {
+            let slice = ElectronApp::from_bytes_readonly(&bytes[range]).unwrap();
+            assert!(!slice.is_present(ElectronOption::JsFlags));
+            assert!(slice.is_present(PatchTarget::NodeInspect));
         }
     }
 }
@@ -0,0 +1,2174 @@
+//! Hardening policies that describe a full set of modifications to apply to an application at once.
+
+use crate::{
+    fuses::{FuseStatus, FuseWire},
+    journal::ModifiedItem,
+    patcher::{ElectronOption, PatchOutcome, PatchState, PatchTarget, PlannedPatch},
+    AppLayout, BinaryError, BufferPool, ElectronApp, Fuse, HardeningReport, HardeningWarning,
+    PatchContext, PatcherError, PolicyError, SourceError,
+};
+use std::{
+    convert::TryFrom,
+    ffi::OsString,
+    fmt, fs,
+    io::{self, Read},
+    num::NonZeroUsize,
+    ops::Range,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// A set of fuse changes and option patches to apply to an [application](ElectronApp).
+///
+/// Policies are applied with [apply_policy](ElectronApp::apply_policy) and produce a [report](HardeningReport)
+/// describing what happened.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HardeningPolicy {
+    fuses: Vec<(Fuse, bool)>,
+    targets: Vec<(PatchTarget, Severity)>,
+    expectations: Expectations,
+    warnings: Vec<PolicyWarning>,
+}
+
+/// A harmless mistake found when a [policy](HardeningPolicy) was [built](HardeningPolicy::build).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum PolicyWarning {
+    /// The fuse was set to the same value more than once. Only the first change is kept.
+    DuplicateFuse(Fuse),
+    /// The target was patched with the same severity more than once. Only the first patch is kept.
+    DuplicateTarget(PatchTarget),
+    /// The target is a Node.JS debugging flag or message, which the policy already prevents from being used by
+    /// disabling the [NodeCliInspect](Fuse::NodeCliInspect) fuse.
+    SupersededTarget(PatchTarget),
+}
+
+impl fmt::Display for PolicyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyWarning::DuplicateFuse(fuse) => {
+                write!(f, "The {} fuse is set more than once", fuse)
+            }
+            PolicyWarning::DuplicateTarget(target) => {
+                write!(f, "The {} target is patched more than once", target)
+            }
+            PolicyWarning::SupersededTarget(target) => write!(
+                f,
+                "The {} target is superseded by disabling the NodeCliInspect fuse",
+                target
+            ),
+        }
+    }
+}
+
+/// What an application has to look like for a [policy](HardeningPolicy) to be applied to it.
+///
+/// These are checked before anything is written, so that a policy applied to the wrong binary fails instead of
+/// partially modifying it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Expectations {
+    min_patches: usize,
+    fuse_wire: bool,
+}
+
+impl Expectations {
+    /// Checks that every fuse can be changed in each of the application's fuse wires, if that's expected.
+    fn check_fuse_wire(
+        &self,
+        app: &ElectronApp<'_>,
+        fuses: &[(Fuse, bool)],
+    ) -> Result<(), PatcherError> {
+        if !self.fuse_wire {
+            return Ok(());
+        }
+
+        for &(fuse, _) in fuses {
+            for wire in 0..app.wires().len() {
+                if app.get_fuse_status_in(wire, fuse)? == FuseStatus::Removed {
+                    return Err(PatcherError::RemovedFuse(fuse));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that enough of the policy's targets were found to be patched.
+    pub(crate) fn check_patches(&self, found: usize) -> Result<(), PatcherError> {
+        if found < self.min_patches {
+            Err(PatcherError::TooFewPatches {
+                expected: self.min_patches,
+                found,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Where each copy of a patch target was found in an application, or its state if it couldn't be found.
+#[derive(Debug, Clone)]
+enum LocatedTarget {
+    Found(Vec<PlannedPatch>),
+    Missing(PatchState),
+}
+
+/// A [policy](HardeningPolicy) prepared against a reference binary, so that it can be applied to many binaries with the
+/// same layout without searching each of them.
+///
+/// This is meant for builds of one application that only differ in their resources, such as branded builds made from
+/// the same Electron binary. Preparing a policy records where the reference's fuse wires and patch targets are, along
+/// with their original bytes. [apply_to](CompiledPolicy::apply_to) checks that those bytes are unchanged in another
+/// binary before patching it at the same positions.
+///
+/// Targets that couldn't be found in the reference are assumed to be missing from the other binaries too, since they
+/// are expected to use the same version of Electron.
+#[derive(Debug, Clone)]
+pub struct CompiledPolicy {
+    policy: HardeningPolicy,
+    wires: Vec<FuseWire>,
+    targets: Vec<LocatedTarget>,
+    /// The reference's bytes at each found target, in the same order as `targets`.
+    originals: Vec<(Range<usize>, Vec<u8>)>,
+}
+
+/// How important it is for a patch target in a [policy](HardeningPolicy) to be found.
+///
+/// This controls how a target that couldn't be found is reported. Targets that aren't expected to exist in the
+/// detected version of Electron are never reported, regardless of their severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum Severity {
+    /// A missing target is a [warning](HardeningWarning::TargetNotPresent) that should block a release.
+    ///
+    /// The command line tool's `--strict` mode fails when one of these is missing.
+    Required,
+    /// A missing target is a [warning](HardeningWarning::TargetNotPresent), but shouldn't block a release.
+    Recommended,
+    /// A missing target is only recorded in the report's [outcomes](HardeningReport::targets).
+    Optional,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Required => "required",
+            Severity::Recommended => "recommended",
+            Severity::Optional => "optional",
+        })
+    }
+}
+
+/// A predefined [policy](HardeningPolicy) for common levels of hardening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Preset {
+    /// Disables the fuses that let an application be run as a plain Node.JS process or be debugged through Node.JS.
+    Minimal,
+    /// Everything in [Minimal](Preset::Minimal), as well as forcing the app to load from `app.asar` and patching out
+    /// Electron's remote debugging options.
+    ///
+    /// This is the same set of modifications made by the command line tool.
+    Recommended,
+    /// Everything in [Recommended](Preset::Recommended), as well as patching out options that relax the app's
+    /// security or redirect its crash reports and dumps at launch.
+    ///
+    /// The options added by this preset have a [Recommended](Severity::Recommended) severity, while all others are
+    /// [Required](Severity::Required).
+    Paranoid,
+}
+
+impl Preset {
+    const MINIMAL_FUSES: &'static [(Fuse, bool)] = &[
+        (Fuse::RunAsNode, false),
+        (Fuse::NodeOptions, false),
+        (Fuse::NodeCliInspect, false),
+    ];
+
+    const RECOMMENDED_FUSES: &'static [(Fuse, bool)] = &[(Fuse::OnlyLoadAppFromAsar, true)];
+
+    const RECOMMENDED_OPTIONS: &'static [ElectronOption] = &[
+        ElectronOption::JsFlags,
+        ElectronOption::RemoteDebuggingPipe,
+        ElectronOption::RemoteDebuggingPort,
+        ElectronOption::WaitForDebuggerChildren,
+    ];
+
+    const PARANOID_OPTIONS: &'static [ElectronOption] = &[
+        ElectronOption::RemoteAllowOrigins,
+        ElectronOption::UnsafelyTreatInsecureOriginAsSecure,
+        ElectronOption::CrashServerUrl,
+        ElectronOption::CrashDumpsDir,
+        ElectronOption::FullMemoryCrashReport,
+    ];
+}
+
+impl From<Preset> for HardeningPolicy {
+    fn from(preset: Preset) -> Self {
+        let mut fuses = Preset::MINIMAL_FUSES.to_vec();
+        let mut targets = Vec::new();
+
+        let with_severity = |options: &[ElectronOption], severity| {
+            options
+                .iter()
+                .map(move |&opt| (PatchTarget::from(opt), severity))
+                .collect::<Vec<_>>()
+        };
+
+        if preset >= Preset::Recommended {
+            fuses.extend_from_slice(Preset::RECOMMENDED_FUSES);
+            targets.extend(with_severity(
+                Preset::RECOMMENDED_OPTIONS,
+                Severity::Required,
+            ));
+        }
+
+        if preset >= Preset::Paranoid {
+            targets.extend(with_severity(
+                Preset::PARANOID_OPTIONS,
+                Severity::Recommended,
+            ));
+        }
+
+        Self {
+            fuses,
+            targets,
+            expectations: Expectations::default(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl HardeningPolicy {
+    /// Creates a new, empty policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy containing everything in the provided preset.
+    ///
+    /// More modifications can be added to it afterwards.
+    pub fn from_preset(preset: Preset) -> Self {
+        preset.into()
+    }
+
+    /// Adds a fuse to the policy that should be set to the provided value.
+    pub fn set_fuse(mut self, fuse: Fuse, enabled: bool) -> Self {
+        self.fuses.push((fuse, enabled));
+        self
+    }
+
+    /// Adds a target to the policy that should be patched out of the application.
+    ///
+    /// The target is [Required](Severity::Required).
+    pub fn patch<T: Into<PatchTarget>>(self, target: T) -> Self {
+        self.patch_with_severity(target, Severity::Required)
+    }
+
+    /// Adds a target to the policy that should be patched out of the application, with the provided severity
+    /// if it can't be found.
+    pub fn patch_with_severity<T: Into<PatchTarget>>(
+        mut self,
+        target: T,
+        severity: Severity,
+    ) -> Self {
+        self.targets.push((target.into(), severity));
+        self
+    }
+
+    /// Requires at least `count` of the policy's targets to be found in the application, or the policy fails without
+    /// modifying it.
+    ///
+    /// Targets that were already patched out count as found, so a hardened application can be hardened again.
+    /// This catches a policy being applied to the wrong binary, such as a different product or architecture, which
+    /// would otherwise only produce [warnings](HardeningReport::warnings).
+    pub fn expect_min_patches(mut self, count: usize) -> Self {
+        self.expectations.min_patches = count;
+        self
+    }
+
+    /// Requires every fuse in the policy to be present in each of the application's fuse wires, or the policy fails
+    /// without modifying it.
+    ///
+    /// Without this, a fuse that was [removed](FuseStatus::Removed) from a wire is only reported as a
+    /// [warning](HardeningWarning::RemovedFuse), which can hide that the policy was applied to a build of Electron it
+    /// wasn't written for.
+    pub fn expect_fuse_wire(mut self, expected: bool) -> Self {
+        self.expectations.fuse_wire = expected;
+        self
+    }
+
+    /// Checks the policy for entries that contradict or repeat each other.
+    ///
+    /// Policies don't have to be built before they're applied, but doing so catches mistakes in hand-written ones
+    /// before they cause confusing results. Entries that repeat an earlier one exactly are removed and recorded as
+    /// [warnings](Self::warnings), along with Node.JS debugging targets that the policy's
+    /// [NodeCliInspect](Fuse::NodeCliInspect) change already covers. Otherwise, the policy is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a fuse is set to different values, or a target is patched with different
+    /// severities.
+    pub fn build(mut self) -> Result<Self, PolicyError> {
+        let mut warnings = Vec::new();
+
+        let mut fuses: Vec<(Fuse, bool)> = Vec::with_capacity(self.fuses.len());
+        for &(fuse, enabled) in &self.fuses {
+            match fuses.iter().find(|(f, _)| *f == fuse) {
+                Some(&(_, value)) if value == enabled => {
+                    warnings.push(PolicyWarning::DuplicateFuse(fuse))
+                }
+                Some(_) => return Err(PolicyError::ConflictingFuse(fuse)),
+                None => fuses.push((fuse, enabled)),
+            }
+        }
+
+        let mut targets: Vec<(PatchTarget, Severity)> = Vec::with_capacity(self.targets.len());
+        for &(target, severity) in &self.targets {
+            match targets.iter().find(|(t, _)| *t == target) {
+                Some(&(_, first)) if first == severity => {
+                    warnings.push(PolicyWarning::DuplicateTarget(target))
+                }
+                Some(&(_, first)) => {
+                    return Err(PolicyError::ConflictingTarget {
+                        target,
+                        severities: vec![first, severity],
+                    })
+                }
+                None => targets.push((target, severity)),
+            }
+        }
+
+        if fuses.contains(&(Fuse::NodeCliInspect, false)) {
+            warnings.extend(
+                targets
+                    .iter()
+                    .filter(|(target, _)| target.is_node_debugging())
+                    .map(|&(target, _)| PolicyWarning::SupersededTarget(target)),
+            );
+        }
+
+        self.fuses = fuses;
+        self.targets = targets;
+        self.warnings = warnings;
+        Ok(self)
+    }
+
+    /// Returns the mistakes found when this policy was last [built](Self::build).
+    pub fn warnings(&self) -> &[PolicyWarning] {
+        &self.warnings
+    }
+
+    /// Returns the minimum number of targets that have to be found for this policy to be applied.
+    pub fn min_patches(&self) -> usize {
+        self.expectations.min_patches
+    }
+
+    /// Returns whether this policy requires all of its fuses to be present in the application.
+    pub fn expects_fuse_wire(&self) -> bool {
+        self.expectations.fuse_wire
+    }
+
+    /// Returns a copy of this policy without any changes to the provided fuses.
+    pub(crate) fn without_fuses(&self, fuses: &[Fuse]) -> Self {
+        let mut policy = self.clone();
+        policy.fuses.retain(|(fuse, _)| !fuses.contains(fuse));
+        policy
+    }
+
+    pub(crate) fn expectations(&self) -> Expectations {
+        self.expectations
+    }
+
+    /// Returns the fuse changes in this policy.
+    pub fn fuses(&self) -> &[(Fuse, bool)] {
+        &self.fuses
+    }
+
+    /// Returns the targets that this policy patches, along with their severities.
+    pub fn targets(&self) -> &[(PatchTarget, Severity)] {
+        &self.targets
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Applies every fuse change and patch in the policy to the application.
+    ///
+    /// Fuses are changed and targets are patched in policy order. Since patches never overlap, the resulting bytes
+    /// don't depend on that order.
+    ///
+    /// Problems that don't prevent the rest of the policy from being applied, such as a missing patch target or a removed
+    /// fuse, are collected as [warnings](HardeningReport::warnings) instead of stopping the operation.
+    ///
+    /// If the [Electron version](ElectronApp::electron_version) can be detected, targets that are missing because they don't
+    /// exist in that version are reported as [unavailable](PatchOutcome::Unavailable) instead of producing a warning.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the fuse wire is malformed, or a fuse in the policy isn't present in it.
+    /// If two patches would modify the same bytes, or the application doesn't meet the policy's
+    /// [expectations](HardeningPolicy::expect_min_patches), an error is returned before anything is written. The
+    /// binary is left unmodified on errors, including when a fuse is only missing from one of several
+    /// [wires](Self::wires).
+    pub fn apply_policy(
+        &mut self,
+        policy: &HardeningPolicy,
+    ) -> Result<HardeningReport, PatcherError> {
+        self.apply(
+            Some(policy.fuses()),
+            policy.targets(),
+            None,
+            policy.expectations(),
+        )
+    }
+
+    /// Applies only the fuse changes in the policy to the application, ignoring its patch targets.
+    ///
+    /// This is useful when fuses and options are handled by different stages of a build. The report only
+    /// describes the fuses, and the policy's [minimum patches](HardeningPolicy::expect_min_patches) aren't checked.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the fuse wire is malformed, or a fuse in the policy isn't present in it.
+    pub fn apply_fuses_only(
+        &mut self,
+        policy: &HardeningPolicy,
+    ) -> Result<HardeningReport, PatcherError> {
+        let expectations = Expectations {
+            min_patches: 0,
+            ..policy.expectations()
+        };
+        self.apply(Some(policy.fuses()), &[], None, expectations)
+    }
+
+    /// Patches only the targets in the policy out of the application, ignoring its fuse changes.
+    ///
+    /// This is useful when fuses and options are handled by different stages of a build. The report only
+    /// describes the targets, and the policy's [fuse wire expectation](HardeningPolicy::expect_fuse_wire) isn't
+    /// checked.
+    ///
+    /// # Errors
+    ///
+    /// If two patches would modify the same bytes, or fewer targets than the policy's
+    /// [minimum](HardeningPolicy::expect_min_patches) are found, an error is returned before anything is written.
+    pub fn apply_options_only(
+        &mut self,
+        policy: &HardeningPolicy,
+    ) -> Result<HardeningReport, PatcherError> {
+        let expectations = Expectations {
+            fuse_wire: false,
+            ..policy.expectations()
+        };
+        self.apply(None, policy.targets(), None, expectations)
+    }
+
+    /// Puts back the contents of the fuse wires from before [apply](Self::apply) changed them, along with the
+    /// journal and the recorded modifications.
+    fn restore_wires(
+        &mut self,
+        snapshot: &[(Range<usize>, Vec<u8>)],
+        journal_len: Option<usize>,
+        modifications_len: usize,
+    ) {
+        for (range, original) in snapshot {
+            self.contents[range.clone()].copy_from_slice(original);
+        }
+        self.modifications.truncate(modifications_len);
+
+        if let (Some(journal), Some(len)) = (&mut self.journal, journal_len) {
+            journal.truncate(len);
+        }
+    }
+
+    /// Locates a target in the application, or determines its state if it can't be found.
+    fn locate_target(&self, target: PatchTarget) -> Result<LocatedTarget, PatcherError> {
+        match self.plan_target(target) {
+            Ok(patches) => Ok(LocatedTarget::Found(patches)),
+            Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(_)))
+            | Err(PatcherError::Binary(BinaryError::TargetNotPresent(_))) => {
+                Ok(LocatedTarget::Missing(target.patch_state(&self.contents)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Applies the fuse changes and targets. The fuse wire is only checked for unknown fuses when `fuses` is provided.
+    ///
+    /// If `located` is provided, it's used instead of searching for each target.
+    fn apply(
+        &mut self,
+        fuses: Option<&[(Fuse, bool)]>,
+        targets: &[(PatchTarget, Severity)],
+        located: Option<&[LocatedTarget]>,
+        expectations: Expectations,
+    ) -> Result<HardeningReport, PatcherError> {
+        expectations.check_fuse_wire(self, fuses.unwrap_or_default())?;
+
+        let mut report = HardeningReport::new(self.schema_version());
+        report.wire_fuses = vec![Vec::new(); self.wires.len()];
+        report.electron_version = self.electron_version();
+        report.patch_fill = self.patch_fill;
+
+        let unknown_positions = self.unknown_fuse_positions();
+        if fuses.is_some() && !unknown_positions.is_empty() {
+            report
+                .warnings
+                .push(HardeningWarning::UnknownFusePositions(unknown_positions));
+        }
+
+        // Every target is located before anything is written, so that overlapping patches can be rejected
+        // without leaving the binary partially modified.
+        let mut planned = Vec::new();
+        let mut found = 0;
+        let mut missing_warnings = Vec::new();
+
+        for (i, &(target, severity)) in targets.iter().enumerate() {
+            let target_location = match located {
+                Some(located) => located[i].clone(),
+                None => self.locate_target(target)?,
+            };
+
+            let outcome = match target_location {
+                LocatedTarget::Found(patches) => {
+                    planned.extend(patches);
+                    found += 1;
+                    report.prior_states.push((target, PatchState::Unpatched));
+                    PatchOutcome::Patched
+                }
+                LocatedTarget::Missing(state) => {
+                    report.prior_states.push((target, state));
+
+                    match (state, report.electron_version) {
+                        (PatchState::PatchedByThisTool, _)
+                        | (PatchState::PatchedByEvilFeaturePatcher, _) => {
+                            PatchOutcome::AlreadyPatched
+                        }
+                        (_, Some(version)) if !target.is_available_in(version) => {
+                            PatchOutcome::Unavailable
+                        }
+                        _ => {
+                            if severity != Severity::Optional {
+                                missing_warnings
+                                    .push(HardeningWarning::TargetNotPresent { target, severity });
+                            }
+                            PatchOutcome::NotPresent
+                        }
+                    }
+                }
+            };
+
+            report.targets.push((target, outcome));
+        }
+
+        PlannedPatch::check_overlaps(&planned)?;
+        #[cfg(feature = "object-parser")]
+        self.check_code_patches(&planned)?;
+        for patch in &planned {
+            self.check_write(
+                ModifiedItem::Target(patch.target),
+                patch.range.start,
+                &patch.replacement,
+            )?;
+        }
+        // Fuses check the signature themselves, only when they would be modified.
+        if !planned.is_empty() {
+            self.check_signature()?;
+        }
+        let already_patched = report
+            .prior_states
+            .iter()
+            .filter(|(_, state)| {
+                matches!(
+                    state,
+                    PatchState::PatchedByThisTool | PatchState::PatchedByEvilFeaturePatcher
+                )
+            })
+            .count();
+        expectations.check_patches(found + already_patched)?;
+
+        // This is the last point the application can be cancelled at, because everything after it writes to the
+        // binary.
+        self.check_cancelled()?;
+
+        // If a fuse can't be changed in one of the wires, the others are restored as well. A binary containing
+        // several Electron binaries, such as a universal macOS binary, is worse off with only some of them hardened
+        // than with none.
+        let snapshot: Vec<(Range<usize>, Vec<u8>)> = self
+            .wires
+            .iter()
+            .map(|wire| (wire.range(), self.contents[wire.range()].to_vec()))
+            .collect();
+        let journal_len = self.journal.as_ref().map(|journal| journal.entries().len());
+        let modifications_len = self.modifications.len();
+
+        for &(fuse, enabled) in fuses.unwrap_or_default() {
+            // Enabling this fuse on a build that loads its code from a directory stops it from starting.
+            if fuse == Fuse::OnlyLoadAppFromAsar
+                && enabled
+                && self.get_fuse_status(fuse) == Ok(FuseStatus::Present(false))
+            {
+                if let Some((offset, path)) = self.non_asar_app_path() {
+                    report.warnings.push(HardeningWarning::NonAsarAppPath {
+                        path: path.to_string(),
+                        offset,
+                    });
+                }
+
+                if let Some(layout) = self.app_layout {
+                    if layout.conflicts_with_asar_only() {
+                        report.warnings.push(HardeningWarning::UnpackedApp(layout));
+                    }
+                }
+            }
+
+            // Each wire is changed separately so that a fuse removed from one doesn't stop the others from being
+            // changed.
+            let mut statuses = Vec::with_capacity(self.wires.len());
+            for wire in 0..self.wires.len() {
+                statuses.push(match self.set_fuse_status_in(wire, fuse, enabled) {
+                    Ok(status) => status,
+                    Err(PatcherError::RemovedFuse(_)) => FuseStatus::Removed,
+                    Err(e) => {
+                        self.restore_wires(&snapshot, journal_len, modifications_len);
+                        return Err(e);
+                    }
+                });
+            }
+
+            if statuses.contains(&FuseStatus::Removed) {
+                report.warnings.push(HardeningWarning::RemovedFuse(fuse));
+            }
+
+            let status = if statuses.contains(&FuseStatus::Modified) {
+                FuseStatus::Modified
+            } else {
+                statuses[0]
+            };
+
+            if let (true, Some(version), Some(since)) =
+                (enabled, report.electron_version, fuse.effective_since())
+            {
+                if version.major < since {
+                    report
+                        .warnings
+                        .push(HardeningWarning::IneffectiveFuse { fuse, version });
+                }
+            }
+
+            report.fuses.push((fuse, status));
+            for (wire_fuses, status) in report.wire_fuses.iter_mut().zip(statuses) {
+                wire_fuses.push((fuse, status));
+            }
+        }
+
+        let inspect_disabled = matches!(
+            self.get_fuse_status(Fuse::NodeCliInspect),
+            Ok(FuseStatus::Present(false))
+        );
+
+        if inspect_disabled {
+            report.warnings.extend(
+                targets
+                    .iter()
+                    .filter(|(target, _)| target.is_node_debugging())
+                    .map(|&(target, _)| HardeningWarning::RedundantPatch(target)),
+            );
+        }
+
+        report.warnings.extend(missing_warnings);
+
+        report.final_fuses = Fuse::ALL
+            .iter()
+            .filter_map(|&fuse| Some((fuse, self.get_fuse_status(fuse).ok()?)))
+            .collect();
+
+        // Context is captured before anything is written, so neighboring patches don't show up in each other's.
+        if self.context_bytes > 0 {
+            report.contexts = planned
+                .iter()
+                .map(|patch| {
+                    let context = PatchContext::capture(
+                        &self.contents,
+                        patch.range.clone(),
+                        self.context_bytes,
+                    );
+                    (patch.target, context)
+                })
+                .collect();
+        }
+
+        for patch in planned {
+            self.write_bytes(
+                ModifiedItem::Target(patch.target),
+                patch.range.start,
+                &patch.replacement,
+            )?;
+            report.matches.push((patch.target, patch.found));
+        }
+
+        report.modifications = self.modifications[modifications_len..].to_vec();
+        if !report.modifications.is_empty() && self.has_code_signature() {
+            report
+                .warnings
+                .push(HardeningWarning::CodeSignatureInvalidated);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the strongest [preset](Preset) whose modifications have already been made to the application, or
+    /// `None` if it doesn't satisfy any of them.
+    ///
+    /// A fuse satisfies a preset if it has the value the preset would set, or if it has been removed. A patch target
+    /// satisfies a preset if it can't be found in the application.
+    pub fn satisfied_preset(&self) -> Option<Preset> {
+        [Preset::Paranoid, Preset::Recommended, Preset::Minimal]
+            .iter()
+            .copied()
+            .find(|&preset| self.satisfies(&HardeningPolicy::from_preset(preset)))
+    }
+
+    /// Checks if applying the policy would leave the application unchanged.
+    fn satisfies(&self, policy: &HardeningPolicy) -> bool {
+        let fuses_satisfied = policy.fuses().iter().all(|&(fuse, enabled)| {
+            (0..self.wires.len()).all(|wire| match self.get_fuse_status_in(wire, fuse) {
+                Ok(FuseStatus::Present(current)) => current == enabled,
+                Ok(FuseStatus::Removed) => true,
+                _ => false,
+            })
+        });
+
+        fuses_satisfied
+            && policy
+                .targets()
+                .iter()
+                .all(|&(target, _)| self.scan(target).is_none())
+    }
+}
+
+impl CompiledPolicy {
+    /// Prepares a policy for the binaries that share the layout of `reference`.
+    ///
+    /// The reference itself isn't modified.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the reference isn't an Electron application, or if searching it for the
+    /// policy's targets fails.
+    pub fn prepare(reference: &[u8], policy: &HardeningPolicy) -> Result<Self, PatcherError> {
+        let app = ElectronApp::from_bytes_readonly(reference)?;
+        let mut targets = Vec::with_capacity(policy.targets().len());
+        let mut originals = Vec::new();
+
+        for &(target, _) in policy.targets() {
+            match app.plan_target(target) {
+                Ok(patches) => {
+                    for patch in &patches {
+                        let range = patch.found.range.start.min(patch.range.start)
+                            ..patch.found.range.end.max(patch.range.end);
+                        originals.push((range.clone(), reference[range].to_vec()));
+                    }
+                    targets.push(LocatedTarget::Found(patches));
+                }
+                Err(PatcherError::Binary(BinaryError::ElectronOptionNotPresent(_)))
+                | Err(PatcherError::Binary(BinaryError::TargetNotPresent(_))) => {
+                    targets.push(LocatedTarget::Missing(target.patch_state(reference)));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self {
+            policy: policy.clone(),
+            wires: app.wires.clone(),
+            targets,
+            originals,
+        })
+    }
+
+    /// Returns the policy that was prepared.
+    pub fn policy(&self) -> &HardeningPolicy {
+        &self.policy
+    }
+
+    /// Applies the prepared policy to a binary with the same layout as the reference.
+    ///
+    /// If the binary's fuse wires or patch targets aren't where they were in the reference, the binary is searched
+    /// from scratch like [apply_policy](ElectronApp::apply_policy) and a
+    /// [ReferenceLayoutMismatch](HardeningWarning::ReferenceLayoutMismatch) warning is added to the report. Either
+    /// way, the binary ends up hardened the same.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error for the same reasons as [apply_policy](ElectronApp::apply_policy).
+    pub fn apply_to(&self, other: &mut [u8]) -> Result<HardeningReport, PatcherError> {
+        match self.first_mismatch(other) {
+            None => ElectronApp::with_wires(other, self.wires.clone()).apply(
+                Some(self.policy.fuses()),
+                self.policy.targets(),
+                Some(&self.targets),
+                self.policy.expectations(),
+            ),
+            Some(offset) => {
+                let mut report = ElectronApp::from_bytes(other)?.apply_policy(&self.policy)?;
+                report
+                    .warnings
+                    .push(HardeningWarning::ReferenceLayoutMismatch { offset });
+                Ok(report)
+            }
+        }
+    }
+
+    /// Returns the first position where `other` doesn't match the reference's fuse wires and patch targets.
+    fn first_mismatch(&self, other: &[u8]) -> Option<usize> {
+        for wire in &self.wires {
+            let start = wire.header_start();
+            let end = wire.range().end;
+            match other
+                .get(start..end)
+                .map(|section| FuseWire::locate(section, start))
+            {
+                Some(Ok(found))
+                    if found.range() == wire.range() && found.version() == wire.version() => {}
+                _ => return Some(start),
+            }
+        }
+
+        for (range, original) in &self.originals {
+            if other.get(range.clone()) != Some(original.as_slice()) {
+                return Some(range.start);
+            }
+        }
+
+        None
+    }
+}
+
+/// Applies the [Recommended](Preset::Recommended) policy to an application binary.
+///
+/// This is the quickest way to harden an application. Use [apply_policy](ElectronApp::apply_policy) to apply a
+/// different policy or to configure how the application is searched.
+///
+/// # Errors
+///
+/// This function will return an error if the binary isn't an Electron application, or for the same reasons as
+/// [apply_policy](ElectronApp::apply_policy). The binary is left unmodified on errors.
+pub fn harden(bytes: &mut [u8]) -> Result<HardeningReport, PatcherError> {
+    ElectronApp::from_bytes(bytes)?.apply_policy(&HardeningPolicy::from_preset(Preset::Recommended))
+}
+
+/// Applies the [Recommended](Preset::Recommended) policy to an application binary on disk, the same way the command
+/// line tool does by default.
+///
+/// The binary's [resources directory](AppLayout::for_binary) is inspected, so that enabling the
+/// [OnlyLoadAppFromAsar](Fuse::OnlyLoadAppFromAsar) fuse on an application loaded from an `app` directory is reported
+/// as a warning. If anything was [modified](HardeningReport::is_modified), the hardened binary is written to a
+/// temporary file next to it, which then replaces it. The binary is never left partially written, even if the
+/// process is interrupted.
+///
+/// # Errors
+///
+/// This function returns [ReadOnly](SourceError::ReadOnly) if the binary can't be replaced because of how it's
+/// stored, such as on a read-only filesystem or with an immutable flag set, and [Io](SourceError::Io) if it couldn't
+/// be read or replaced for another reason. Otherwise, it returns the same errors as [harden].
+pub fn harden_file<P: AsRef<Path>>(path: P) -> Result<HardeningReport, SourceError> {
+    let path = path.as_ref();
+    let mut bytes = fs::read(path)?;
+    harden_bytes_of(
+        path,
+        &mut bytes,
+        &HardeningPolicy::from_preset(Preset::Recommended),
+    )
+}
+
+/// Applies a policy to many application binaries on disk, hardening several at once while keeping the memory used by
+/// their contents under the [pool's](BufferPool) cap.
+///
+/// Each binary is hardened like [harden_file] does, but with `policy` instead of the recommended one. They're read
+/// into buffers taken from `pool`, which are reused between binaries. Up to one binary per available CPU is hardened
+/// in parallel, as long as their sizes fit under [max_resident_bytes](BufferPool::max_resident_bytes) together;
+/// otherwise, binaries wait for others to finish.
+///
+/// The results are returned in the same order as `paths`. A binary that can't be hardened doesn't stop the others.
+///
+/// The `electron-hardener` CLI doesn't use this function: it hardens its binaries one at a time, printing each result
+/// as soon as it's done.
+///
+/// # Errors
+///
+/// Each binary's result contains the same errors as [harden_file]. A binary that changes size while it's being read
+/// is reported as an [Io](SourceError::Io) error and left as it is.
+pub fn harden_files<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    policy: &HardeningPolicy,
+    pool: &BufferPool,
+) -> Vec<Result<HardeningReport, SourceError>> {
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(paths.len());
+
+    let mut results = thread::scope(|scope| {
+        let workers: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(index) else {
+                            return results;
+                        };
+                        results.push((index, harden_pooled_file(path.as_ref(), policy, pool)));
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_by_key(|&(index, _)| index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn harden_pooled_file(
+    path: &Path,
+    policy: &HardeningPolicy,
+    pool: &BufferPool,
+) -> Result<HardeningReport, SourceError> {
+    let mut file = fs::File::open(path)?;
+    let len = usize::try_from(file.metadata()?.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "binary is too large"))?;
+
+    let mut bytes = pool.acquire(len);
+    bytes.resize(len, 0);
+    file.read_exact(&mut bytes)?;
+    // A binary that grew would be truncated when it's replaced, so it's hardened on the next run instead.
+    if file.read(&mut [0])? != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} changed while it was read", path.display()),
+        )
+        .into());
+    }
+
+    harden_bytes_of(path, &mut bytes, policy)
+}
+
+/// Applies a policy to the contents of a binary read from `path`, replacing the binary if anything was modified.
+fn harden_bytes_of(
+    path: &Path,
+    bytes: &mut [u8],
+    policy: &HardeningPolicy,
+) -> Result<HardeningReport, SourceError> {
+    let mut app = ElectronApp::from_bytes(bytes)?;
+    if let Some(layout) = AppLayout::for_binary(path) {
+        app.set_app_layout(layout);
+    }
+    let report = app.apply_policy(policy)?;
+
+    if app.is_dirty() {
+        drop(app);
+        replace_binary(path, bytes)?;
+    }
+
+    Ok(report)
+}
+
+/// Writes a binary to a file, replacing it if it exists, the same way [harden_file] does.
+///
+/// The contents are written to a temporary file in the same directory, which is then renamed over the file and keeps
+/// its permissions. The file is never left partially written, even if the process is interrupted. Every function in
+/// this crate that writes a binary to disk goes through this one.
+///
+/// # Errors
+///
+/// This function returns [ReadOnly](SourceError::ReadOnly) if the file can't be written because of how it's stored,
+/// and [Io](SourceError::Io) if it couldn't be written for another reason. See
+/// [from_write_error](SourceError::from_write_error).
+pub fn replace_binary<P: AsRef<Path>>(path: P, contents: &[u8]) -> Result<(), SourceError> {
+    let path = path.as_ref();
+    replace_file(path, contents).map_err(|e| SourceError::from_write_error(path, e))
+}
+
+fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} isn't a file", path.display()),
+        )
+    })?;
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    // A file that doesn't exist yet is created with the default permissions.
+    let permissions = match fs::metadata(path) {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let result = fs::write(&temp_path, contents)
+        .and_then(|_| match permissions {
+            Some(permissions) => fs::set_permissions(&temp_path, permissions),
+            None => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Applies a policy to a copy of an application binary, leaving the original untouched.
+///
+/// This is useful when both the original and hardened versions of the binary are needed. The
+/// [report](HardeningReport) is discarded, so use [apply_policy](ElectronApp::apply_policy) instead if its warnings
+/// are needed.
+///
+/// # Errors
+///
+/// This function will return an error if the binary isn't an Electron application, or for the same reasons as
+/// [apply_policy](ElectronApp::apply_policy).
+pub fn harden_to_vec(original: &[u8], policy: &HardeningPolicy) -> Result<Vec<u8>, PatcherError> {
+    let mut hardened = original.to_vec();
+    ElectronApp::from_bytes(&mut hardened)?.apply_policy(policy)?;
+    Ok(hardened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fuses::FuseWire,
+        patcher::{
+            tests::{app_bytes, test_app_bytes},
+            PatternId,
+        },
+        ElectronVersion, UndoJournal,
+    };
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+    const FLAG_BYTES: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+
+    /// Erases a target from the binary, as if it had been built without it.
+    fn remove_target<T: Into<PatchTarget>>(bytes: &mut [u8], target: T) {
+        let found = target.into().locate(bytes).unwrap();
+        for byte in &mut bytes[found.range] {
+            *byte = 0;
+        }
+    }
+
+    #[test]
+    fn clean_policies_are_built_unchanged() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid).expect_min_patches(3);
+        assert_eq!(policy.clone().build(), Ok(policy));
+    }
+
+    #[test]
+    fn duplicate_policy_entries_are_removed() {
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .patch(ElectronOption::JsFlags)
+            .set_fuse(Fuse::RunAsNode, false)
+            .patch(ElectronOption::JsFlags)
+            .build()
+            .unwrap();
+
+        assert_eq!(policy.fuses(), &[(Fuse::RunAsNode, false)]);
+        assert_eq!(
+            policy.targets(),
+            &[(ElectronOption::JsFlags.into(), Severity::Required)]
+        );
+        assert_eq!(
+            policy.warnings(),
+            &[
+                PolicyWarning::DuplicateFuse(Fuse::RunAsNode),
+                PolicyWarning::DuplicateTarget(ElectronOption::JsFlags.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicting_policy_entries_are_rejected() {
+        let err = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .set_fuse(Fuse::RunAsNode, true)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, PolicyError::ConflictingFuse(Fuse::RunAsNode));
+        assert_eq!(
+            err.to_string(),
+            "The RunAsNode fuse is set to both enabled and disabled"
+        );
+
+        let err = HardeningPolicy::new()
+            .patch(ElectronOption::JsFlags)
+            .patch_with_severity(ElectronOption::JsFlags, Severity::Optional)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            PolicyError::ConflictingTarget {
+                target: ElectronOption::JsFlags.into(),
+                severities: vec![Severity::Required, Severity::Optional],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "The js-flags target is patched with conflicting severities: required, optional"
+        );
+    }
+
+    #[test]
+    fn superseded_policy_targets_are_flagged() {
+        let policy = HardeningPolicy::from_preset(Preset::Minimal)
+            .patch(PatchTarget::NodeInspect)
+            .patch(ElectronOption::JsFlags)
+            .build()
+            .unwrap();
+
+        assert_eq!(policy.targets().len(), 2);
+        assert_eq!(
+            policy.warnings(),
+            &[PolicyWarning::SupersededTarget(PatchTarget::NodeInspect)]
+        );
+    }
+
+    #[test]
+    fn policy_is_applied() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .set_fuse(Fuse::EncryptedCookies, false)
+            .patch(ElectronOption::JsFlags);
+
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.fuses,
+            vec![
+                (Fuse::RunAsNode, FuseStatus::Modified),
+                (Fuse::EncryptedCookies, FuseStatus::Present(false)),
+            ]
+        );
+        assert_eq!(
+            report.targets,
+            vec![(ElectronOption::JsFlags.into(), PatchOutcome::Patched)]
+        );
+        assert!(report.warnings.is_empty());
+
+        let (target, found) = &report.matches[0];
+        assert_eq!(*target, ElectronOption::JsFlags.into());
+        assert_eq!(found.matched_pattern, PatternId::Canonical);
+        assert_eq!(
+            &bytes[found.range.start + 1..found.range.end],
+            b"xx\r\nxxxx\0"
+        );
+        assert_eq!(report.patch_fill, b'x');
+    }
+
+    #[test]
+    fn patch_fill_is_reported() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_patch_fill(b'#');
+
+        let report = app
+            .apply_policy(&HardeningPolicy::new().patch(ElectronOption::JsFlags))
+            .unwrap();
+        assert_eq!(report.patch_fill, b'#');
+
+        let found = &report.matches[0].1;
+        assert_eq!(&bytes[found.range.clone()], b"\0xx\r\n####\0");
+    }
+
+    #[test]
+    fn patch_context_is_captured() {
+        let original = test_app_bytes();
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new().patch(ElectronOption::JsFlags);
+        let report = app.apply_policy(&policy).unwrap();
+
+        let (target, context) = &report.contexts[0];
+        assert_eq!(*target, ElectronOption::JsFlags.into());
+        let range = context.range.clone();
+        assert_eq!(
+            context.before,
+            crate::journal::encode_hex(&original[range.start - 16..range.start])
+        );
+        assert_eq!(
+            context.after,
+            crate::journal::encode_hex(&original[range.end..range.end + 16])
+        );
+        assert_eq!(context.before_text.len(), 16);
+        assert!(context
+            .before_text
+            .chars()
+            .all(|c| c == '.' || c.is_ascii_graphic() || c == ' '));
+
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_report_context(0);
+        assert!(app.apply_policy(&policy).unwrap().contexts.is_empty());
+
+        // Context stops at the ends of the binary.
+        let context = PatchContext::capture(b"\0js-flags\0", 1..10, 16);
+        assert_eq!(context.before, "00");
+        assert_eq!(context.after, "");
+        assert_eq!(context.before_text, ".");
+    }
+
+    #[test]
+    fn harden_applies_the_recommended_preset() {
+        let original = test_app_bytes();
+        let mut bytes = original.clone();
+
+        let report = harden(&mut bytes).unwrap();
+        assert!(report.is_modified());
+        assert_eq!(
+            bytes,
+            harden_to_vec(
+                &original,
+                &HardeningPolicy::from_preset(Preset::Recommended)
+            )
+            .unwrap()
+        );
+
+        assert!(!harden(&mut bytes).unwrap().is_modified());
+    }
+
+    #[test]
+    fn harden_file_replaces_the_binary() {
+        let dir = std::env::temp_dir().join(format!(
+            "electron-hardener-harden-file-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("resources").join("app")).unwrap();
+        let binary = dir.join("app");
+        fs::write(&binary, test_app_bytes()).unwrap();
+        fs::write(dir.join("not-electron"), b"\0js-flags\0".repeat(8)).unwrap();
+
+        let report = harden_file(&binary);
+        let hardened = fs::read(&binary);
+        let second = harden_file(&binary);
+        let not_electron = harden_file(dir.join("not-electron"));
+        let entries = fs::read_dir(&dir).unwrap().count();
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut expected = test_app_bytes();
+        let expected_report = harden(&mut expected).unwrap();
+        let report = report.unwrap();
+        assert_eq!(hardened.unwrap(), expected);
+        assert!(report.is_modified());
+        // The layout is detected from the resources directory next to the binary.
+        assert!(report
+            .warnings
+            .contains(&HardeningWarning::UnpackedApp(AppLayout::Directory)));
+        assert!(!expected_report
+            .warnings
+            .contains(&HardeningWarning::UnpackedApp(AppLayout::Directory)));
+
+        assert!(!second.unwrap().is_modified());
+        assert!(matches!(
+            not_electron,
+            Err(SourceError::Patcher(PatcherError::Binary(
+                BinaryError::NoSentinel
+            )))
+        ));
+        // No temporary files are left behind.
+        assert_eq!(entries, 3);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn write_errors_explain_read_only_binaries() {
+        use crate::ReadOnlyReason;
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = Path::new("app");
+        let reason =
+            |code| match SourceError::from_write_error(path, io::Error::from_raw_os_error(code)) {
+                SourceError::ReadOnly { reason, .. } => Some(reason),
+                _ => None,
+            };
+        assert_eq!(reason(30), Some(ReadOnlyReason::ReadOnlyFilesystem));
+        // EPERM alone doesn't mean the binary is immutable, since it's also returned when the system refuses the
+        // write for other reasons.
+        assert_eq!(reason(1), Some(ReadOnlyReason::PermissionDenied));
+        assert_eq!(reason(13), Some(ReadOnlyReason::PermissionDenied));
+        assert_eq!(reason(28), None);
+
+        let error = SourceError::from_write_error(path, io::Error::from_raw_os_error(30));
+        assert!(error.to_string().contains("read-only filesystem"));
+        assert!(error
+            .to_string()
+            .contains(ReadOnlyReason::ReadOnlyFilesystem.remediation()));
+
+        // The binary is replaced through its directory, so a read-only directory stops it from being written.
+        let dir = std::env::temp_dir().join(format!(
+            "electron-hardener-read-only-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("app");
+        fs::write(&binary, test_app_bytes()).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Permissions don't apply to the superuser, so there's nothing to check when running as one.
+        let writable = fs::write(dir.join("probe"), b"").is_ok();
+        let result = harden_file(&binary);
+        let contents = fs::read(&binary).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        if !writable {
+            assert!(matches!(
+                result,
+                Err(SourceError::ReadOnly {
+                    reason: ReadOnlyReason::PermissionDenied,
+                    ..
+                })
+            ));
+            assert!(contents == test_app_bytes());
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn immutable_binaries_are_identified() {
+        use crate::ReadOnlyReason;
+        use std::process::Command;
+
+        let dir = std::env::temp_dir().join(format!(
+            "electron-hardener-immutable-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("app");
+        fs::write(&binary, test_app_bytes()).unwrap();
+
+        let chattr = |flag| {
+            Command::new("chattr")
+                .arg(flag)
+                .arg(&binary)
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        // Setting the flag needs privileges and a filesystem that supports it, so there's nothing to check otherwise.
+        let immutable = chattr("+i");
+        let result = harden_file(&binary);
+        if immutable {
+            chattr("-i");
+        }
+        let _ = fs::remove_dir_all(&dir);
+
+        if immutable {
+            assert!(matches!(
+                result,
+                Err(SourceError::ReadOnly {
+                    reason: ReadOnlyReason::Immutable,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn policy_is_applied_to_copy() {
+        let original = test_app_bytes();
+        let policy = HardeningPolicy::from_preset(Preset::Minimal);
+
+        let hardened = harden_to_vec(&original, &policy).unwrap();
+        assert_eq!(original, test_app_bytes());
+
+        let mut expected = test_app_bytes();
+        ElectronApp::from_bytes(&mut expected)
+            .unwrap()
+            .apply_policy(&policy)
+            .unwrap();
+        assert_eq!(hardened, expected);
+        assert_ne!(hardened, original);
+    }
+
+    #[test]
+    fn hardening_is_deterministic() {
+        let original = test_app_bytes();
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+
+        let first = harden_to_vec(&original, &policy).unwrap();
+        let second = harden_to_vec(&original, &policy).unwrap();
+        assert_eq!(first, second);
+
+        // Neither the order of the policy nor a search hint affect the result.
+        let reversed = HardeningPolicy {
+            fuses: policy.fuses.iter().rev().copied().collect(),
+            targets: policy.targets.iter().rev().copied().collect(),
+            ..policy.clone()
+        };
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_search_hint(FUSE_BYTES.len()..original.len());
+        app.apply_policy(&reversed).unwrap();
+        assert_eq!(bytes, first);
+
+        // Hardening an already hardened binary doesn't change it.
+        assert_eq!(harden_to_vec(&first, &policy).unwrap(), first);
+
+        // The same bytes are written whether the binary is in memory, read in windows through a source, or mapped.
+        // The fuse wire crosses the boundary of the first window, so it's only found in the overlap between windows.
+        let mut spread = vec![0; (1 << 20) - 10];
+        spread.extend_from_slice(FUSE_BYTES);
+        spread.resize((2 << 20) - 4, 0);
+        spread.extend_from_slice(FLAG_BYTES);
+        let in_memory = harden_to_vec(&spread, &policy).unwrap();
+        assert!(in_memory != spread);
+
+        let mut windowed = spread.clone();
+        crate::harden_source(&mut windowed[..], &policy).unwrap();
+        assert!(windowed == in_memory, "the source was hardened differently");
+
+        #[cfg(feature = "mmap")]
+        {
+            let path = std::env::temp_dir().join(format!(
+                "electron-hardener-deterministic-{}",
+                std::process::id()
+            ));
+            fs::write(&path, &spread).unwrap();
+            let mut app = ElectronApp::open_mmap(&path).unwrap();
+            app.apply_policy(&policy).unwrap();
+            app.flush().unwrap();
+            drop(app);
+            let mapped = fs::read(&path);
+            let _ = fs::remove_file(&path);
+            assert!(
+                mapped.unwrap() == in_memory,
+                "the mapped binary was hardened differently"
+            );
+        }
+    }
+
+    #[test]
+    fn policy_is_applied_to_every_wire() {
+        let mut bytes = FUSE_BYTES.to_vec();
+        bytes.extend_from_slice(FUSE_BYTES);
+        bytes.extend_from_slice(FLAG_BYTES);
+        let second_wire = FuseWire::locate_all(&bytes, 0).unwrap()[1].range();
+        // RunAsNode is the first fuse in the wire.
+        bytes[second_wire.start] = b'0';
+
+        let policy = HardeningPolicy::new().set_fuse(Fuse::RunAsNode, false);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(report.fuses, vec![(Fuse::RunAsNode, FuseStatus::Modified)]);
+        assert_eq!(
+            report.wire_fuses,
+            vec![
+                vec![(Fuse::RunAsNode, FuseStatus::Modified)],
+                vec![(Fuse::RunAsNode, FuseStatus::Present(false))],
+            ]
+        );
+        assert_eq!(
+            app.get_fuse_status_in(0, Fuse::RunAsNode),
+            Ok(FuseStatus::Present(false))
+        );
+    }
+
+    #[test]
+    fn fuses_and_options_can_be_applied_separately() {
+        let policy = HardeningPolicy::from_preset(Preset::Recommended);
+        let expected = harden_to_vec(&test_app_bytes(), &policy).unwrap();
+
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let report = app.apply_fuses_only(&policy).unwrap();
+        assert_eq!(report.fuses.len(), policy.fuses().len());
+        assert!(report.targets.is_empty());
+        assert!(app.scan(ElectronOption::JsFlags).is_some());
+
+        let report = app.apply_options_only(&policy).unwrap();
+        assert!(report.fuses.is_empty());
+        assert!(report
+            .targets
+            .iter()
+            .all(|(_, outcome)| *outcome == PatchOutcome::Patched));
+
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn hardening_twice_changes_nothing() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+        let windows_flags = include_bytes!("../examples/fake_electron_flags_windows.bin");
+
+        for flags in [FLAG_BYTES, &windows_flags[..]] {
+            let mut bytes = app_bytes(flags);
+            ElectronApp::from_bytes(&mut bytes)
+                .unwrap()
+                .apply_policy(&policy)
+                .unwrap();
+            let hardened = bytes.clone();
+
+            let report = ElectronApp::from_bytes(&mut bytes)
+                .unwrap()
+                .apply_policy(&policy)
+                .unwrap();
+            assert!(!report.is_modified());
+            assert!(report
+                .targets
+                .iter()
+                .all(|(_, outcome)| *outcome == PatchOutcome::AlreadyPatched));
+            assert!(!report
+                .warnings
+                .iter()
+                .any(|warning| matches!(warning, HardeningWarning::TargetNotPresent { .. })));
+            assert_eq!(bytes, hardened);
+        }
+    }
+
+    #[test]
+    fn missing_option_is_a_warning() {
+        let mut bytes = test_app_bytes();
+        remove_target(&mut bytes, ElectronOption::JsFlags);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new().patch(ElectronOption::JsFlags);
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.targets,
+            vec![(ElectronOption::JsFlags.into(), PatchOutcome::NotPresent)]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::TargetNotPresent {
+                target: ElectronOption::JsFlags.into(),
+                severity: Severity::Required,
+            }]
+        );
+    }
+    #[test]
+    fn compiled_policy_patches_matching_layout() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+        let reference = test_app_bytes();
+        let compiled = CompiledPolicy::prepare(&reference, &policy).unwrap();
+
+        // A resource difference before the fuse wire doesn't change the layout.
+        let mut other = reference.clone();
+        other[0] ^= 0xff;
+        let expected = harden_to_vec(&other, &policy).unwrap();
+
+        let report = compiled.apply_to(&mut other).unwrap();
+        assert!(!report
+            .warnings
+            .iter()
+            .any(|w| matches!(w, HardeningWarning::ReferenceLayoutMismatch { .. })));
+        assert_eq!(other, expected);
+
+        // The reference is left as it was.
+        assert_eq!(reference, test_app_bytes());
+    }
+
+    #[test]
+    fn compiled_policy_falls_back_on_shifted_layout() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+        let compiled = CompiledPolicy::prepare(&test_app_bytes(), &policy).unwrap();
+
+        let mut shifted = vec![0; 16];
+        shifted.extend_from_slice(&test_app_bytes());
+        let expected = harden_to_vec(&shifted, &policy).unwrap();
+
+        let report = compiled.apply_to(&mut shifted).unwrap();
+        assert!(report
+            .warnings
+            .contains(&HardeningWarning::ReferenceLayoutMismatch { offset: 112 }));
+        assert_eq!(shifted, expected);
+    }
+
+    #[test]
+    fn tiny_inputs_are_rejected_without_writing() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+        let compiled = CompiledPolicy::prepare(&test_app_bytes(), &policy).unwrap();
+
+        for &len in &[0, 1, 31, 33] {
+            let original = test_app_bytes()[..len].to_vec();
+            let too_small = |result: Result<_, PatcherError>| {
+                matches!(
+                    result,
+                    Err(PatcherError::Binary(BinaryError::EmptyInput))
+                        | Err(PatcherError::Binary(BinaryError::InputTooSmall { .. }))
+                )
+            };
+
+            let mut bytes = original.clone();
+            assert!(too_small(harden(&mut bytes).map(|_| ())), "{}", len);
+            assert!(too_small(harden_to_vec(&bytes, &policy).map(|_| ())));
+            assert!(too_small(
+                CompiledPolicy::prepare(&bytes, &policy).map(|_| ())
+            ));
+            assert!(too_small(compiled.apply_to(&mut bytes).map(|_| ())));
+            assert_eq!(bytes, original);
+
+            let posture = crate::PostureReport::from_binary(&bytes);
+            assert_eq!(posture.count(crate::Exposure::Exposed), 0);
+        }
+    }
+
+    #[test]
+    fn severity_controls_missing_target_warnings() {
+        let mut bytes = test_app_bytes();
+        remove_target(&mut bytes, ElectronOption::JsFlags);
+        remove_target(&mut bytes, ElectronOption::RemoteDebuggingPipe);
+        remove_target(&mut bytes, ElectronOption::RemoteDebuggingPort);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new()
+            .patch_with_severity(ElectronOption::JsFlags, Severity::Required)
+            .patch_with_severity(ElectronOption::RemoteDebuggingPipe, Severity::Recommended)
+            .patch_with_severity(ElectronOption::RemoteDebuggingPort, Severity::Optional)
+            .patch_with_severity(ElectronOption::WaitForDebuggerChildren, Severity::Optional);
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.targets,
+            vec![
+                (ElectronOption::JsFlags.into(), PatchOutcome::NotPresent),
+                (
+                    ElectronOption::RemoteDebuggingPipe.into(),
+                    PatchOutcome::NotPresent
+                ),
+                (
+                    ElectronOption::RemoteDebuggingPort.into(),
+                    PatchOutcome::NotPresent
+                ),
+                (
+                    ElectronOption::WaitForDebuggerChildren.into(),
+                    PatchOutcome::Patched
+                ),
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![
+                HardeningWarning::TargetNotPresent {
+                    target: ElectronOption::JsFlags.into(),
+                    severity: Severity::Required,
+                },
+                HardeningWarning::TargetNotPresent {
+                    target: ElectronOption::RemoteDebuggingPipe.into(),
+                    severity: Severity::Recommended,
+                },
+            ]
+        );
+    }
+
+    fn versioned_app_bytes(version: &str) -> Vec<u8> {
+        let mut bytes = test_app_bytes();
+        bytes.extend_from_slice(format!("\0Electron/{}\0", version).as_bytes());
+
+        // Simulate a binary that doesn't contain the version-gated targets.
+        remove_target(&mut bytes, PatchTarget::NodeInspectWait);
+        remove_target(&mut bytes, ElectronOption::RemoteAllowOrigins);
+        bytes
+    }
+
+    #[test]
+    fn missing_targets_are_classified_by_version() {
+        let policy = HardeningPolicy::new()
+            .patch(PatchTarget::NodeInspectWait)
+            .patch(ElectronOption::RemoteAllowOrigins);
+
+        let mut bytes = versioned_app_bytes("20.3.0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.electron_version,
+            Some(ElectronVersion::new(20, 3, 0))
+        );
+        assert_eq!(
+            report.targets,
+            vec![
+                (PatchTarget::NodeInspectWait, PatchOutcome::Unavailable),
+                (
+                    ElectronOption::RemoteAllowOrigins.into(),
+                    PatchOutcome::Unavailable
+                ),
+            ]
+        );
+        assert!(report.warnings.is_empty());
+
+        let mut bytes = versioned_app_bytes("33.0.0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.targets,
+            vec![
+                (PatchTarget::NodeInspectWait, PatchOutcome::NotPresent),
+                (
+                    ElectronOption::RemoteAllowOrigins.into(),
+                    PatchOutcome::NotPresent
+                ),
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![
+                HardeningWarning::TargetNotPresent {
+                    target: PatchTarget::NodeInspectWait,
+                    severity: Severity::Required,
+                },
+                HardeningWarning::TargetNotPresent {
+                    target: ElectronOption::RemoteAllowOrigins.into(),
+                    severity: Severity::Required,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ineffective_fuse_is_a_warning() {
+        let policy = HardeningPolicy::new().set_fuse(Fuse::EncryptedCookies, true);
+
+        let mut bytes = versioned_app_bytes("12.2.3");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::IneffectiveFuse {
+                fuse: Fuse::EncryptedCookies,
+                version: ElectronVersion::new(12, 2, 3),
+            }]
+        );
+
+        let mut bytes = versioned_app_bytes("20.0.0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn non_asar_app_path_is_a_warning() {
+        let policy = HardeningPolicy::new().set_fuse(Fuse::OnlyLoadAppFromAsar, true);
+
+        let mut bytes = test_app_bytes();
+        let offset = bytes.len() + 1;
+        bytes.extend_from_slice(b"\0resources/app/main.js\0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::NonAsarAppPath {
+                path: "resources/app/".to_string(),
+                offset,
+            }]
+        );
+        assert_eq!(
+            report.fuses,
+            vec![(Fuse::OnlyLoadAppFromAsar, FuseStatus::Modified)]
+        );
+
+        // Nothing is warned about once the fuse is already enabled.
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert!(app.apply_policy(&policy).unwrap().warnings.is_empty());
+
+        // Asar archives themselves aren't a problem.
+        let mut bytes = test_app_bytes();
+        bytes.extend_from_slice(b"\0resources/app.asar\0");
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert!(app.apply_policy(&policy).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn node_debugging_patch_is_redundant_with_fuse() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::NodeCliInspect, false)
+            .patch(PatchTarget::NodeInspectBrk)
+            .patch(ElectronOption::JsFlags);
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::RedundantPatch(
+                PatchTarget::NodeInspectBrk
+            )]
+        );
+    }
+
+    #[test]
+    fn overlapping_targets_are_rejected_before_writing() {
+        let original = test_app_bytes();
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .patch(ElectronOption::RemoteDebuggingPort)
+            .patch(ElectronOption::JsFlags)
+            .patch(ElectronOption::RemoteDebuggingPort);
+
+        assert!(matches!(
+            app.apply_policy(&policy),
+            Err(PatcherError::OverlappingPatches { first, second })
+                if first.0 == ElectronOption::RemoteDebuggingPort.into() && first == second
+        ));
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn unmet_expectations_fail_before_writing() {
+        let original = test_app_bytes();
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::from_preset(Preset::Recommended).expect_min_patches(5);
+        assert_eq!(
+            app.apply_policy(&policy),
+            Err(PatcherError::TooFewPatches {
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(bytes, original);
+
+        // Removed fuses are only an error when every fuse is expected to be present.
+        let wire = FuseWire::locate(&original, 0).unwrap().range();
+        let mut bytes = original.clone();
+        bytes[wire.start + 2] = b'r';
+        let removed = bytes.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::from_preset(Preset::Minimal).expect_fuse_wire(true);
+        assert_eq!(
+            app.apply_policy(&policy),
+            Err(PatcherError::RemovedFuse(Fuse::NodeOptions))
+        );
+        assert_eq!(bytes, removed);
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy.expect_fuse_wire(false)).unwrap();
+        assert!(report
+            .warnings
+            .contains(&HardeningWarning::RemovedFuse(Fuse::NodeOptions)));
+    }
+
+    #[test]
+    fn already_patched_targets_meet_expectations() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let policy = HardeningPolicy::from_preset(Preset::Recommended)
+            .expect_min_patches(4)
+            .expect_fuse_wire(true);
+
+        app.apply_policy(&policy).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+        assert!(report
+            .targets
+            .iter()
+            .all(|(_, outcome)| *outcome == PatchOutcome::AlreadyPatched));
+
+        // Only patches count, so fuse changes alone always meet the expectation.
+        app.apply_fuses_only(&policy).unwrap();
+        assert_eq!(
+            app.apply_options_only(&policy.expect_min_patches(5)),
+            Err(PatcherError::TooFewPatches {
+                expected: 5,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn satisfied_preset_is_the_strongest() {
+        let original = test_app_bytes();
+
+        let mut bytes = original.clone();
+        let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(app.satisfied_preset(), None);
+
+        for &preset in &[Preset::Minimal, Preset::Recommended, Preset::Paranoid] {
+            let mut bytes =
+                harden_to_vec(&original, &HardeningPolicy::from_preset(preset)).unwrap();
+            let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+            assert_eq!(app.satisfied_preset(), Some(preset));
+        }
+
+        // A removed fuse can't be used, so it doesn't stop a preset from being satisfied.
+        let mut bytes =
+            harden_to_vec(&original, &HardeningPolicy::from_preset(Preset::Minimal)).unwrap();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start] = b'r';
+        let app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(app.satisfied_preset(), Some(Preset::Minimal));
+    }
+
+    #[test]
+    fn prior_states_are_reported() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+
+        let mut bytes = FUSE_BYTES.to_vec();
+        bytes.extend_from_slice(include_bytes!(
+            "../examples/fake_electron_flags_evil_patched.bin"
+        ));
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(report.prior_states.len(), policy.targets().len());
+        assert!(report
+            .prior_states
+            .iter()
+            .all(|(_, state)| *state == PatchState::PatchedByEvilFeaturePatcher));
+
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app.apply_policy(&policy).unwrap();
+        assert!(report
+            .prior_states
+            .iter()
+            .all(|(_, state)| *state == PatchState::Unpatched));
+
+        let report = app.apply_policy(&policy).unwrap();
+        assert!(report
+            .prior_states
+            .iter()
+            .all(|(_, state)| *state == PatchState::PatchedByThisTool));
+    }
+
+    #[test]
+    fn presets_build_on_each_other() {
+        let minimal = HardeningPolicy::from_preset(Preset::Minimal);
+        let recommended = HardeningPolicy::from_preset(Preset::Recommended);
+        let paranoid = HardeningPolicy::from_preset(Preset::Paranoid);
+
+        assert!(minimal.targets().is_empty());
+        assert!(recommended.fuses().starts_with(minimal.fuses()));
+        assert_eq!(paranoid.fuses(), recommended.fuses());
+        assert!(paranoid.targets().starts_with(recommended.targets()));
+        assert!(paranoid.targets().contains(&(
+            ElectronOption::UnsafelyTreatInsecureOriginAsSecure.into(),
+            Severity::Recommended
+        )));
+        assert!(recommended
+            .targets()
+            .iter()
+            .all(|(_, severity)| *severity == Severity::Required));
+
+        // Low severity behavior toggles are only patched by policies that ask for them.
+        for opt in [ElectronOption::DisableHttpCache, ElectronOption::DisableGpu].iter() {
+            assert!(paranoid
+                .targets()
+                .iter()
+                .all(|(target, _)| *target != PatchTarget::from(*opt)));
+        }
+    }
+
+    #[test]
+    fn v8_snapshot_fuse_is_left_alone_by_presets() {
+        // Changing this fuse affects how the application starts, so it's never part of a preset.
+        for &preset in &[Preset::Minimal, Preset::Recommended, Preset::Paranoid] {
+            let policy = HardeningPolicy::from_preset(preset);
+            assert!(policy
+                .fuses()
+                .iter()
+                .all(|(fuse, _)| *fuse != Fuse::LoadBrowserProcessSpecificV8Snapshot));
+        }
+
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app
+            .apply_policy(&HardeningPolicy::from_preset(Preset::Paranoid))
+            .unwrap();
+        assert!(report.final_fuses.contains(&(
+            Fuse::LoadBrowserProcessSpecificV8Snapshot,
+            FuseStatus::Present(true)
+        )));
+
+        let policy =
+            HardeningPolicy::new().set_fuse(Fuse::LoadBrowserProcessSpecificV8Snapshot, false);
+        let report = app.apply_policy(&policy).unwrap();
+        assert_eq!(
+            report.fuses,
+            vec![(
+                Fuse::LoadBrowserProcessSpecificV8Snapshot,
+                FuseStatus::Modified
+            )]
+        );
+    }
+
+    #[test]
+    fn paranoid_preset_applies_cleanly() {
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let report = app
+            .apply_policy(&HardeningPolicy::from_preset(Preset::Paranoid))
+            .unwrap();
+
+        assert!(report.warnings.is_empty());
+        assert!(report
+            .targets
+            .iter()
+            .all(|(_, outcome)| *outcome == PatchOutcome::Patched));
+    }
+
+    #[test]
+    fn removed_fuse_is_a_warning() {
+        let mut bytes = test_app_bytes();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        bytes[wire.start + 1] = b'r';
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let policy = HardeningPolicy::new().set_fuse(Fuse::EncryptedCookies, true);
+        let report = app.apply_policy(&policy).unwrap();
+
+        assert_eq!(
+            report.fuses,
+            vec![(Fuse::EncryptedCookies, FuseStatus::Removed)]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::RemovedFuse(Fuse::EncryptedCookies)]
+        );
+    }
+
+    #[test]
+    fn fuses_are_changed_in_every_wire_or_none() {
+        // A universal binary whose second slice was built without the newer fuses.
+        let mut bytes = FUSE_BYTES.to_vec();
+        let mut short_slice = FUSE_BYTES.to_vec();
+        let wire = FuseWire::locate(&short_slice, 0).unwrap().range();
+        short_slice[wire.start - 1] = 2;
+        bytes.extend_from_slice(&short_slice);
+        bytes.extend_from_slice(FLAG_BYTES);
+
+        let original = bytes.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.start_undo_journal();
+
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .set_fuse(Fuse::OnlyLoadAppFromAsar, true)
+            .patch(ElectronOption::JsFlags);
+        assert_eq!(
+            app.apply_policy(&policy),
+            Err(PatcherError::Binary(BinaryError::FuseDoesNotExist(
+                Fuse::OnlyLoadAppFromAsar
+            )))
+        );
+        assert_eq!(app.take_undo_journal(), Some(UndoJournal::new()));
+        assert!(bytes == original, "a slice was left hardened");
+    }
+
+    #[test]
+    fn unknown_fuse_positions_are_reported() {
+        let mut bytes = test_app_bytes();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        // Extend the wire into the padding after it, as if a newer Electron added fuses.
+        bytes[wire.start - 1] += 2;
+        bytes[wire.end] = b'1';
+        bytes[wire.end + 1] = b'0';
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        let report = app.apply_policy(&HardeningPolicy::new()).unwrap();
+
+        assert_eq!(
+            report.warnings,
+            vec![HardeningWarning::UnknownFusePositions(vec![
+                wire.len(),
+                wire.len() + 1
+            ])]
+        );
+    }
+
+    #[test]
+    fn harden_files_stays_under_the_cap() {
+        let dir = std::env::temp_dir().join(format!(
+            "electron-hardener-harden-files-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Binaries of different sizes, so some buffers are reused and others have to be replaced.
+        let mut originals = Vec::new();
+        let mut paths = Vec::new();
+        for i in 0..12 {
+            let mut bytes = test_app_bytes();
+            bytes.resize(bytes.len() + (i % 4) * 4096, 0);
+            let path = dir.join(format!("app-{}", i));
+            fs::write(&path, &bytes).unwrap();
+            originals.push(bytes);
+            paths.push(path);
+        }
+        paths.insert(5, dir.join("missing"));
+
+        let largest = originals.iter().map(Vec::len).max().unwrap();
+        let pool = BufferPool::new(largest * 2 + largest / 2);
+        let policy = HardeningPolicy::from_preset(Preset::Recommended);
+        let mut results = harden_files(&paths, &policy, &pool);
+        let mut hardened: Vec<_> = paths.iter().map(fs::read).collect();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(pool.peak_resident_bytes() <= pool.max_resident_bytes());
+        assert!(pool.peak_resident_bytes() >= largest);
+        assert!(pool.resident_bytes() <= pool.max_resident_bytes());
+
+        assert_eq!(results.len(), paths.len());
+        assert!(matches!(results.remove(5), Err(SourceError::Io(_))));
+        assert!(hardened.remove(5).is_err());
+        for ((result, hardened), original) in results.into_iter().zip(hardened).zip(&originals) {
+            assert!(result.unwrap().is_modified());
+            assert_eq!(hardened.unwrap(), harden_to_vec(original, &policy).unwrap());
+        }
+    }
+
+    #[test]
+    fn every_architecture_is_hardened() {
+        let slice = test_app_bytes();
+        let mut bytes = crate::fat::tests::fat_binary(&[&slice, &slice]);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        assert_eq!(app.architecture_count(), 2);
+        assert_eq!(app.wires().len(), 2);
+
+        let policy = HardeningPolicy::new()
+            .set_fuse(Fuse::RunAsNode, false)
+            .patch(ElectronOption::JsFlags)
+            .expect_min_patches(1);
+        app.apply_policy(&policy).unwrap();
+
+        for range in crate::fat::slices(&bytes).unwrap() {
+            let slice = ElectronApp::from_bytes_readonly(&bytes[range]).unwrap();
+            assert!(!slice.is_present(ElectronOption::JsFlags));
+            assert_eq!(
+                slice.get_fuse_status(Fuse::RunAsNode),
+                Ok(FuseStatus::Present(false))
+            );
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Reusing the buffers that binaries are read into when hardening many of them, while keeping the memory they use
+//! under a cap.
+//!
+//! See [harden_files](crate::harden_files) for how a pool is used.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// A set of buffers shared between the binaries hardened by [harden_files](crate::harden_files).
+///
+/// Buffers are returned to the pool once a binary is hardened, and reused for the next one that fits in them instead
+/// of allocating another. The bytes held by the pool, both the buffers in use and the ones waiting to be reused, are
+/// kept under `max_resident_bytes`: a binary that doesn't fit waits for others to finish first, which limits how many
+/// are hardened in parallel. A binary larger than the cap on its own is hardened once nothing else is.
+#[derive(Debug)]
+pub struct BufferPool {
+    max_resident_bytes: usize,
+    state: Mutex<PoolState>,
+    released: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    /// Buffers that aren't in use, cleared but keeping their capacity.
+    free: Vec<Vec<u8>>,
+    /// The capacity of every buffer held by the pool, in use or not.
+    resident_bytes: usize,
+    peak_resident_bytes: usize,
+}
+
+impl PoolState {
+    fn add_resident(&mut self, bytes: usize) {
+        self.resident_bytes += bytes;
+        self.peak_resident_bytes = self.peak_resident_bytes.max(self.resident_bytes);
+    }
+}
+
+impl BufferPool {
+    /// Creates an empty pool that holds at most `max_resident_bytes` at once.
+    pub fn new(max_resident_bytes: usize) -> Self {
+        Self {
+            max_resident_bytes,
+            state: Mutex::default(),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Returns the most bytes the pool holds at once, unless a single binary is larger.
+    pub fn max_resident_bytes(&self) -> usize {
+        self.max_resident_bytes
+    }
+
+    /// Returns the bytes currently held by the pool, including buffers waiting to be reused.
+    pub fn resident_bytes(&self) -> usize {
+        self.lock().resident_bytes
+    }
+
+    /// Returns the most bytes the pool has held at once since it was created.
+    pub fn peak_resident_bytes(&self) -> usize {
+        self.lock().peak_resident_bytes
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        // The state is consistent between statements, so a panic elsewhere doesn't leave it half-updated.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Takes an empty buffer that can hold `len` bytes without growing, waiting until it fits under the cap.
+    pub(crate) fn acquire(&self, len: usize) -> PooledBuffer<'_> {
+        let mut state = self.lock();
+        loop {
+            if let Some(index) = state.free.iter().position(|free| free.capacity() >= len) {
+                let buffer = state.free.swap_remove(index);
+                return PooledBuffer::new(self, buffer);
+            }
+
+            // None of the free buffers are large enough, so they're released to make room for one that is.
+            while state.resident_bytes + len > self.max_resident_bytes {
+                match state.free.pop() {
+                    Some(free) => state.resident_bytes -= free.capacity(),
+                    None => break,
+                }
+            }
+
+            if state.resident_bytes + len <= self.max_resident_bytes || state.resident_bytes == 0 {
+                let buffer = Vec::with_capacity(len);
+                state.add_resident(buffer.capacity());
+                return PooledBuffer::new(self, buffer);
+            }
+
+            state = self
+                .released
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn release(&self, mut buffer: Vec<u8>, accounted: usize) {
+        let mut state = self.lock();
+        // The buffer may have grown while it was in use.
+        state.resident_bytes -= accounted;
+        state.add_resident(buffer.capacity());
+
+        if state.resident_bytes <= self.max_resident_bytes {
+            buffer.clear();
+            state.free.push(buffer);
+        } else {
+            state.resident_bytes -= buffer.capacity();
+        }
+        drop(state);
+
+        self.released.notify_all();
+    }
+}
+
+/// A buffer taken from a [BufferPool], which is returned to it when dropped.
+pub(crate) struct PooledBuffer<'a> {
+    pool: &'a BufferPool,
+    buffer: Vec<u8>,
+    accounted: usize,
+}
+
+impl<'a> PooledBuffer<'a> {
+    fn new(pool: &'a BufferPool, buffer: Vec<u8>) -> Self {
+        Self {
+            pool,
+            accounted: buffer.capacity(),
+            buffer,
+        }
+    }
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        self.pool
+            .release(std::mem::take(&mut self.buffer), self.accounted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    #[test]
+    fn buffers_are_reused() {
+        let pool = BufferPool::new(100);
+
+        let first = pool.acquire(50);
+        let address = first.as_ptr();
+        drop(first);
+        let second = pool.acquire(40);
+
+        assert_eq!(second.as_ptr(), address);
+        assert!(second.is_empty());
+        assert_eq!(pool.resident_bytes(), 50);
+        assert_eq!(pool.peak_resident_bytes(), 50);
+    }
+
+    #[test]
+    fn small_buffers_make_room_for_larger_ones() {
+        let pool = BufferPool::new(100);
+
+        drop(pool.acquire(60));
+        let large = pool.acquire(80);
+
+        assert!(large.capacity() >= 80);
+        assert_eq!(pool.resident_bytes(), 80);
+        assert_eq!(pool.peak_resident_bytes(), 80);
+    }
+
+    #[test]
+    fn buffers_over_the_cap_wait_for_others() {
+        let pool = BufferPool::new(100);
+        let acquired = AtomicBool::new(false);
+
+        let first = pool.acquire(60);
+        thread::scope(|scope| {
+            let waiting = scope.spawn(|| {
+                let second = pool.acquire(60);
+                acquired.store(true, Ordering::SeqCst);
+                drop(second);
+            });
+
+            thread::sleep(Duration::from_millis(50));
+            assert!(!acquired.load(Ordering::SeqCst));
+            drop(first);
+            waiting.join().unwrap();
+        });
+
+        assert!(acquired.load(Ordering::SeqCst));
+        assert_eq!(pool.peak_resident_bytes(), 60);
+    }
+
+    #[test]
+    fn buffers_larger_than_the_cap_are_allowed_alone() {
+        let pool = BufferPool::new(100);
+
+        drop(pool.acquire(30));
+        let large = pool.acquire(150);
+
+        assert!(large.capacity() >= 150);
+        // The free buffer was released, so only the oversized one is held.
+        assert_eq!(pool.resident_bytes(), 150);
+        drop(large);
+        // It's too large to keep once it's returned.
+        assert_eq!(pool.resident_bytes(), 0);
+    }
+}
@@ -0,0 +1,341 @@
+//! Read-only assessment of how well an installed application is hardened.
+//!
+//! This is meant for auditing applications that can't be modified, such as third-party applications installed on a
+//! fleet of machines. Nothing is ever written, and binaries from unknown Electron forks or with unusual layouts are
+//! scored as well as possible instead of failing.
+
+use crate::{
+    fuses::FuseStatus,
+    patcher::{PatchState, PatchTarget},
+    AppLayout, ElectronApp, ElectronVersion, Fuse,
+};
+use memchr::memmem;
+use std::{
+    cmp::Reverse,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// How a fuse or debugging surface of an application was scored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum Exposure {
+    /// The fuse has its secure value, or the surface was patched out or doesn't exist in the application.
+    Hardened,
+    /// The fuse or surface can be used to tamper with the application.
+    Exposed,
+    /// The application couldn't be checked, such as when it's built with an Electron fork that lays out its binary
+    /// differently.
+    Unknown,
+}
+
+impl fmt::Display for Exposure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Exposure::Hardened => "hardened",
+            Exposure::Exposed => "exposed",
+            Exposure::Unknown => "unknown",
+        })
+    }
+}
+
+/// The hardening posture of an application, produced without modifying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PostureReport {
+    /// The binary that was assessed, if the report was made from a path.
+    pub binary: Option<PathBuf>,
+    /// The version of Electron the application was built with, if it could be detected.
+    pub electron_version: Option<ElectronVersion>,
+    /// How the application's code is packaged, if its resources directory could be found.
+    pub app_layout: Option<AppLayout>,
+    /// The score of every fuse known by this library.
+    pub fuses: Vec<(Fuse, Exposure)>,
+    /// The score of every patch target known by this library.
+    pub targets: Vec<(PatchTarget, Exposure)>,
+    /// Why parts of the application couldn't be checked.
+    pub problems: Vec<String>,
+}
+
+impl PostureReport {
+    /// Assesses the application at a path, which can be its binary, a macOS `.app` bundle, or the directory it's
+    /// installed in.
+    ///
+    /// When a directory is provided, the largest file directly inside of it (or inside `Contents/MacOS` for a bundle)
+    /// that contains a fuse wire is assessed. If none do, the largest file is assessed instead and its fuses are
+    /// scored as [Unknown](Exposure::Unknown).
+    ///
+    /// # Errors
+    ///
+    /// This function only returns an error if the path or a binary inside of it can't be read. Problems with the
+    /// binary itself are recorded in the report's [problems](PostureReport::problems).
+    pub fn assess<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        let (binary, contents) = if path.is_dir() {
+            Self::find_binary(path)?
+        } else {
+            (path.to_path_buf(), fs::read(path)?)
+        };
+
+        let mut report = Self::from_binary(&contents);
+        report.app_layout = AppLayout::for_binary(&binary);
+        report.binary = Some(binary);
+
+        Ok(report)
+    }
+
+    /// Assesses an application binary that's already been read.
+    pub fn from_binary(binary: &[u8]) -> Self {
+        let electron_version = ElectronVersion::detect(binary);
+        let mut problems = Vec::new();
+
+        // Every fuse wire is read, so that binaries containing several are only scored where they agree.
+        let fuses: Vec<(Fuse, Exposure)> = match ElectronApp::from_bytes_readonly(binary) {
+            Ok(app) => Fuse::ALL
+                .iter()
+                .map(|&fuse| {
+                    let exposure = match app.get_fuse_status(fuse) {
+                        Ok(FuseStatus::Present(value)) => {
+                            let effective = match (fuse.effective_since(), electron_version) {
+                                (Some(since), Some(version)) => version.major >= since,
+                                _ => true,
+                            };
+                            if value == fuse.secure_value() && (effective || !value) {
+                                Exposure::Hardened
+                            } else {
+                                Exposure::Exposed
+                            }
+                        }
+                        Ok(FuseStatus::Removed) => Exposure::Hardened,
+                        Ok(FuseStatus::Modified) => Exposure::Unknown,
+                        Err(e) => {
+                            problems.push(format!("{:?}: {}", fuse, e));
+                            Exposure::Unknown
+                        }
+                    };
+                    (fuse, exposure)
+                })
+                .collect(),
+            Err(e) => {
+                problems.push(format!("the fuse wire couldn't be read: {}", e));
+                Fuse::ALL
+                    .iter()
+                    .map(|&fuse| (fuse, Exposure::Unknown))
+                    .collect()
+            }
+        };
+
+        // The Node.js debugging flags and messages have no effect once the fuse disables them.
+        let node_cli_inspect_hardened = fuses.contains(&(Fuse::NodeCliInspect, Exposure::Hardened));
+
+        let targets = PatchTarget::ALL
+            .iter()
+            .map(|&target| {
+                let exposure = match target.patch_state(binary) {
+                    _ if node_cli_inspect_hardened && target.is_node_debugging() => {
+                        Exposure::Hardened
+                    }
+                    PatchState::Unpatched => Exposure::Exposed,
+                    PatchState::Absent => match electron_version {
+                        Some(version) if !target.is_available_in(version) => Exposure::Hardened,
+                        _ => Exposure::Unknown,
+                    },
+                    _ => Exposure::Hardened,
+                };
+                (target, exposure)
+            })
+            .collect();
+
+        Self {
+            binary: None,
+            electron_version,
+            app_layout: None,
+            fuses,
+            targets,
+            problems,
+        }
+    }
+
+    /// Returns how many fuses and patch targets were given a score.
+    pub fn count(&self, exposure: Exposure) -> usize {
+        let fuses = self.fuses.iter().filter(|(_, e)| *e == exposure).count();
+        let targets = self.targets.iter().filter(|(_, e)| *e == exposure).count();
+        fuses + targets
+    }
+
+    /// Finds the binary to assess inside of a directory.
+    fn find_binary(dir: &Path) -> io::Result<(PathBuf, Vec<u8>)> {
+        let macos_dir = dir.join("Contents").join("MacOS");
+        let dir = if macos_dir.is_dir() { &macos_dir } else { dir };
+
+        let mut candidates = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                candidates.push((metadata.len(), entry.path()));
+            }
+        }
+        candidates.sort_by_key(|&(len, _)| Reverse(len));
+
+        let mut largest = None;
+        for (_, path) in candidates {
+            let contents = fs::read(&path)?;
+            if memmem::find(&contents, Fuse::SENTINEL).is_some() {
+                return Ok((path, contents));
+            }
+            largest.get_or_insert((path, contents));
+        }
+
+        largest.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no application binary found in {}", dir.display()),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fuses::FuseWire,
+        patcher::{tests::test_app_bytes, ElectronOption},
+    };
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+    const FLAG_BYTES: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+
+    fn fuse_exposure(report: &PostureReport, fuse: Fuse) -> Exposure {
+        report.fuses.iter().find(|(f, _)| *f == fuse).unwrap().1
+    }
+
+    #[test]
+    fn complete_app_is_scored() {
+        let report = PostureReport::from_binary(&test_app_bytes());
+
+        assert_eq!(
+            report.count(Exposure::Exposed),
+            report.fuses.len() + report.targets.len()
+        );
+        assert!(report.problems.is_empty());
+    }
+
+    #[test]
+    fn node_debugging_is_hardened_by_fuse() {
+        let mut bytes = test_app_bytes();
+        let mut app = crate::ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_fuse_status(Fuse::NodeCliInspect, false).unwrap();
+
+        let report = PostureReport::from_binary(&bytes);
+        assert_eq!(
+            fuse_exposure(&report, Fuse::NodeCliInspect),
+            Exposure::Hardened
+        );
+
+        for (target, exposure) in &report.targets {
+            let expected = if target.is_node_debugging() {
+                Exposure::Hardened
+            } else {
+                Exposure::Exposed
+            };
+            assert_eq!(*exposure, expected, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn app_without_fuses_is_partially_scored() {
+        let report = PostureReport::from_binary(FLAG_BYTES);
+
+        assert!(report
+            .fuses
+            .iter()
+            .all(|(_, exposure)| *exposure == Exposure::Unknown));
+        assert_eq!(report.problems.len(), 1);
+
+        assert!(report
+            .targets
+            .contains(&(ElectronOption::JsFlags.into(), Exposure::Exposed)));
+        assert!(report
+            .targets
+            .contains(&(PatchTarget::NodeInspect, Exposure::Exposed)));
+    }
+
+    #[test]
+    fn short_fuse_wire_is_partially_scored() {
+        let mut bytes = test_app_bytes();
+        let wire = FuseWire::locate(&bytes, 0).unwrap().range();
+        // Only the first two fuses are left in the wire.
+        bytes[wire.start - 1] = 2;
+
+        let report = PostureReport::from_binary(&bytes);
+        assert_eq!(fuse_exposure(&report, Fuse::RunAsNode), Exposure::Exposed);
+        assert_eq!(
+            fuse_exposure(&report, Fuse::NodeCliInspect),
+            Exposure::Unknown
+        );
+        assert_eq!(report.problems.len(), 5);
+
+        // Without the fuse, the Node.js debugging flags are exposed.
+        assert!(report
+            .targets
+            .contains(&(PatchTarget::NodeInspect, Exposure::Exposed)));
+    }
+
+    #[test]
+    fn disagreeing_fuse_wires_are_unknown() {
+        let mut bytes = test_app_bytes();
+        bytes.extend_from_slice(FUSE_BYTES);
+        let mut app = crate::ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_fuse_status_in(1, Fuse::RunAsNode, false).unwrap();
+
+        let report = PostureReport::from_binary(&bytes);
+        assert_eq!(fuse_exposure(&report, Fuse::RunAsNode), Exposure::Unknown);
+        assert_eq!(fuse_exposure(&report, Fuse::NodeOptions), Exposure::Exposed);
+        assert_eq!(report.problems.len(), 1);
+    }
+
+    #[test]
+    fn patched_targets_are_hardened() {
+        let mut bytes = test_app_bytes();
+        let mut app = crate::ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+
+        let report = PostureReport::from_binary(&bytes);
+        assert!(report
+            .targets
+            .contains(&(ElectronOption::JsFlags.into(), Exposure::Hardened)));
+    }
+
+    #[test]
+    fn bundles_are_assessed() {
+        let bundle = std::env::temp_dir().join(format!(
+            "electron-hardener-posture-{}.app",
+            std::process::id()
+        ));
+        let contents = bundle.join("Contents");
+        fs::create_dir_all(contents.join("MacOS")).unwrap();
+        fs::create_dir_all(contents.join("Resources")).unwrap();
+        fs::write(contents.join("MacOS").join("App"), test_app_bytes()).unwrap();
+        fs::write(contents.join("MacOS").join("helper"), vec![0; 4096]).unwrap();
+        fs::write(contents.join("Resources").join("app.asar"), b"").unwrap();
+
+        let report = PostureReport::assess(&bundle);
+        let _ = fs::remove_dir_all(&bundle);
+        let report = report.unwrap();
+
+        assert_eq!(report.binary, Some(contents.join("MacOS").join("App")));
+        assert_eq!(report.app_layout, Some(AppLayout::Asar));
+        assert_eq!(report, {
+            let mut expected = PostureReport::from_binary(&test_app_bytes());
+            expected.binary = report.binary.clone();
+            expected.app_layout = report.app_layout;
+            expected
+        });
+
+        assert!(PostureReport::assess(&bundle).is_err());
+    }
+}
@@ -0,0 +1,510 @@
+//! Structured results from applying a [hardening policy](crate::HardeningPolicy) to an application.
+
+use crate::{
+    fuses::FuseStatus,
+    journal::{encode_hex, Modification},
+    patcher::{PatchOutcome, PatchState, PatchTarget, PatternMatch, DEFAULT_PATCH_FILL},
+    AppLayout, ElectronVersion, Fuse, Severity,
+};
+use std::{fmt, ops::Range};
+
+/// How many bytes on each side of a patched range are [captured](crate::ElectronApp::set_report_context) by default.
+pub(crate) const DEFAULT_CONTEXT_BYTES: usize = 16;
+
+/// A summary of everything that happened while [applying a policy](crate::ElectronApp::apply_policy).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct HardeningReport {
+    /// The fuse schema version found in the application.
+    pub fuse_schema_version: u8,
+    /// The version of Electron detected in the application, if it could be found.
+    pub electron_version: Option<ElectronVersion>,
+    /// The result of each fuse change requested by the policy, in policy order.
+    ///
+    /// If the application contains more than one fuse wire, a fuse is [Modified](FuseStatus::Modified) if it was
+    /// modified in any of them. Otherwise, its status in the first wire is used.
+    pub fuses: Vec<(Fuse, FuseStatus)>,
+    /// The result of each fuse change requested by the policy in each of the application's
+    /// [fuse wires](crate::ElectronApp::wires), indexed by wire.
+    pub wire_fuses: Vec<Vec<(Fuse, FuseStatus)>>,
+    /// The result of each patch requested by the policy, in policy order.
+    pub targets: Vec<(PatchTarget, PatchOutcome)>,
+    /// Where each patched target was found in the binary, and which of its patterns matched.
+    pub matches: Vec<(PatchTarget, PatternMatch)>,
+    /// The bytes surrounding each range that was patched, as they were before the policy was applied.
+    ///
+    /// See [set_report_context](crate::ElectronApp::set_report_context).
+    pub contexts: Vec<(PatchTarget, PatchContext)>,
+    /// The byte that the rest of each patched Electron option's name was overwritten with.
+    ///
+    /// See [set_patch_fill](crate::ElectronApp::set_patch_fill).
+    pub patch_fill: u8,
+    /// The status of every fuse in the first fuse wire after the policy was applied.
+    ///
+    /// Fuses that aren't present in the wire are left out.
+    pub final_fuses: Vec<(Fuse, FuseStatus)>,
+    /// The state of each target in the policy before it was applied, in policy order.
+    ///
+    /// This shows which targets were already patched out, and by which tool.
+    pub prior_states: Vec<(PatchTarget, PatchState)>,
+    /// Every [modification](crate::ElectronApp::modifications) made to the binary by the policy, in the order they
+    /// were made.
+    pub modifications: Vec<Modification>,
+    /// Advisory findings that didn't stop the policy from being applied.
+    ///
+    /// These represent the "best effort" nature of hardening. Consumers can decide which of these to treat as hard failures.
+    pub warnings: Vec<HardeningWarning>,
+}
+
+impl HardeningReport {
+    pub(crate) fn new(fuse_schema_version: u8) -> Self {
+        Self {
+            fuse_schema_version,
+            electron_version: None,
+            fuses: Vec::new(),
+            wire_fuses: Vec::new(),
+            targets: Vec::new(),
+            matches: Vec::new(),
+            contexts: Vec::new(),
+            patch_fill: DEFAULT_PATCH_FILL,
+            final_fuses: Vec::new(),
+            prior_states: Vec::new(),
+            modifications: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Compares this report to the report of a previously approved build, returning every way this build is less
+    /// hardened than it, or `None` if it's at least as hardened.
+    ///
+    /// A fuse has regressed if it had its [secure value](Fuse::secure_value) in the baseline, but now has the other
+    /// value or isn't present. A patch target has regressed if it was patched out in the baseline, but this report
+    /// doesn't show it as patched out. Targets that couldn't be found at all are considered regressions, because
+    /// a changed binary layout can leave them enabled without matching their patterns.
+    pub fn is_regression_from(&self, baseline: &HardeningReport) -> Option<Vec<Regression>> {
+        let mut regressions = Vec::new();
+
+        for &(fuse, status) in &baseline.final_fuses {
+            if status != FuseStatus::Present(fuse.secure_value()) {
+                continue;
+            }
+
+            let current = self
+                .final_fuses
+                .iter()
+                .find(|(current, _)| *current == fuse)
+                .map(|&(_, status)| status);
+
+            if matches!(current, None | Some(FuseStatus::Present(_))) && current != Some(status) {
+                regressions.push(Regression::Fuse(fuse));
+            }
+        }
+
+        for &(target, _) in &baseline.targets {
+            if baseline.is_patched_out(target) && !self.is_patched_out(target) {
+                regressions.push(Regression::Target(target));
+            }
+        }
+
+        if regressions.is_empty() {
+            None
+        } else {
+            Some(regressions)
+        }
+    }
+
+    /// Returns `true` if applying the policy changed any fuse or patched out any target.
+    ///
+    /// When this is `false`, everything in the policy was already applied and the binary was left untouched.
+    pub fn is_modified(&self) -> bool {
+        self.fuses
+            .iter()
+            .any(|(_, status)| *status == FuseStatus::Modified)
+            || self
+                .targets
+                .iter()
+                .any(|(_, outcome)| *outcome == PatchOutcome::Patched)
+    }
+
+    /// Returns whether a target is known to be patched out after the policy was applied.
+    fn is_patched_out(&self, target: PatchTarget) -> bool {
+        let outcome = self
+            .targets
+            .iter()
+            .find(|(t, _)| *t == target)
+            .map(|(_, outcome)| outcome);
+        let prior_state = self
+            .prior_states
+            .iter()
+            .find(|(t, _)| *t == target)
+            .map(|(_, state)| state);
+
+        match outcome {
+            Some(PatchOutcome::Patched)
+            | Some(PatchOutcome::AlreadyPatched)
+            | Some(PatchOutcome::Unavailable) => true,
+            Some(_) => matches!(
+                prior_state,
+                Some(PatchState::PatchedByThisTool) | Some(PatchState::PatchedByEvilFeaturePatcher)
+            ),
+            None => false,
+        }
+    }
+}
+
+/// The bytes around a range that was patched, kept so that unexpected matches can be diagnosed after the fact
+/// without the original binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct PatchContext {
+    /// The range of the binary that was modified.
+    pub range: Range<usize>,
+    /// The bytes before the range, hex-encoded.
+    pub before: String,
+    /// The bytes after the range, hex-encoded.
+    pub after: String,
+    /// The bytes before the range, with anything that isn't printable ASCII shown as `.`.
+    pub before_text: String,
+    /// The bytes after the range, with anything that isn't printable ASCII shown as `.`.
+    pub after_text: String,
+}
+
+impl PatchContext {
+    /// Captures up to `len` bytes on each side of `range`, stopping at the ends of the binary.
+    pub(crate) fn capture(binary: &[u8], range: Range<usize>, len: usize) -> Self {
+        let before = &binary[range.start.saturating_sub(len)..range.start];
+        let after = &binary[range.end..range.end.saturating_add(len).min(binary.len())];
+
+        Self {
+            range,
+            before: encode_hex(before),
+            after: encode_hex(after),
+            before_text: printable(before),
+            after_text: printable(after),
+        }
+    }
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// A patch target that is patched out in some of a set of related binaries, such as the executables in an
+/// application bundle, but still present in others.
+///
+/// This usually means a previous hardening run was interrupted, or used a different policy for some of the binaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct MixedTarget {
+    /// The target with mixed states.
+    pub target: PatchTarget,
+    /// The indexes of the binaries that have it patched out.
+    pub hardened: Vec<usize>,
+    /// The indexes of the binaries that still contain it.
+    pub unhardened: Vec<usize>,
+}
+
+impl MixedTarget {
+    /// Compares the [patch states](crate::ElectronApp::patch_states) of several binaries, returning every target
+    /// that's patched out in some of them but [unpatched](PatchState::Unpatched) in others.
+    ///
+    /// Binaries are referred to by their index in `states`. Targets that are [absent](PatchState::Absent) from a
+    /// binary don't count towards either side.
+    pub fn find(states: &[Vec<(PatchTarget, PatchState)>]) -> Vec<MixedTarget> {
+        let mut mixed: Vec<MixedTarget> = Vec::new();
+
+        for (binary, binary_states) in states.iter().enumerate() {
+            for &(target, state) in binary_states {
+                let index = match mixed.iter().position(|m| m.target == target) {
+                    Some(index) => index,
+                    None => {
+                        mixed.push(MixedTarget {
+                            target,
+                            hardened: Vec::new(),
+                            unhardened: Vec::new(),
+                        });
+                        mixed.len() - 1
+                    }
+                };
+
+                match state {
+                    PatchState::PatchedByThisTool | PatchState::PatchedByEvilFeaturePatcher => {
+                        mixed[index].hardened.push(binary)
+                    }
+                    PatchState::Unpatched => mixed[index].unhardened.push(binary),
+                    PatchState::Absent => {}
+                }
+            }
+        }
+
+        mixed.retain(|m| !m.hardened.is_empty() && !m.unhardened.is_empty());
+        mixed
+    }
+}
+
+/// A way in which an application is less hardened than a [baseline](HardeningReport::is_regression_from).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum Regression {
+    /// The fuse had its secure value in the baseline, but no longer does.
+    Fuse(Fuse),
+    /// The patch target was patched out in the baseline, but isn't known to be anymore.
+    Target(PatchTarget),
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regression::Fuse(fuse) => write!(
+                f,
+                "The {} fuse no longer has the secure value it had in the baseline",
+                fuse
+            ),
+            Regression::Target(target) => write!(
+                f,
+                "The patch target {} was patched out in the baseline, but isn't anymore",
+                target
+            ),
+        }
+    }
+}
+
+/// An advisory finding produced while hardening an application.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum HardeningWarning {
+    /// The policy requested a change to a fuse that is marked as removed in the binary, so it was left alone.
+    RemovedFuse(Fuse),
+    /// The fuse wire contains positions that don't correspond to any fuse known by this library.
+    ///
+    /// This usually means the application was built with a newer version of Electron that added new fuses.
+    UnknownFusePositions(Vec<usize>),
+    /// The policy enabled a fuse whose feature isn't implemented by the detected version of Electron, so enabling
+    /// it has no effect.
+    IneffectiveFuse {
+        /// The fuse that was enabled.
+        fuse: Fuse,
+        /// The detected version of Electron.
+        version: ElectronVersion,
+    },
+    /// A target the policy requested to patch wasn't present in the binary.
+    ///
+    /// This isn't produced for [Optional](Severity::Optional) targets.
+    TargetNotPresent {
+        /// The target that was missing.
+        target: PatchTarget,
+        /// How important it was for the target to be found.
+        severity: Severity,
+    },
+    /// A Node.JS debugging flag or message was patched even though the [NodeCliInspect](Fuse::NodeCliInspect)
+    /// fuse already prevents it from being used.
+    RedundantPatch(PatchTarget),
+    /// The policy enabled the [OnlyLoadAppFromAsar](Fuse::OnlyLoadAppFromAsar) fuse, but the binary references a path
+    /// that suggests it loads its code from outside of an asar archive.
+    ///
+    /// This is a heuristic. If it's correct, the application will fail to start.
+    NonAsarAppPath {
+        /// The path that was found.
+        path: String,
+        /// The position of the path in the binary.
+        offset: usize,
+    },
+    /// The policy enabled the [OnlyLoadAppFromAsar](Fuse::OnlyLoadAppFromAsar) fuse, but the application's
+    /// [layout](crate::ElectronApp::set_app_layout) shows that its code is loaded from an `app` directory.
+    ///
+    /// If the layout is [Directory](AppLayout::Directory), the application will fail to start. If it's
+    /// [Both](AppLayout::Both), it will start loading the code in `app.asar` instead.
+    UnpackedApp(AppLayout),
+    /// A [compiled policy](crate::CompiledPolicy) was applied to a binary whose layout differs from its reference
+    /// binary, so the binary was searched from scratch instead.
+    ReferenceLayoutMismatch {
+        /// The first position where the binary didn't match what was recorded from the reference.
+        offset: usize,
+    },
+    /// The application is a macOS binary with a [code signature](crate::ElectronApp::has_code_signature), which
+    /// was invalidated by modifying it.
+    ///
+    /// The application has to be signed again before macOS will launch it.
+    CodeSignatureInvalidated,
+}
+
+impl fmt::Display for HardeningWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardeningWarning::RemovedFuse(fuse) => {
+                write!(
+                    f,
+                    "The {} fuse is marked as removed and was not modified",
+                    fuse
+                )
+            }
+            HardeningWarning::UnknownFusePositions(positions) => write!(
+                f,
+                "The fuse wire contains unknown fuses at positions {:?}",
+                positions
+            ),
+            HardeningWarning::IneffectiveFuse { fuse, version } => write!(
+                f,
+                "The {} fuse was enabled, but Electron {} does not implement it",
+                fuse, version.major
+            ),
+            HardeningWarning::TargetNotPresent { target, severity } => write!(
+                f,
+                "The {} patch target {} wasn't present",
+                severity, target
+            ),
+            HardeningWarning::RedundantPatch(target) => write!(
+                f,
+                "The patch target {} is redundant because the {} fuse is disabled",
+                target,
+                Fuse::NodeCliInspect
+            ),
+            HardeningWarning::NonAsarAppPath { path, offset } => write!(
+                f,
+                "The OnlyLoadAppFromAsar fuse was enabled, but the binary references the non-asar app path {:?} at offset {}, so it may fail to start",
+                path, offset
+            ),
+            HardeningWarning::UnpackedApp(layout) => write!(
+                f,
+                "The OnlyLoadAppFromAsar fuse was enabled, but the application's code is loaded from an app directory ({:?})",
+                layout
+            ),
+            HardeningWarning::ReferenceLayoutMismatch { offset } => write!(
+                f,
+                "The binary's layout differs from the compiled policy's reference binary at offset {}, so it was searched in full",
+                offset
+            ),
+            HardeningWarning::CodeSignatureInvalidated => write!(
+                f,
+                "The binary's code signature was invalidated and it must be signed again"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        patcher::{tests::test_app_bytes, ElectronOption},
+        ElectronApp, HardeningPolicy, Preset,
+    };
+
+    fn report_for(bytes: &mut [u8], preset: Preset) -> HardeningReport {
+        ElectronApp::from_bytes(bytes)
+            .unwrap()
+            .apply_policy(&HardeningPolicy::from_preset(preset))
+            .unwrap()
+    }
+
+    #[test]
+    fn equal_hardening_is_not_a_regression() {
+        let baseline = report_for(&mut test_app_bytes(), Preset::Recommended);
+        let current = report_for(&mut test_app_bytes(), Preset::Recommended);
+        assert_eq!(current.is_regression_from(&baseline), None);
+
+        // Targets that were already patched out still count as patched out.
+        let mut bytes = test_app_bytes();
+        report_for(&mut bytes, Preset::Paranoid);
+        let rerun = report_for(&mut bytes, Preset::Paranoid);
+        assert_eq!(rerun.is_regression_from(&baseline), None);
+    }
+
+    #[test]
+    fn weaker_hardening_is_a_regression() {
+        let baseline = report_for(&mut test_app_bytes(), Preset::Paranoid);
+        let current = report_for(&mut test_app_bytes(), Preset::Minimal);
+
+        let regressions = current.is_regression_from(&baseline).unwrap();
+        assert!(regressions.contains(&Regression::Fuse(Fuse::OnlyLoadAppFromAsar)));
+        assert!(
+            regressions.contains(&Regression::Target(PatchTarget::ElectronOption(
+                crate::patcher::ElectronOption::JsFlags
+            )))
+        );
+        assert_eq!(regressions.len(), 1 + 9);
+
+        // The baseline only exceeds the preset through its fuses, which a preset check can't catch.
+        let mut bytes = test_app_bytes();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.set_fuse_status(Fuse::EncryptedCookies, true).unwrap();
+        let baseline = app
+            .apply_policy(&HardeningPolicy::from_preset(Preset::Minimal))
+            .unwrap();
+
+        assert_eq!(
+            current.is_regression_from(&baseline),
+            Some(vec![Regression::Fuse(Fuse::EncryptedCookies)])
+        );
+    }
+
+    #[test]
+    fn mixed_targets_are_found_and_repaired() {
+        let mut framework = test_app_bytes();
+        let mut helper = test_app_bytes();
+        let mut untouched = test_app_bytes();
+        report_for(&mut framework, Preset::Paranoid);
+        report_for(&mut helper, Preset::Recommended);
+
+        let states = |binaries: Vec<&mut [u8]>| -> Vec<_> {
+            binaries
+                .into_iter()
+                .map(|bytes| ElectronApp::from_bytes(bytes).unwrap().patch_states())
+                .collect()
+        };
+
+        let mixed = MixedTarget::find(&states(vec![&mut framework, &mut helper, &mut untouched]));
+        let paranoid_only = [
+            PatchTarget::from(ElectronOption::RemoteAllowOrigins),
+            PatchTarget::from(ElectronOption::UnsafelyTreatInsecureOriginAsSecure),
+            PatchTarget::from(ElectronOption::CrashServerUrl),
+            PatchTarget::from(ElectronOption::CrashDumpsDir),
+            PatchTarget::from(ElectronOption::FullMemoryCrashReport),
+        ];
+        assert_eq!(mixed.len(), 9);
+        for m in &mixed {
+            if paranoid_only.contains(&m.target) {
+                assert_eq!(
+                    (m.hardened.clone(), m.unhardened.clone()),
+                    (vec![0], vec![1, 2])
+                );
+            } else {
+                assert_eq!(
+                    (m.hardened.clone(), m.unhardened.clone()),
+                    (vec![0, 1], vec![2])
+                );
+            }
+        }
+
+        // Patching the mixed targets out of the stragglers makes the binaries consistent.
+        for (i, straggler) in [&mut helper, &mut untouched].iter_mut().enumerate() {
+            let repair = mixed
+                .iter()
+                .filter(|m| m.unhardened.contains(&(i + 1)))
+                .fold(HardeningPolicy::new(), |policy, m| policy.patch(m.target));
+            ElectronApp::from_bytes(straggler)
+                .unwrap()
+                .apply_policy(&repair)
+                .unwrap();
+        }
+
+        assert_eq!(
+            MixedTarget::find(&states(vec![&mut framework, &mut helper, &mut untouched])),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Checks of planned patches against the sections of the binary's object file, and searches restricted to them.
+
+use crate::{fuses::FuseWire, patcher::PlannedPatch, BinaryError, ElectronApp, PatcherError};
+use object::{Object, ObjectSection, SectionKind};
+use std::{convert::TryFrom, ops::Range};
+
+/// A section of an object file, and where its contents are in the file.
+struct Section {
+    name: String,
+    range: Range<usize>,
+}
+
+/// Returns the sections of a binary that `include` accepts the kind of, or `None` if it can't be parsed as an
+/// object file.
+///
+/// Universal macOS binaries and other containers aren't parsed, so they aren't checked.
+fn sections(binary: &[u8], include: fn(SectionKind) -> bool) -> Option<Vec<Section>> {
+    let file = object::File::parse(binary).ok()?;
+
+    Some(
+        file.sections()
+            .filter(|section| include(section.kind()))
+            .filter_map(|section| {
+                let (start, len) = section.file_range()?;
+                let start = usize::try_from(start).ok()?;
+                let len = usize::try_from(len).ok()?;
+
+                Some(Section {
+                    name: section.name().unwrap_or("<unnamed>").to_string(),
+                    range: start..(start + len),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Returns the executable sections of a binary, or `None` if it can't be parsed as an object file.
+fn code_sections(binary: &[u8]) -> Option<Vec<Section>> {
+    sections(binary, |kind| kind == SectionKind::Text)
+}
+
+/// Returns whether a section can contain read-only data, such as Electron's command line switches.
+fn is_read_only_data(kind: SectionKind) -> bool {
+    matches!(
+        kind,
+        SectionKind::ReadOnlyData | SectionKind::ReadOnlyDataWithRel | SectionKind::ReadOnlyString
+    )
+}
+
+/// Returns the read-only data sections of a binary, such as `__cstring` in Mach-O files and `.rdata` in PE files,
+/// along with their names.
+///
+/// The sections of each slice of a universal binary are listed together, in the order of the slices. `None` is
+/// returned if any of them can't be parsed as an object file or has no read-only data, so that it's searched in full.
+pub(crate) fn string_sections(
+    binary: &[u8],
+    slices: &[Range<usize>],
+) -> Option<(Vec<Range<usize>>, Vec<String>)> {
+    let whole = 0..binary.len();
+    let slices = if slices.is_empty() {
+        std::slice::from_ref(&whole)
+    } else {
+        slices
+    };
+
+    let mut ranges = Vec::new();
+    let mut names = Vec::new();
+    for slice in slices {
+        let found: Vec<Section> = sections(&binary[slice.clone()], is_read_only_data)?
+            .into_iter()
+            .filter(|section| !section.range.is_empty())
+            .collect();
+        if found.is_empty() {
+            return None;
+        }
+
+        for section in found {
+            ranges.push((section.range.start + slice.start)..(section.range.end + slice.start));
+            names.push(section.name);
+        }
+    }
+
+    Some((ranges, names))
+}
+
+/// Returns whether a section can contain the fuse wire, which some platforms store with writable data.
+fn is_data(kind: SectionKind) -> bool {
+    kind == SectionKind::Data || is_read_only_data(kind)
+}
+
+impl<'a> ElectronApp<'a> {
+    /// Constructs a new [electron app](Self) like [from_bytes](Self::from_bytes), but only searches the sections of
+    /// the binary's object file that are expected to contain what's being searched for.
+    ///
+    /// The fuse wire is only searched for in data sections, so copies of it in code, embedded resources or V8
+    /// snapshots are never used. Patch targets are always searched for in read-only data sections when the binary
+    /// can be parsed, as described in [restrict_search_to_sections](Self::restrict_search_to_sections). Binaries
+    /// that can't be parsed as an ELF, PE or Mach-O file, such as universal macOS binaries, are searched in full like
+    /// [from_bytes](Self::from_bytes) does.
+    ///
+    /// # Errors
+    ///
+    /// This function returns the same errors as [from_bytes](Self::from_bytes). If none of the data sections contain
+    /// a fuse wire, [NoSentinel](BinaryError::NoSentinel) is returned even if the rest of the binary contains one.
+    pub fn from_bytes_with_layout(
+        application_bytes: &'a mut [u8],
+    ) -> Result<ElectronApp<'a>, PatcherError> {
+        FuseWire::check_input_len(application_bytes.len())?;
+
+        let data = match sections(application_bytes, is_data) {
+            Some(data) => data,
+            None => return Self::from_bytes(application_bytes),
+        };
+
+        let mut wires = Vec::new();
+        let mut rejection = None;
+        for section in &data {
+            let contents = match application_bytes.get(section.range.clone()) {
+                Some(contents) if !contents.is_empty() => contents,
+                _ => continue,
+            };
+
+            match FuseWire::locate_all(contents, section.range.start) {
+                Ok(found) => wires.extend(found),
+                Err(PatcherError::Binary(BinaryError::NoSentinel)) => {}
+                Err(e) => {
+                    rejection.get_or_insert(e);
+                }
+            }
+        }
+
+        if wires.is_empty() {
+            return Err(rejection.unwrap_or_else(|| BinaryError::NoSentinel.into()));
+        }
+
+        Ok(Self::with_wires(application_bytes, wires))
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Controls whether patch targets are only searched for in the read-only data sections of the binary.
+    ///
+    /// When the binary can be parsed as an ELF, PE or Mach-O file (including each architecture of a universal
+    /// binary), Electron's command line switches are only searched for where strings are stored, such as Mach-O's
+    /// `__TEXT,__cstring` and PE's `.rdata` sections. This is faster for large binaries, and avoids false positives
+    /// in code or embedded resources. It's enabled by default, and binaries that can't be parsed are always searched
+    /// in full.
+    ///
+    /// The section each match was found in is reported by [scan](Self::scan).
+    pub fn restrict_search_to_sections(&mut self, restrict: bool) {
+        self.restrict_search = restrict;
+    }
+
+    /// Returns the name of the search section containing an offset, if the binary's sections are known.
+    pub(crate) fn section_name(&self, offset: usize) -> Option<&str> {
+        let sections = self.search_sections.as_ref()?;
+        let index = sections
+            .iter()
+            .position(|section| section.contains(&offset))?;
+        Some(&self.section_names[index])
+    }
+
+    /// Allows patch targets found inside of executable sections of the binary to be patched.
+    ///
+    /// By default, if the binary is an object file that can be parsed, patching a target found in one of its code
+    /// sections fails with [PatchInCodeSection](PatcherError::PatchInCodeSection). Options are always stored as data,
+    /// so a match inside of code is almost certainly a false positive that would corrupt instructions.
+    pub fn allow_code_patches(&mut self, allow: bool) {
+        self.allow_code_patches = allow;
+    }
+
+    /// Returns an error if any of the patches would modify an executable section, unless that's been allowed.
+    pub(crate) fn check_code_patches(&self, planned: &[PlannedPatch]) -> Result<(), PatcherError> {
+        if self.allow_code_patches || planned.is_empty() {
+            return Ok(());
+        }
+
+        let sections = match code_sections(&self.contents) {
+            Some(sections) => sections,
+            None => return Ok(()),
+        };
+
+        for patch in planned {
+            let overlapping = sections.iter().find(|section| {
+                patch.range.start < section.range.end && section.range.start < patch.range.end
+            });
+
+            if let Some(section) = overlapping {
+                return Err(PatcherError::PatchInCodeSection {
+                    target: patch.target,
+                    section: section.name.clone(),
+                    range: patch.range.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        patcher::{ElectronOption, PatchTarget},
+        HardeningPolicy,
+    };
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+
+    /// Builds a minimal 64-bit ELF file with a `.text` and a `.rodata` section.
+    fn elf(text: &[u8], rodata: &[u8]) -> Vec<u8> {
+        const HEADER_LEN: usize = 64;
+        const SECTION_HEADER_LEN: usize = 64;
+        let shstrtab = b"\0.text\0.rodata\0.shstrtab\0";
+
+        let text_offset = HEADER_LEN;
+        let rodata_offset = text_offset + text.len();
+        let shstrtab_offset = rodata_offset + rodata.len();
+        // Section headers have to be aligned.
+        let section_headers_offset = (shstrtab_offset + shstrtab.len() + 7) & !7;
+
+        let mut file = Vec::new();
+        file.extend_from_slice(b"\x7fELF\x02\x01\x01");
+        file.resize(16, 0);
+        file.extend_from_slice(&2u16.to_le_bytes()); // e_type: executable
+        file.extend_from_slice(&62u16.to_le_bytes()); // e_machine: x86-64
+        file.extend_from_slice(&1u32.to_le_bytes()); // e_version
+        file.extend_from_slice(&0u64.to_le_bytes()); // e_entry
+        file.extend_from_slice(&0u64.to_le_bytes()); // e_phoff
+        file.extend_from_slice(&(section_headers_offset as u64).to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+        file.extend_from_slice(&(HEADER_LEN as u16).to_le_bytes());
+        file.extend_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        file.extend_from_slice(&0u16.to_le_bytes()); // e_phnum
+        file.extend_from_slice(&(SECTION_HEADER_LEN as u16).to_le_bytes());
+        file.extend_from_slice(&4u16.to_le_bytes()); // e_shnum
+        file.extend_from_slice(&3u16.to_le_bytes()); // e_shstrndx
+
+        file.extend_from_slice(text);
+        file.extend_from_slice(rodata);
+        file.extend_from_slice(shstrtab);
+        file.resize(section_headers_offset, 0);
+
+        // Name offset, type, flags, file offset and size of each section.
+        let sections: [(u32, u32, u64, usize, usize); 4] = [
+            (0, 0, 0, 0, 0),
+            (1, 1, 0x6, text_offset, text.len()), // PROGBITS, ALLOC | EXECINSTR
+            (7, 1, 0x2, rodata_offset, rodata.len()), // PROGBITS, ALLOC
+            (15, 3, 0, shstrtab_offset, shstrtab.len()), // STRTAB
+        ];
+        for (name, kind, flags, offset, size) in sections.iter().copied() {
+            file.extend_from_slice(&name.to_le_bytes());
+            file.extend_from_slice(&kind.to_le_bytes());
+            file.extend_from_slice(&flags.to_le_bytes());
+            file.extend_from_slice(&0u64.to_le_bytes()); // sh_addr
+            file.extend_from_slice(&(offset as u64).to_le_bytes());
+            file.extend_from_slice(&(size as u64).to_le_bytes());
+            file.extend_from_slice(&0u32.to_le_bytes()); // sh_link
+            file.extend_from_slice(&0u32.to_le_bytes()); // sh_info
+            file.extend_from_slice(&1u64.to_le_bytes()); // sh_addralign
+            file.extend_from_slice(&0u64.to_le_bytes()); // sh_entsize
+        }
+
+        file
+    }
+
+    #[test]
+    fn code_sections_are_found() {
+        let sections = code_sections(&elf(b"\xc3", b"data")).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, ".text");
+        assert_eq!(sections[0].range, 64..65);
+
+        assert!(code_sections(FUSE_BYTES).is_none());
+    }
+
+    #[test]
+    fn patches_in_code_sections_are_refused() {
+        // The decoy is in the code, and the real option only appears after it.
+        let decoy = b"\x48\x89\xe5\0js-flags\0\xc3";
+        let mut rodata = FUSE_BYTES.to_vec();
+        rodata.extend_from_slice(b"\0js-flags\0");
+
+        let original = elf(decoy, &rodata);
+        let mut bytes = original.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.restrict_search_to_sections(false);
+
+        let policy = HardeningPolicy::new().patch(ElectronOption::JsFlags);
+        assert_eq!(
+            app.apply_policy(&policy),
+            Err(PatcherError::PatchInCodeSection {
+                target: PatchTarget::from(ElectronOption::JsFlags),
+                section: ".text".to_string(),
+                range: 68..76,
+            })
+        );
+        assert!(matches!(
+            app.patch_option(ElectronOption::JsFlags),
+            Err(PatcherError::PatchInCodeSection { .. })
+        ));
+        assert!(bytes == original, "the code section was modified");
+    }
+
+    #[test]
+    fn code_patches_can_be_allowed() {
+        let mut bytes = elf(b"\0js-flags\0", FUSE_BYTES);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.restrict_search_to_sections(false);
+        app.allow_code_patches(true);
+
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+        assert_eq!(&bytes[64..70], b"\0xx\r\nx");
+    }
+
+    #[test]
+    fn searches_are_restricted_to_data_sections() {
+        // The code contains copies of the fuse wire and option, which are only skipped when the layout is used.
+        let mut text = FUSE_BYTES.to_vec();
+        text.extend_from_slice(b"\0js-flags\0");
+        let mut rodata = FUSE_BYTES.to_vec();
+        rodata.extend_from_slice(b"\0js-flags\0");
+        let rodata_range = (64 + text.len())..(64 + text.len() + rodata.len());
+
+        let mut bytes = elf(&text, &rodata);
+        assert_eq!(
+            ElectronApp::from_bytes(&mut bytes).unwrap().wires().len(),
+            2
+        );
+
+        let mut app = ElectronApp::from_bytes_with_layout(&mut bytes).unwrap();
+        assert_eq!(app.wires().len(), 1);
+        assert!(rodata_range.contains(&app.wires()[0].range().start));
+
+        let found = app.scan(ElectronOption::JsFlags).unwrap();
+        assert!(rodata_range.contains(&found.range.start));
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+        assert_eq!(
+            &bytes[(rodata_range.end - 10)..rodata_range.end],
+            b"\0xx\r\nxxxx\0"
+        );
+        assert_eq!(
+            &bytes[(64 + text.len() - 10)..(64 + text.len())],
+            b"\0js-flags\0"
+        );
+    }
+
+    #[test]
+    fn searches_are_restricted_to_string_sections_by_default() {
+        let decoy = b"\x48\x89\xe5\0js-flags\0\xc3";
+        let mut rodata = FUSE_BYTES.to_vec();
+        rodata.extend_from_slice(b"\0js-flags\0");
+        let mut bytes = elf(decoy, &rodata);
+        let rodata_start = 64 + decoy.len();
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let found = app.scan(ElectronOption::JsFlags).unwrap();
+        assert_eq!(found.section.as_deref(), Some(".rodata"));
+        assert!(found.range.start > rodata_start);
+
+        let report = app
+            .apply_policy(&HardeningPolicy::new().patch(ElectronOption::JsFlags))
+            .unwrap();
+        assert_eq!(report.matches[0].1.section.as_deref(), Some(".rodata"));
+        assert_eq!(&bytes[64..(64 + decoy.len())], &decoy[..]);
+    }
+
+    #[test]
+    fn layout_falls_back_to_whole_binary() {
+        let mut bytes = FUSE_BYTES.to_vec();
+        bytes.extend_from_slice(b"\0js-flags\0");
+        let mut app = ElectronApp::from_bytes_with_layout(&mut bytes).unwrap();
+        assert_eq!(app.wires().len(), 1);
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+
+        // An object file without a fuse wire in its data sections isn't an Electron application.
+        assert!(matches!(
+            ElectronApp::from_bytes_with_layout(&mut elf(FUSE_BYTES, b"\0js-flags\0")),
+            Err(PatcherError::Binary(BinaryError::NoSentinel))
+        ));
+    }
+
+    #[test]
+    fn patches_in_data_sections_are_allowed() {
+        let mut rodata = FUSE_BYTES.to_vec();
+        rodata.extend_from_slice(b"\0js-flags\0");
+        let mut bytes = elf(b"\x48\x89\xe5\xc3", &rodata);
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+
+        app.patch_option(ElectronOption::JsFlags).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! Detection of code signatures embedded in macOS binaries.
+//!
+//! Signed Mach-O binaries carry a hash of every page in their `LC_CODE_SIGNATURE` load command, so changing a single
+//! fuse or patching out a single option invalidates it. macOS then refuses to launch the application until it's
+//! signed again.
+
+use crate::ElectronApp;
+#[cfg(feature = "patch")]
+use crate::PatcherError;
+use std::{convert::TryInto, ops::Range};
+
+/// The magic number of a 32-bit Mach-O file.
+const MH_MAGIC: u32 = 0xFEED_FACE;
+/// The magic number of a 64-bit Mach-O file.
+const MH_MAGIC_64: u32 = 0xFEED_FACF;
+/// The load command pointing to a binary's code signature.
+const LC_CODE_SIGNATURE: u32 = 0x1D;
+
+/// Returns `Some(true)` if a Mach-O binary has a code signature load command, or `None` if it isn't a Mach-O binary
+/// or its load commands can't be read.
+fn macho_is_signed(binary: &[u8]) -> Option<bool> {
+    let magic = u32::from_le_bytes(binary.get(0..4)?.try_into().ok()?);
+    let (big_endian, header_len) = if magic == MH_MAGIC {
+        (false, 28)
+    } else if magic == MH_MAGIC_64 {
+        (false, 32)
+    } else if magic.swap_bytes() == MH_MAGIC {
+        (true, 28)
+    } else if magic.swap_bytes() == MH_MAGIC_64 {
+        (true, 32)
+    } else {
+        return None;
+    };
+
+    let read_u32 = |pos: usize| -> Option<u32> {
+        let bytes = binary.get(pos..pos.checked_add(4)?)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    // Each load command starts with its type and its size, including those two fields.
+    let count = read_u32(16)?;
+    let mut pos = header_len;
+    for _ in 0..count {
+        if read_u32(pos)? == LC_CODE_SIGNATURE {
+            return Some(true);
+        }
+
+        let len = read_u32(pos + 4)? as usize;
+        if len < 8 {
+            return None;
+        }
+        pos = pos.checked_add(len)?;
+    }
+
+    Some(false)
+}
+
+/// Returns `true` if the binary, or any architecture slice of a universal binary, is a signed Mach-O binary.
+pub(crate) fn is_signed(binary: &[u8], slices: &[Range<usize>]) -> bool {
+    if slices.is_empty() {
+        return macho_is_signed(binary) == Some(true);
+    }
+
+    slices
+        .iter()
+        .any(|slice| macho_is_signed(&binary[slice.clone()]) == Some(true))
+}
+
+impl ElectronApp<'_> {
+    /// Returns `true` if the application is a macOS binary with an embedded code signature.
+    ///
+    /// Any modification invalidates the signature, so the application has to be signed again after it's hardened.
+    /// Until it is, macOS refuses to launch it. Universal binaries are signed if any of their architectures are.
+    pub fn has_code_signature(&self) -> bool {
+        is_signed(&self.contents, &self.slices)
+    }
+}
+
+#[cfg(feature = "patch")]
+impl ElectronApp<'_> {
+    /// Refuses to modify the application if it [has a code signature](Self::has_code_signature).
+    ///
+    /// When enabled, every method that would write to a signed binary returns
+    /// [SignatureInvalidated](PatcherError::SignatureInvalidated) instead, without writing anything. Methods that
+    /// wouldn't modify anything still succeed. By default, signed binaries are modified and
+    /// [applying a policy](Self::apply_policy) reports a
+    /// [CodeSignatureInvalidated](crate::HardeningWarning::CodeSignatureInvalidated) warning.
+    pub fn deny_signature_invalidation(&mut self, deny: bool) {
+        self.deny_signature_invalidation = deny;
+    }
+
+    /// Returns an error if writing to the application would invalidate a code signature that must be kept.
+    pub(crate) fn check_signature(&self) -> Result<(), PatcherError> {
+        if self.deny_signature_invalidation && self.has_code_signature() {
+            Err(PatcherError::SignatureInvalidated)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+
+    /// Wraps the contents in a minimal 64-bit Mach-O file, with a code signature load command if `signed` is set.
+    fn macho(contents: &[u8], signed: bool) -> Vec<u8> {
+        // A segment command without any sections, followed by the code signature.
+        let mut commands: Vec<u32> = vec![0x19, 72];
+        commands.resize(18, 0);
+        if signed {
+            commands.extend_from_slice(&[LC_CODE_SIGNATURE, 16, 0, 0]);
+        }
+        let commands_len = commands.len() as u32 * 4;
+        let count = if signed { 2 } else { 1 };
+
+        // Magic, x86_64 and its subtype, an executable, then the load commands' count and size, flags, and padding.
+        let header = [MH_MAGIC_64, 0x0100_0007, 3, 2, count, commands_len, 0, 0];
+
+        let mut file: Vec<u8> = header
+            .iter()
+            .chain(&commands)
+            .flat_map(|field| field.to_le_bytes().to_vec())
+            .collect();
+        file.extend_from_slice(contents);
+        file
+    }
+
+    #[test]
+    fn code_signatures_are_detected() {
+        assert_eq!(macho_is_signed(&macho(FUSE_BYTES, true)), Some(true));
+        assert_eq!(macho_is_signed(&macho(FUSE_BYTES, false)), Some(false));
+        assert_eq!(macho_is_signed(FUSE_BYTES), None);
+
+        // Load commands running past the end of the binary can't be read.
+        let mut truncated = macho(b"", true);
+        truncated.truncate(truncated.len() - 14);
+        assert_eq!(macho_is_signed(&truncated), None);
+
+        let signed = macho(FUSE_BYTES, true);
+        let unsigned = macho(FUSE_BYTES, false);
+        let fat = crate::fat::tests::fat_binary(&[&unsigned, &signed]);
+        assert!(ElectronApp::from_bytes_readonly(&fat)
+            .unwrap()
+            .has_code_signature());
+        let fat = crate::fat::tests::fat_binary(&[&unsigned, &unsigned]);
+        assert!(!ElectronApp::from_bytes_readonly(&fat)
+            .unwrap()
+            .has_code_signature());
+    }
+
+    #[test]
+    #[cfg(feature = "patch")]
+    fn signed_binaries_can_be_protected() {
+        use crate::{patcher::ElectronOption, Fuse};
+
+        let mut bytes = macho(FUSE_BYTES, true);
+        bytes.extend_from_slice(b"\0js-flags\0");
+        let original = bytes.clone();
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        app.deny_signature_invalidation(true);
+
+        assert_eq!(
+            app.set_fuse_status(Fuse::RunAsNode, false),
+            Err(PatcherError::SignatureInvalidated)
+        );
+        assert_eq!(
+            app.patch_option(ElectronOption::JsFlags),
+            Err(PatcherError::SignatureInvalidated)
+        );
+        assert_eq!(
+            app.apply_policy(&crate::HardeningPolicy::from_preset(crate::Preset::Minimal)),
+            Err(PatcherError::SignatureInvalidated)
+        );
+        // Changes that don't modify anything are still allowed.
+        app.set_fuse_status(Fuse::RunAsNode, true).unwrap();
+        assert!(bytes == original, "the signed binary was modified");
+
+        let mut app = ElectronApp::from_bytes(&mut bytes).unwrap();
+        let report = app
+            .apply_policy(&crate::HardeningPolicy::from_preset(crate::Preset::Minimal))
+            .unwrap();
+        assert_eq!(
+            report.warnings.last(),
+            Some(&crate::HardeningWarning::CodeSignatureInvalidated)
+        );
+    }
+}
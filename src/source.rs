@@ -0,0 +1,518 @@
+//! Hardening binaries that aren't loaded into memory, such as large files or objects in remote storage.
+//!
+//! A [BinarySource] only has to read and write ranges of bytes. [harden_source] reads the binary in fixed-size
+//! windows to find its fuse wires and patch targets, then writes only the bytes that change. This allows a source
+//! backed by ranged reads and writes, like an object store, to be hardened without transferring the whole binary.
+
+use crate::{
+    fuses::{FuseStatus, FuseWire},
+    journal::{Modification, ModifiedItem},
+    patcher::{
+        PatchOutcome, PatchState, PatchTarget, PatternId, PlannedPatch, SearchScope,
+        DEFAULT_PATCH_FILL,
+    },
+    BinaryError, ElectronApp, ElectronVersion, HardeningPolicy, HardeningReport, HardeningWarning,
+    PatcherError, Severity, SourceError,
+};
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+/// Random access to the bytes of an application binary.
+pub trait BinarySource {
+    /// Returns the length of the binary in bytes.
+    fn len(&mut self) -> io::Result<usize>;
+
+    /// Returns `true` if the binary is empty.
+    fn is_empty(&mut self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Fills `buf` with the bytes of the binary starting at `offset`.
+    ///
+    /// Reading past the end of the binary is an error.
+    fn read_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()>;
+
+    /// Replaces the bytes of the binary starting at `offset` with `bytes`.
+    ///
+    /// Writes never extend the binary, so writing past its end is an error.
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> io::Result<()>;
+}
+
+fn out_of_bounds() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "The range extends past the end of the binary",
+    )
+}
+
+impl BinarySource for [u8] {
+    fn len(&mut self) -> io::Result<usize> {
+        Ok(<[u8]>::len(self))
+    }
+
+    fn read_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+        let bytes = self
+            .get(offset..offset.saturating_add(<[u8]>::len(buf)))
+            .ok_or_else(out_of_bounds)?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> io::Result<()> {
+        self.get_mut(offset..offset.saturating_add(bytes.len()))
+            .ok_or_else(out_of_bounds)?
+            .copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl BinarySource for File {
+    fn len(&mut self) -> io::Result<usize> {
+        usize::try_from(self.metadata()?.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The file is too large to be addressed",
+            )
+        })
+    }
+
+    fn read_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+        self.seek(SeekFrom::Start(offset as u64))?;
+        self.read_exact(buf)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> io::Result<()> {
+        if offset.saturating_add(bytes.len()) > BinarySource::len(self)? {
+            return Err(out_of_bounds());
+        }
+
+        self.seek(SeekFrom::Start(offset as u64))?;
+        self.write_all(bytes)
+    }
+}
+
+/// How many bytes of the binary each window starts after the previous one.
+const WINDOW_LEN: usize = 1 << 20;
+
+/// How far each window extends into the next one.
+///
+/// This is longer than any fuse wire or search pattern, so anything starting inside a window is read in full.
+const WINDOW_OVERLAP: usize = 4096;
+
+/// The sentinel, version and length that come before the fuses in a fuse wire.
+const WIRE_HEADER_LEN: usize = 34;
+
+/// Applies a policy to an application binary that's read and written through a [BinarySource].
+///
+/// The result is the same as loading the whole binary and calling [apply_policy](ElectronApp::apply_policy), but the
+/// binary is read one window at a time and only the modified bytes are written. Every fuse wire and patch target is
+/// located before anything is written, so the source is left unmodified if an error is returned.
+///
+/// The report doesn't contain [prior states](HardeningReport::prior_states) or the findings that need the whole
+/// binary to be searched at once, which are [IneffectiveFuse](HardeningWarning::IneffectiveFuse),
+/// [RedundantPatch](HardeningWarning::RedundantPatch) and [NonAsarAppPath](HardeningWarning::NonAsarAppPath).
+/// For the same reason, targets that were already patched out don't count towards the policy's
+/// [minimum patches](HardeningPolicy::expect_min_patches).
+///
+/// # Errors
+///
+/// This function returns [Io](SourceError::Io) if the source couldn't be read or written. Otherwise, it returns the
+/// same errors as [from_bytes](ElectronApp::from_bytes) and [apply_policy](ElectronApp::apply_policy).
+pub fn harden_source<S: BinarySource + ?Sized>(
+    source: &mut S,
+    policy: &HardeningPolicy,
+) -> Result<HardeningReport, SourceError> {
+    harden_windowed(source, policy, WINDOW_LEN)
+}
+
+fn harden_windowed<S: BinarySource + ?Sized>(
+    source: &mut S,
+    policy: &HardeningPolicy,
+    window_len: usize,
+) -> Result<HardeningReport, SourceError> {
+    let len = source.len()?;
+    FuseWire::check_input_len(len).map_err(PatcherError::from)?;
+
+    let targets = policy.targets();
+
+    let mut wires = Vec::new();
+    let mut wire_error = None;
+    let mut electron_version = None;
+    let mut canonical: Vec<Option<PlannedPatch>> = vec![None; targets.len()];
+    let mut fallback: Vec<Option<PlannedPatch>> = vec![None; targets.len()];
+    let mut patched_out = vec![false; targets.len()];
+
+    let mut window = Vec::new();
+    let mut start = 0;
+
+    while start < len {
+        let end = start.saturating_add(window_len + WINDOW_OVERLAP).min(len);
+        window.resize(end - start, 0);
+        source.read_at(start, &mut window)?;
+
+        // Wires starting in the overlap are left to the next window, which contains them in full.
+        let next_start = start.saturating_add(window_len);
+        match FuseWire::locate_all(&window, start) {
+            Ok(found) => wires.extend(
+                found
+                    .into_iter()
+                    .filter(|wire| wire.range().start - WIRE_HEADER_LEN < next_start),
+            ),
+            Err(PatcherError::Binary(BinaryError::NoSentinel)) => {}
+            Err(e) => {
+                wire_error.get_or_insert(e);
+            }
+        }
+
+        if electron_version.is_none() {
+            electron_version = ElectronVersion::detect(&window);
+        }
+
+        // Like searching the whole binary, a target's canonical pattern is preferred to its fallback wherever
+        // they're found.
+        for (i, &(target, _)) in targets.iter().enumerate() {
+            if canonical[i].is_some() {
+                continue;
+            }
+
+            if let Ok(mut patch) =
+                target.plan(&window, SearchScope::default(), None, DEFAULT_PATCH_FILL)
+            {
+                patch.range = (patch.range.start + start)..(patch.range.end + start);
+                patch.found.range =
+                    (patch.found.range.start + start)..(patch.found.range.end + start);
+
+                match patch.found.matched_pattern {
+                    PatternId::Canonical => canonical[i] = Some(patch),
+                    PatternId::Fallback => {
+                        fallback[i].get_or_insert(patch);
+                    }
+                }
+            } else if !patched_out[i] {
+                patched_out[i] = matches!(
+                    target.patch_state(&window),
+                    PatchState::PatchedByThisTool | PatchState::PatchedByEvilFeaturePatcher
+                );
+            }
+        }
+
+        start = next_start;
+    }
+
+    if wires.is_empty() {
+        return Err(wire_error
+            .unwrap_or_else(|| BinaryError::NoSentinel.into())
+            .into());
+    }
+
+    let mut report = HardeningReport::new(wires[0].version());
+    report.electron_version = electron_version;
+
+    // Each wire is hardened as its own small application, and the modifications it records are written back.
+    let mut writes = Vec::new();
+    let mut wire_reports = Vec::with_capacity(wires.len());
+
+    for wire in &wires {
+        let wire_start = wire.range().start - WIRE_HEADER_LEN;
+        let mut wire_bytes = vec![0; wire.range().end - wire_start];
+        source.read_at(wire_start, &mut wire_bytes)?;
+
+        let wire_report = ElectronApp::from_bytes(&mut wire_bytes)?.apply_fuses_only(policy)?;
+
+        for modification in &wire_report.modifications {
+            let range =
+                (wire_start + modification.range.start)..(wire_start + modification.range.end);
+            writes.push((range.start, wire_bytes[modification.range.clone()].to_vec()));
+            report.modifications.push(Modification {
+                item: modification.item,
+                range,
+            });
+        }
+        wire_reports.push(wire_report);
+    }
+
+    for (i, &(fuse, _)) in policy.fuses().iter().enumerate() {
+        let statuses = wire_reports
+            .iter()
+            .map(|wire_report| wire_report.fuses[i].1);
+        let status = if statuses.clone().any(|s| s == FuseStatus::Modified) {
+            FuseStatus::Modified
+        } else {
+            wire_reports[0].fuses[i].1
+        };
+        report.fuses.push((fuse, status));
+    }
+
+    report.final_fuses = wire_reports[0].final_fuses.clone();
+
+    for wire_report in wire_reports {
+        for warning in wire_report.warnings {
+            if !report.warnings.contains(&warning) {
+                report.warnings.push(warning);
+            }
+        }
+        report.wire_fuses.push(wire_report.fuses);
+    }
+
+    let mut planned = Vec::new();
+    for (i, &(target, severity)) in targets.iter().enumerate() {
+        let outcome = match canonical[i].take().or_else(|| fallback[i].take()) {
+            Some(patch) => {
+                planned.push(patch);
+                PatchOutcome::Patched
+            }
+            None if patched_out[i] => PatchOutcome::AlreadyPatched,
+            None => missing_outcome(&mut report, target, severity),
+        };
+
+        report.targets.push((target, outcome));
+    }
+
+    PlannedPatch::check_overlaps(&planned)?;
+    policy.expectations().check_patches(planned.len())?;
+
+    for patch in planned {
+        report.modifications.push(Modification {
+            item: ModifiedItem::Target(patch.target),
+            range: patch.range.clone(),
+        });
+        writes.push((patch.range.start, patch.replacement));
+        report.matches.push((patch.target, patch.found));
+    }
+
+    for (offset, bytes) in writes {
+        source.write_at(offset, &bytes)?;
+    }
+
+    Ok(report)
+}
+
+/// Classifies a target that wasn't found in the binary, like [apply_policy](ElectronApp::apply_policy) does.
+fn missing_outcome(
+    report: &mut HardeningReport,
+    target: PatchTarget,
+    severity: Severity,
+) -> PatchOutcome {
+    match report.electron_version {
+        Some(version) if !target.is_available_in(version) => PatchOutcome::Unavailable,
+        _ => {
+            if severity != Severity::Optional {
+                report
+                    .warnings
+                    .push(HardeningWarning::TargetNotPresent { target, severity });
+            }
+            PatchOutcome::NotPresent
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Preset;
+    use std::ops::Range;
+
+    const FUSE_BYTES: &[u8] = include_bytes!("../examples/fake_electron_fuses.bin");
+    const FLAG_BYTES: &[u8] = include_bytes!("../examples/fake_electron_flags.bin");
+
+    const TEST_WINDOW_LEN: usize = 1024;
+
+    /// A source that records which ranges of it were accessed.
+    #[derive(Default)]
+    struct RecordingSource {
+        bytes: Vec<u8>,
+        reads: Vec<Range<usize>>,
+        writes: Vec<Range<usize>>,
+    }
+
+    impl BinarySource for RecordingSource {
+        fn len(&mut self) -> io::Result<usize> {
+            Ok(self.bytes.len())
+        }
+
+        fn read_at(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+            self.reads.push(offset..(offset + <[u8]>::len(buf)));
+            self.bytes[..].read_at(offset, buf)
+        }
+
+        fn write_at(&mut self, offset: usize, bytes: &[u8]) -> io::Result<()> {
+            self.writes.push(offset..(offset + bytes.len()));
+            self.bytes[..].write_at(offset, bytes)
+        }
+    }
+
+    /// An application that spans many windows, with its fuse wire and a patch target crossing window boundaries.
+    fn windowed_app_bytes() -> Vec<u8> {
+        let mut bytes = vec![0; TEST_WINDOW_LEN * 3 - 10];
+        bytes.extend_from_slice(FUSE_BYTES);
+        bytes.resize(TEST_WINDOW_LEN * 8 - 4, 0);
+        bytes.extend_from_slice(FLAG_BYTES);
+        bytes.resize(TEST_WINDOW_LEN * 16, 0);
+        bytes
+    }
+
+    #[test]
+    fn source_is_hardened_like_memory() {
+        let policy = HardeningPolicy::from_preset(Preset::Paranoid);
+        let original = windowed_app_bytes();
+
+        let mut expected = original.clone();
+        let expected_report = ElectronApp::from_bytes(&mut expected)
+            .unwrap()
+            .apply_policy(&policy)
+            .unwrap();
+
+        let mut source = RecordingSource {
+            bytes: original,
+            ..RecordingSource::default()
+        };
+        let report = harden_windowed(&mut source, &policy, TEST_WINDOW_LEN).unwrap();
+
+        assert!(
+            source.bytes == expected,
+            "the source was hardened differently"
+        );
+        assert_eq!(report.fuses, expected_report.fuses);
+        assert_eq!(report.wire_fuses, expected_report.wire_fuses);
+        assert_eq!(report.targets, expected_report.targets);
+        assert_eq!(report.matches, expected_report.matches);
+
+        // Hardening it again finds every target already patched out, the same as in memory.
+        let expected_report = ElectronApp::from_bytes(&mut expected)
+            .unwrap()
+            .apply_policy(&policy)
+            .unwrap();
+        let report = harden_windowed(&mut source, &policy, TEST_WINDOW_LEN).unwrap();
+        assert!(source.bytes == expected, "the source was modified again");
+        assert_eq!(report.targets, expected_report.targets);
+        assert!(report
+            .targets
+            .iter()
+            .all(|(_, outcome)| *outcome == PatchOutcome::AlreadyPatched));
+    }
+
+    #[test]
+    fn only_modified_bytes_are_written() {
+        let original = windowed_app_bytes();
+        let mut source = RecordingSource {
+            bytes: original.clone(),
+            ..RecordingSource::default()
+        };
+
+        harden_windowed(
+            &mut source,
+            &HardeningPolicy::from_preset(Preset::Recommended),
+            TEST_WINDOW_LEN,
+        )
+        .unwrap();
+
+        // Every window is read once, in order.
+        let windows = source
+            .reads
+            .iter()
+            .filter(|read| read.start % TEST_WINDOW_LEN == 0)
+            .map(|read| read.start);
+        assert!(windows.eq((0..16).map(|i| i * TEST_WINDOW_LEN)));
+
+        assert!(!source.writes.is_empty());
+        for write in &source.writes {
+            let first = write.start;
+            let last = write.end - 1;
+            assert_ne!(source.bytes[first], original[first]);
+            assert_ne!(source.bytes[last], original[last]);
+        }
+    }
+
+    #[test]
+    fn source_is_untouched_on_error() {
+        let mut source = RecordingSource {
+            bytes: vec![0; TEST_WINDOW_LEN * 4],
+            ..RecordingSource::default()
+        };
+        assert!(matches!(
+            harden_windowed(&mut source, &HardeningPolicy::new(), TEST_WINDOW_LEN),
+            Err(SourceError::Patcher(PatcherError::Binary(
+                BinaryError::NoSentinel
+            )))
+        ));
+        assert!(source.writes.is_empty());
+
+        let mut source = RecordingSource::default();
+        assert!(matches!(
+            harden_windowed(&mut source, &HardeningPolicy::new(), TEST_WINDOW_LEN),
+            Err(SourceError::Patcher(PatcherError::Binary(
+                BinaryError::EmptyInput
+            )))
+        ));
+        assert!(source.reads.is_empty());
+
+        let mut source = RecordingSource {
+            bytes: vec![0; 33],
+            ..RecordingSource::default()
+        };
+        assert!(matches!(
+            harden_windowed(&mut source, &HardeningPolicy::new(), TEST_WINDOW_LEN),
+            Err(SourceError::Patcher(PatcherError::Binary(
+                BinaryError::InputTooSmall { len: 33, .. }
+            )))
+        ));
+        assert!(source.reads.is_empty());
+
+        let mut source = RecordingSource {
+            bytes: windowed_app_bytes(),
+            ..RecordingSource::default()
+        };
+        let policy = HardeningPolicy::from_preset(Preset::Recommended).expect_min_patches(5);
+        assert!(matches!(
+            harden_windowed(&mut source, &policy, TEST_WINDOW_LEN),
+            Err(SourceError::Patcher(PatcherError::TooFewPatches {
+                expected: 5,
+                found: 4
+            }))
+        ));
+        assert!(source.writes.is_empty());
+    }
+
+    #[test]
+    fn slices_are_sources() {
+        let mut bytes = windowed_app_bytes();
+        let policy = HardeningPolicy::from_preset(Preset::Minimal);
+
+        let expected = crate::harden_to_vec(&bytes, &policy).unwrap();
+        harden_source(&mut bytes[..], &policy).unwrap();
+        assert!(bytes == expected);
+
+        let mut buf = [0; 4];
+        let offset = bytes.len() - 2;
+        assert_eq!(
+            bytes[..].read_at(offset, &mut buf).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn files_are_sources() {
+        let path = std::env::temp_dir().join(format!(
+            "electron-hardener-source-{}.bin",
+            std::process::id()
+        ));
+        let original = windowed_app_bytes();
+        std::fs::write(&path, &original).unwrap();
+
+        let policy = HardeningPolicy::from_preset(Preset::Recommended);
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        harden_source(&mut file, &policy).unwrap();
+        drop(file);
+
+        let hardened = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(hardened == crate::harden_to_vec(&original, &policy).unwrap());
+    }
+}
@@ -0,0 +1,106 @@
+//! Detection of the Electron version an application was built with.
+
+use crate::ElectronApp;
+use memchr::memmem;
+use std::fmt;
+
+/// A version of Electron, detected from an application binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ElectronVersion {
+    /// The major version, which is what patch target availability is tracked against.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl ElectronVersion {
+    /// Creates a new version from its parts.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Searches for the `Electron/<version>` string that Electron embeds in its user agent.
+    pub(crate) fn detect(binary: &[u8]) -> Option<Self> {
+        const PREFIX: &[u8] = b"Electron/";
+
+        memmem::find_iter(binary, PREFIX)
+            .find_map(|pos| Self::parse(&binary[(pos + PREFIX.len())..]))
+    }
+
+    /// Parses a `major.minor.patch` version from the start of the bytes.
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let mut parts = [0; 3];
+        let mut rest = bytes;
+
+        for (i, part) in parts.iter_mut().enumerate() {
+            if i != 0 {
+                if rest.first() != Some(&b'.') {
+                    return None;
+                }
+                rest = &rest[1..];
+            }
+
+            let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+            if !(1..=4).contains(&digits) {
+                return None;
+            }
+
+            *part = std::str::from_utf8(&rest[..digits]).ok()?.parse().ok()?;
+            rest = &rest[digits..];
+        }
+
+        Some(Self::new(parts[0], parts[1], parts[2]))
+    }
+}
+
+impl fmt::Display for ElectronVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl ElectronApp<'_> {
+    /// Attempts to detect which version of Electron the application was built with.
+    ///
+    /// This is a best-effort search for the version string Electron embeds in its user agent. `None` is returned
+    /// if it couldn't be found.
+    pub fn electron_version(&self) -> Option<ElectronVersion> {
+        ElectronVersion::detect(&self.contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_is_detected() {
+        let binary = b"\0Chrome/118.0.5993.159 Electron/27.1.3 Safari/537.36\0";
+        assert_eq!(
+            ElectronVersion::detect(binary),
+            Some(ElectronVersion::new(27, 1, 3))
+        );
+    }
+
+    #[test]
+    fn missing_version_is_none() {
+        let binary = include_bytes!("../examples/fake_electron_flags.bin");
+        assert_eq!(ElectronVersion::detect(binary), None);
+    }
+
+    #[test]
+    fn invalid_versions_are_skipped() {
+        let binary = b"\0Electron/\0Electron/1.2\0Electron/12345.0.0\0Electron/13.6.9\0";
+        assert_eq!(
+            ElectronVersion::detect(binary),
+            Some(ElectronVersion::new(13, 6, 9))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Runs the command line tool on copies of the test application.
+
+mod common;
+
+use common::test_app_bytes;
+use std::{fs, process::Command};
+
+const TOOL: &str = env!("CARGO_BIN_EXE_electron-hardener");
+
+#[test]
+fn hardening_twice_succeeds() {
+    let bytes = test_app_bytes();
+    let app = std::env::temp_dir().join(format!(
+        "electron-hardener-cli-twice-{}",
+        std::process::id()
+    ));
+    fs::write(&app, bytes).unwrap();
+
+    let runs: Vec<_> = (0..2)
+        .map(|_| {
+            Command::new(TOOL)
+                .arg("--strict")
+                .arg(&app)
+                .output()
+                .unwrap()
+        })
+        .collect();
+    let _ = fs::remove_file(&app);
+
+    for output in &runs {
+        assert!(output.status.success(), "{:?}", output);
+    }
+    // The second run recognizes the options patched by the first, instead of warning that they're missing.
+    let second = String::from_utf8_lossy(&runs[1].stdout);
+    assert!(!second.contains("wasn't present"), "{}", second);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn immutable_binaries_are_explained() {
+    let bytes = test_app_bytes();
+    let app = std::env::temp_dir().join(format!(
+        "electron-hardener-cli-immutable-{}",
+        std::process::id()
+    ));
+    fs::write(&app, &bytes).unwrap();
+
+    let chattr = |flag| {
+        Command::new("chattr")
+            .arg(flag)
+            .arg(&app)
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    // Setting the flag needs privileges and a filesystem that supports it, so there's nothing to check otherwise.
+    let immutable = chattr("+i");
+    // Writing an undo journal hardens the binary outside of the library's defaults.
+    let output = Command::new(TOOL)
+        .arg("--undo-journal")
+        .arg(&app)
+        .output()
+        .unwrap();
+    if immutable {
+        chattr("-i");
+    }
+    let contents = fs::read(&app).unwrap();
+    let _ = fs::remove_file(&app);
+    let _ = fs::remove_file(format!("{}.undo", app.display()));
+
+    if immutable {
+        assert!(!output.status.success());
+        let printed =
+            String::from_utf8_lossy(&output.stdout) + String::from_utf8_lossy(&output.stderr);
+        assert!(printed.contains("it's marked as immutable"), "{}", printed);
+        assert!(contents == bytes);
+    }
+}
@@ -0,0 +1,8 @@
+//! Fixtures shared by the integration tests.
+
+/// An application made of the fuse and flag fixtures, like the one the library's own tests use.
+pub fn test_app_bytes() -> Vec<u8> {
+    let mut bytes = include_bytes!("../../examples/fake_electron_fuses.bin").to_vec();
+    bytes.extend_from_slice(include_bytes!("../../examples/fake_electron_flags.bin"));
+    bytes
+}
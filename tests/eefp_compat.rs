@@ -0,0 +1,122 @@
+//! Runs the command line tool with electron-evil-feature-patcher's arguments.
+
+mod common;
+
+use common::test_app_bytes;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const TOOL: &str = env!("CARGO_BIN_EXE_electron-hardener");
+
+/// Creates an empty directory for a test, named after it.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "electron-hardener-eefp-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(tool: &Path, args: &[&str]) -> Output {
+    Command::new(tool).args(args).output().unwrap()
+}
+
+/// Hardens a copy of the test app with the native arguments, returning its contents.
+fn natively_hardened(dir: &Path) -> Vec<u8> {
+    let app = dir.join("native");
+    fs::write(&app, test_app_bytes()).unwrap();
+    assert!(run(Path::new(TOOL), &[app.to_str().unwrap()])
+        .status
+        .success());
+    fs::read(app).unwrap()
+}
+
+#[test]
+fn legacy_arguments_are_mapped() {
+    let dir = test_dir("args");
+    let expected = natively_hardened(&dir);
+
+    for args in [&["--verbose"][..], &["--path"][..], &[][..]] {
+        let app = dir.join("app");
+        fs::write(&app, test_app_bytes()).unwrap();
+
+        let mut full_args = vec!["--compat-eefp"];
+        full_args.extend_from_slice(args);
+        full_args.push(app.to_str().unwrap());
+
+        let output = run(Path::new(TOOL), &full_args);
+        assert!(output.status.success(), "{:?}", output);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains(&format!(
+                "mapped to `electron-hardener {}`",
+                app.to_str().unwrap()
+            )),
+            "{}",
+            stderr
+        );
+        assert!(fs::read(&app).unwrap() == expected, "{:?}", args);
+    }
+
+    let app = dir.join("app");
+    fs::write(&app, test_app_bytes()).unwrap();
+    let path_arg = format!("--path={}", app.to_str().unwrap());
+    assert!(run(Path::new(TOOL), &["--compat-eefp", &path_arg])
+        .status
+        .success());
+    assert!(fs::read(&app).unwrap() == expected);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn legacy_name_enables_compatibility() {
+    let dir = test_dir("name");
+    let expected = natively_hardened(&dir);
+
+    let tool = dir.join(format!(
+        "electron-evil-feature-patcher{}",
+        std::env::consts::EXE_SUFFIX
+    ));
+    fs::copy(TOOL, &tool).unwrap();
+
+    let app = dir.join("app");
+    fs::write(&app, test_app_bytes()).unwrap();
+    let output = run(&tool, &["--verbose", app.to_str().unwrap()]);
+    let hardened = fs::read(&app).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("notice: electron-evil-feature-patcher arguments mapped"));
+    assert!(hardened == expected);
+}
+
+#[test]
+fn unsupported_legacy_arguments_fail() {
+    let dir = test_dir("unsupported");
+    let app = dir.join("app");
+    fs::write(&app, test_app_bytes()).unwrap();
+
+    let unknown = run(
+        Path::new(TOOL),
+        &["--compat-eefp", "--strict", app.to_str().unwrap()],
+    );
+    let missing = run(Path::new(TOOL), &["--compat-eefp"]);
+    let untouched = fs::read(&app).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8(unknown.stderr)
+        .unwrap()
+        .contains("--strict isn't supported"));
+    assert!(!missing.status.success());
+    assert!(untouched == test_app_bytes());
+}
@@ -0,0 +1,150 @@
+//! Hardens a real Electron release, to catch regressions on layouts the fake fixtures don't cover.
+//!
+//! These tests are only built with the `integration-tests` feature. The release for the host platform is downloaded
+//! from GitHub with `curl` and extracted with `unzip`, or `tar` on Windows, then cached under `target/`.
+
+use electron_hardener::{
+    fuses::FuseStatus,
+    patcher::{PatchState, PatchTarget},
+    ElectronApp, HardeningPolicy, Preset, Severity,
+};
+use std::{
+    env::consts::{ARCH, OS},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The Electron release that's tested against.
+const ELECTRON_VERSION: &str = "22.3.27";
+
+/// The Node.JS debugging flags, which are patched out on top of the recommended preset.
+const NODE_FLAGS: &[PatchTarget] = &[
+    PatchTarget::NodeInspect,
+    PatchTarget::NodeInspectBrk,
+    PatchTarget::NodeInspectPort,
+    PatchTarget::NodeDebug,
+    PatchTarget::NodeDebugBrk,
+    PatchTarget::NodeDebugPort,
+    PatchTarget::NodeInspectBrkNode,
+    PatchTarget::NodeInspectPublishUid,
+    PatchTarget::NodeInspectWait,
+];
+
+/// Returns the name of the release archive for the host platform, without its extension.
+fn release_name() -> String {
+    let platform = match OS {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "win32",
+        os => panic!("Electron isn't released for {}", os),
+    };
+    let arch = match ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "x86" => "ia32",
+        arch => panic!("Electron isn't released for {}", arch),
+    };
+
+    format!("electron-v{}-{}-{}", ELECTRON_VERSION, platform, arch)
+}
+
+/// Returns the path of the binary containing the fuses and options in an extracted release.
+fn binary_in_release() -> &'static str {
+    match OS {
+        "macos" => {
+            "Electron.app/Contents/Frameworks/Electron Framework.framework/Versions/A/Electron Framework"
+        }
+        "windows" => "electron.exe",
+        _ => "electron",
+    }
+}
+
+fn run(command: &mut Command) {
+    let status = command
+        .status()
+        .unwrap_or_else(|e| panic!("failed to run {:?}: {}", command, e));
+    assert!(status.success(), "{:?} failed with {}", command, status);
+}
+
+/// Downloads and extracts the release for the host platform, unless it was already, and returns the path of its
+/// binary.
+fn download_release() -> PathBuf {
+    let cache = Path::new(env!("CARGO_TARGET_TMPDIR")).join("electron-releases");
+    let name = release_name();
+    let extracted = cache.join(&name);
+    let binary = extracted.join(binary_in_release());
+    if binary.exists() {
+        return binary;
+    }
+
+    fs::create_dir_all(&cache).unwrap();
+    let archive = cache.join(format!("{}.zip", name));
+    if !archive.exists() {
+        // Downloads are written to a separate file first, so interrupted ones aren't mistaken for the release.
+        let partial = cache.join(format!("{}.zip.part", name));
+        let url = format!(
+            "https://github.com/electron/electron/releases/download/v{}/{}.zip",
+            ELECTRON_VERSION, name
+        );
+        run(Command::new("curl")
+            .args([
+                "--fail",
+                "--location",
+                "--silent",
+                "--show-error",
+                "--output",
+            ])
+            .arg(&partial)
+            .arg(url));
+        fs::rename(&partial, &archive).unwrap();
+    }
+
+    let _ = fs::remove_dir_all(&extracted);
+    fs::create_dir_all(&extracted).unwrap();
+    if OS == "windows" {
+        run(Command::new("tar")
+            .arg("-xf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&extracted));
+    } else {
+        run(Command::new("unzip")
+            .arg("-q")
+            .arg(&archive)
+            .arg("-d")
+            .arg(&extracted));
+    }
+
+    binary
+}
+
+#[test]
+fn release_is_hardened() {
+    let original = fs::read(download_release()).unwrap();
+    let policy = NODE_FLAGS.iter().fold(
+        HardeningPolicy::from_preset(Preset::Recommended),
+        |policy, &flag| policy.patch_with_severity(flag, Severity::Recommended),
+    );
+
+    let hardened = electron_hardener::harden_to_vec(&original, &policy).unwrap();
+    assert_eq!(hardened.len(), original.len());
+
+    // The hardened binary is read back from scratch, like it would be by the next tool that looks at it.
+    let app = ElectronApp::from_bytes_readonly(&hardened).unwrap();
+    for &(fuse, enabled) in policy.fuses() {
+        assert_eq!(
+            app.get_fuse_status(fuse),
+            Ok(FuseStatus::Present(enabled)),
+            "{:?} wasn't changed",
+            fuse
+        );
+    }
+
+    for &(target, severity) in policy.targets() {
+        assert!(!app.is_present(target), "{} is still present", target);
+        if severity == Severity::Required {
+            assert_eq!(app.patch_state(target), PatchState::PatchedByThisTool);
+        }
+    }
+}